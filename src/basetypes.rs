@@ -1,17 +1,20 @@
-use crate::helpers::{center_in_string, round_and_format};
+use std::{collections::{HashMap, HashSet}, fmt::{self, Display}, ops::{Add, Div, Mul, Neg, Sub}, str::FromStr, sync::OnceLock};
+
+use crate::{errors::{EvalError, ParserError}, helpers::{center_in_string, round_and_format, round_and_format_prec, round_and_format_with_prec, split_solution_index}, maths, parser::{binary_op_types, depends_on_angle_mode, eval, eval_with_vars, is_comparison, parse}, PREC};
 
 #[doc(hidden)]
-const VAR_SYMBOLS: [(&str, &str); 48] = [("\\alpha", "𝛼"), ("\\Alpha", "𝛢"), ("\\beta", "𝛽"), ("\\Beta", "𝛣"), ("\\gamma", "𝛾"), ("\\Gamma", "𝚪"),
+pub(crate) const VAR_SYMBOLS: [(&str, &str); 50] = [("\\alpha", "𝛼"), ("\\Alpha", "𝛢"), ("\\beta", "𝛽"), ("\\Beta", "𝛣"), ("\\gamma", "𝛾"), ("\\Gamma", "𝚪"),
 ("\\delta", "𝛿"), ("\\Delta", "𝛥"), ("\\epsilon", "𝜺"), ("\\Epsilon", "𝛦"), ("\\zeta", "𝜁"), ("\\Zeta", "𝛧"), ("\\eta", "𝜂"), ("\\Eta", "𝛨"),
 ("\\theta", "𝜃"), ("\\Theta", "𝛩"), ("\\iota", "𝜄"), ("\\Iota", "𝛪"), ("\\kappa", "𝜅"), ("\\Kappa", "𝛫"), ("\\lambda", "𝜆"), ("\\Lambda", "𝛬"),
 ("\\mu", "𝜇"), ("\\Mu", "𝛭"), ("\\nu", "𝜈"), ("\\Nu", "𝛮"), ("\\xi", "𝜉"), ("\\Xi", "𝛯"), ("\\omicron", "𝜊"), ("\\Omicron", "𝛰"), ("pi", "𝜋"),
-("\\Pi", "𝛱"), ("\\rho", "𝜌"), ("\\Rho", "𝛲"), ("\\sigma", "𝜎"), ("\\Sigma", "𝛴"), ("\\tau", "𝜏"), ("\\Tau", "𝛵"), ("\\upsilon", "𝜐"),
-("\\Upsilon", "𝛶"), ("\\phi", "𝜑"), ("\\Phi", "𝛷"), ("\\xi", "𝜒"), ("\\Xi", "𝛸"), ("\\psi", "𝜓"), ("\\Psi", "𝛹"), ("\\omega", "𝜔"), ("\\Omega", "𝛺")];
+("\\Pi", "𝛱"), ("\\rho", "𝜌"), ("\\Rho", "𝛲"), ("\\sigma", "𝜎"), ("\\Sigma", "𝛴"), ("\\tau", "𝜏"), ("tau", "𝜏"), ("\\Tau", "𝛵"), ("\\upsilon", "𝜐"),
+("\\Upsilon", "𝛶"), ("\\phi", "𝜑"), ("phi", "𝜑"), ("\\Phi", "𝛷"), ("\\xi", "𝜒"), ("\\Xi", "𝛸"), ("\\psi", "𝜓"), ("\\Psi", "𝛹"), ("\\omega", "𝜔"), ("\\Omega", "𝛺")];
 
 /// describes a Variable that can be used in the context of an evaluation. 
 /// 
 /// Variables in this implementation can contain multiple values, in order to make the storage of
-/// results from equations easier.
+/// results from equations easier. The individual values of a variable x can be accessed by their
+/// index using x_0, x_1, ... (or x_{0}, x_{1}, ...), as long as no variable with that exact name exists.
 /// 
 /// Variable Names following the LaTeX format for greek letters (e.g \sigma) (except pi which is not
 /// \pi but just pi) will get replaced with their unicode counterparts when pretty printing.
@@ -25,6 +28,7 @@ const VAR_SYMBOLS: [(&str, &str); 48] = [("\\alpha", "𝛼"), ("\\Alpha", "𝛢"
 /// let variable = Variable::new("x", vec![Value::Scalar(3.)]);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Variable {
     pub name: String,
     pub values: Values
@@ -57,6 +61,7 @@ impl Variable {
 /// let function = Function::new("f", parsed_expr, vec!["x"]);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function {
     pub name: String,
     pub ast: AST,
@@ -74,78 +79,402 @@ impl Function {
     pub fn as_latex(&self, add_aligner: bool) -> String {
         self.ast.as_latex_at_fun(self.name.clone(), self.inputs.clone(), add_aligner)
     }
+    /// checks that the function can be evaluated in the given context before it gets added to it.
+    ///
+    /// Every free variable of the body has to be one of the inputs or a variable of the context,
+    /// otherwise [EvalError::NoVariable] is returned. As recursive functions can't be evaluated, a
+    /// body that calls the function itself, either directly or through other functions of the
+    /// context, results in [EvalError::RecursiveFunction].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let f = Function::new("f", parse("a*x^2")?, vec!["x"]);
+    ///
+    /// assert_eq!(f.validate(&Context::default()), Err(EvalError::NoVariable("a".to_string())));
+    /// ```
+    pub fn validate(&self, context: &Context) -> Result<(), EvalError> {
+        let mut free_vars = self.ast.free_variables().into_iter().collect::<Vec<String>>();
+        free_vars.sort();
+        for v in free_vars {
+            if self.inputs.contains(&v) || context.get_var(&v).is_some() {
+                continue;
+            }
+            if let Some((base, index)) = split_solution_index(&v) {
+                if context.get_var(base).is_some_and(|var| index < var.values.len()) {
+                    continue;
+                }
+            }
+            return Err(EvalError::NoVariable(v));
+        }
+
+        let mut visited = vec![];
+        let mut stack = vec![&self.ast];
+        while let Some(ast) = stack.pop() {
+            let mut calls = vec![];
+            ast.visit(&mut |a| if let AST::Function { name, .. } = a {calls.push(name.to_string())});
+            for c in calls {
+                if c == self.name {
+                    return Err(EvalError::RecursiveFunction);
+                }
+                if visited.contains(&c) {
+                    continue;
+                }
+                if let Some(f) = context.get_fun(&c) {
+                    stack.push(&f.ast);
+                }
+                visited.push(c);
+            }
+        }
+
+        return Ok(());
+    }
+    /// evaluates the function at n evenly spaced points from start to end (both included), binding
+    /// the sweep variable var to each point. The body is only parsed once, so this is considerably
+    /// faster than evaluating "f(x)" for every point, e.g. when plotting the function. Other inputs
+    /// and variables are taken from the context.
+    ///
+    /// Every point is returned as a pair of the value of the sweep variable and the result. If the
+    /// function has multiple results at a point (e.g. sqrt), there is one pair per result, all with
+    /// the same x and in the order in which [eval](crate::eval) returns them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let f = Function::new("f", parse("x^2")?, vec!["x"]);
+    /// let samples = f.sample(&Context::empty(), "x", 0., 4., 5)?;
+    ///
+    /// assert_eq!(samples[2], (2., Value::Scalar(4.)));
+    /// ```
+    pub fn sample(&self, context: &Context, var: &str, start: f64, end: f64, n: usize) -> Result<Vec<(f64, Value)>, EvalError> {
+        if !start.is_finite() || !end.is_finite() {
+            return Err(EvalError::MathError("The sampling range has to be finite!".to_string()));
+        }
+        let mut samples = vec![];
+        for i in 0..n {
+            let x = if i == n-1 {end} else {start + (end-start)*i as f64/(n-1) as f64};
+            let res = eval_with_vars(&self.ast, context, &[Variable::new(var, vec![Value::Scalar(x)])])?;
+            for v in res.to_vec() {
+                samples.push((x, v));
+            }
+        }
+        return Ok(samples);
+    }
+}
+
+/// the number of variables or functions from which on lookups by name use a HashMap index instead
+/// of a linear search.
+const INDEX_THRESHOLD: usize = 16;
+
+fn build_index<'a>(names: impl Iterator<Item = &'a String>) -> HashMap<String, usize> {
+    let mut index = HashMap::new();
+    for (i, n) in names.enumerate() {
+        index.entry(n.clone()).or_insert(i);
+    }
+    return index;
+}
+
+/// returns true if the AST is an operation that isn't already wrapped in parentheses. Used for
+/// the arguments of functions, which get printed as infix operators.
+fn is_compound(a: &AST) -> bool {
+    match a {
+        AST::Operation(o) => return !matches!(**o, Operation::SimpleOperation { op_type: SimpleOpType::Parenths, .. }),
+        _ => return false
+    }
+}
+
+/// returns the operands of an operation with three operands, whose second and third operand are
+/// stored as a vector on the right side (see [SimpleOpType::arity]).
+fn operands<'a>(left: &'a AST, right: &'a AST) -> Vec<&'a AST> {
+    let mut operands = vec![left];
+    match right {
+        AST::Vector(v) => operands.extend(v.iter()),
+        _ => operands.push(right)
+    }
+    return operands;
+}
+
+/// prints the operands of an operation with three operands using the given function.
+fn join_operands(left: &AST, right: &AST, print: fn(&AST) -> String, separator: &str) -> String {
+    return operands(left, right).into_iter().map(print).collect::<Vec<String>>().join(separator);
+}
+
+/// describes the unit of the angles taken by sin, cos, tan, cot, sec and csc and returned by arcsin,
+/// arccos, arctan and atan2.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AngleMode {
+    #[default]
+    Radians,
+    Degrees
+}
+
+impl AngleMode {
+    /// converts an angle in this unit to radians.
+    pub fn to_radians(&self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => return angle,
+            AngleMode::Degrees => return angle.to_radians()
+        }
+    }
+    /// converts an angle in radians to this unit.
+    pub fn from_radians(&self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => return angle,
+            AngleMode::Degrees => return angle.to_degrees()
+        }
+    }
 }
 
 /// combines [Variable]s and [Function]s into a convenient struct, which then gets passed to the
 /// evaluator.
 ///
+/// Additionally, a context can hold aliases for function names (e.g. asin for arcsin), which are
+/// used when an expression is parsed by [quick_eval](crate::quick_eval) or a
+/// [Session](crate::Session) (see [Context::add_alias]).
+///
+/// The angle mode of a context decides whether the trigonometric functions (sin, cos, tan, cot,
+/// sec, csc, arcsin, arccos, arctan and atan2) take and return radians (the default) or degrees
+/// (see [AngleMode]).
+///
+/// For larger contexts, lookups by name use an internal index, which is built on the first lookup
+/// and kept up to date by the methods of the context. If vars or funs get modified directly,
+/// lookups fall back to a linear search.
+///
 /// # Example
 ///
 /// ```
 /// let context = Context::default();
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Context {
     pub vars: Vec<Variable>,
-    pub funs: Vec<Function>
+    pub funs: Vec<Function>,
+    /// pairs of an alias and the name of the function it stands for.
+    pub aliases: Vec<(String, String)>,
+    /// the unit of the angles taken and returned by the trigonometric functions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub angle_mode: AngleMode,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    var_index: OnceLock<HashMap<String, usize>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    fun_index: OnceLock<HashMap<String, usize>>
 }
 
+impl PartialEq for Context {
+    fn eq(&self, other: &Self) -> bool {
+        return self.vars == other.vars && self.funs == other.funs && self.aliases == other.aliases && self.angle_mode == other.angle_mode;
+    }
+}
+
+/// the variables of [Context::default].
+pub(crate) const DEFAULT_CONSTANTS: [(&str, f64); 4] = [
+    ("pi", std::f64::consts::PI),
+    ("tau", std::f64::consts::TAU),
+    ("e", std::f64::consts::E),
+    // the golden ratio (1+sqrt(5))/2
+    ("phi", 1.618033988749895)
+];
+
 impl Context {
-    /// creates a context with the variables pi and e and no functions.
+    /// creates a context with the variables pi, tau (2pi), e and phi (the golden ratio) and no
+    /// functions.
     pub fn default() -> Self {
-        Context::from_vars(vec![
-            Variable::new("pi", vec![Value::Scalar(std::f64::consts::PI)]),
-            Variable::new("e", vec![Value::Scalar(std::f64::consts::E)])
-        ])
+        Context::from_vars(DEFAULT_CONSTANTS.iter().map(|(name, value)| Variable::new(*name, vec![Value::Scalar(*value)])).collect::<Vec<Variable>>())
     }
     /// creates a context with the given variables and functions.
     pub fn new<V: AsRef<[Variable]>, F: AsRef<[Function]>>(vars: V, funs: F) -> Context {
-        Context {vars: vars.as_ref().to_vec(), funs: funs.as_ref().to_vec()}
+        Context { vars: vars.as_ref().to_vec(), funs: funs.as_ref().to_vec(), aliases: vec![], angle_mode: AngleMode::Radians, var_index: OnceLock::new(), fun_index: OnceLock::new() }
     }
     /// creates an empty context.
     pub fn empty() -> Context {
-        Context { vars: vec![], funs: vec![] }
+        Context::new(vec![], vec![])
     }
     /// creates a new context containing only the given variables.
     pub fn from_vars<V: AsRef<[Variable]>>(vars: V) -> Context {
-        Context { vars: vars.as_ref().to_vec(), funs: vec![] }
+        Context::new(vars, vec![])
     }
     /// creates a new context containing only the given functions.
     pub fn from_funs<F: AsRef<[Function]>>(funs: F) -> Context {
-        Context { vars: vec![], funs: funs.as_ref().to_vec() }
+        Context::new(vec![], funs)
     }
     /// adds a variable to the context, replacing an already existing variable with the same name.
     pub fn add_var(&mut self, var: &Variable) {
-        self.vars = self.vars.iter()
-            .filter(|v| v.name != var.name)
-            .map(|v| v.to_owned())
-            .collect();
-
+        self.remove_var(var.name.clone());
         self.vars.push(var.to_owned());
+        if let Some(index) = self.var_index.get_mut() {
+            index.entry(var.name.clone()).or_insert(self.vars.len()-1);
+        }
     }
     /// adds a function to the context, replacing an already existing function with the same name.
     pub fn add_fun(&mut self, fun: &Function) {
-        self.funs = self.funs.iter()
-            .filter(|f| f.name != fun.name)
-            .map(|f| f.to_owned())
-            .collect();
-
+        self.remove_fun(fun.name.clone());
         self.funs.push(fun.to_owned());
+        if let Some(index) = self.fun_index.get_mut() {
+            index.entry(fun.name.clone()).or_insert(self.funs.len()-1);
+        }
+    }
+    /// adds an alias for the function with the given name (a builtin function like arcsin, an
+    /// advanced operation or a function of the context), replacing an already existing alias with
+    /// the same name. Aliases are resolved by the parser before the builtin functions, so
+    /// add_alias("sen", "sin") allows "sen(x)" to be used for "sin(x)".
+    pub fn add_alias<S: Into<String>>(&mut self, alias: S, name: S) {
+        let alias = alias.into();
+        self.aliases.retain(|a| a.0 != alias);
+        self.aliases.push((alias, name.into()));
     }
     /// removes all variables with the given variable name.
     pub fn remove_var<S: Into<String> + Clone>(&mut self, var_name: S) {
-        self.vars = self.vars.iter()
-            .filter(|v| v.name != var_name.clone().into())
-            .map(|v| v.to_owned())
-            .collect();
+        let name = var_name.into();
+        let len = self.vars.len();
+        self.vars.retain(|v| v.name != name);
+        if self.vars.len() == len {
+            return;
+        }
+        let new_len = self.vars.len();
+        match self.var_index.get_mut() {
+            // removing the last variable (the usual case for temporarily added variables) doesn't
+            // move any of the other variables
+            Some(index) if len-new_len == 1 && index.get(&name) == Some(&new_len) => {index.remove(&name);},
+            _ => self.var_index = OnceLock::new()
+        }
     }
     /// removes all functions with the given variable name.
     pub fn remove_fun<S: Into<String> + Clone>(&mut self, fun_name: S) {
-        self.funs = self.funs.iter()
-            .filter(|f| f.name != fun_name.clone().into())
-            .map(|f| f.to_owned())
-            .collect()
+        let name = fun_name.into();
+        let len = self.funs.len();
+        self.funs.retain(|f| f.name != name);
+        if self.funs.len() == len {
+            return;
+        }
+        let new_len = self.funs.len();
+        match self.fun_index.get_mut() {
+            Some(index) if len-new_len == 1 && index.get(&name) == Some(&new_len) => {index.remove(&name);},
+            _ => self.fun_index = OnceLock::new()
+        }
     }
+    /// returns the variable with the given name or None if the context doesn't contain it.
+    pub fn get_var(&self, name: &str) -> Option<&Variable> {
+        if self.vars.len() >= INDEX_THRESHOLD {
+            let index = self.var_index.get_or_init(|| build_index(self.vars.iter().map(|v| &v.name)));
+            if let Some(v) = index.get(name).and_then(|i| self.vars.get(*i)) {
+                if v.name == name {
+                    return Some(v);
+                }
+            }
+        }
+        return self.vars.iter().find(|v| v.name == name);
+    }
+    /// returns the function with the given name or None if the context doesn't contain it.
+    pub fn get_fun(&self, name: &str) -> Option<&Function> {
+        if self.funs.len() >= INDEX_THRESHOLD {
+            let index = self.fun_index.get_or_init(|| build_index(self.funs.iter().map(|f| &f.name)));
+            if let Some(f) = index.get(name).and_then(|i| self.funs.get(*i)) {
+                if f.name == name {
+                    return Some(f);
+                }
+            }
+        }
+        return self.funs.iter().find(|f| f.name == name);
+    }
+    /// checks if the context contains a variable with the given name.
+    pub fn contains_var(&self, name: &str) -> bool {
+        return self.get_var(name).is_some();
+    }
+    /// checks if the context contains a function with the given name.
+    pub fn contains_fun(&self, name: &str) -> bool {
+        return self.get_fun(name).is_some();
+    }
+    /// returns the names that are used by more than one variable or by more than one function of
+    /// the context. Only the first of them is ever used, so duplicates are most likely a mistake.
+    pub fn duplicate_names(&self) -> Vec<String> {
+        let mut duplicates = vec![];
+        let var_names: Vec<&str> = self.vars.iter().map(|v| v.name.as_str()).collect();
+        let fun_names: Vec<&str> = self.funs.iter().map(|f| f.name.as_str()).collect();
+        for names in [var_names, fun_names] {
+            let mut seen = HashSet::new();
+            let mut reported = HashSet::new();
+            for name in names {
+                if !seen.insert(name) && reported.insert(name) {
+                    duplicates.push(name.to_string());
+                }
+            }
+        }
+        return duplicates;
+    }
+    /// converts the context to latex, listing every variable and every function on its own line of
+    /// an aligned block (aligned at the "=").
+    pub fn as_latex(&self) -> String {
+        let mut lines: Vec<String> = self.vars.iter().map(|v| v.as_latex(true)).collect();
+        lines.append(&mut self.funs.iter().map(|f| f.as_latex(true)).collect());
+
+        format!("\\begin{{aligned}}{}\\end{{aligned}}", lines.join("\\\\ \n"))
+    }
+}
+
+/// specifies how numbers are formatted when displaying a [Value].
+///
+/// Numbers that are too small to be displayed with the printing precision are always shown in
+/// scientific notation when using [NumberFormat::Default]. [NumberFormat::SiPrefix] instead scales
+/// numbers by a power of 1000 and appends the corresponding SI prefix (e.g. `1500` -> `1.5k`,
+/// `0.0012` -> `1.2m`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NumberFormat {
+    #[default]
+    Default,
+    SiPrefix
+}
+
+/// specifies the delimiters used for vectors and matrices when converting a [Value] to latex
+/// (see [Value::as_latex_with_style]):
+///
+/// - Paren: (...) using amsmath's pmatrix
+/// - Bracket: [...] using amsmath's bmatrix
+/// - Brace: {...} using amsmath's Bmatrix
+/// - Bars: |...| using amsmath's vmatrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatrixStyle {
+    Paren,
+    Bracket,
+    Brace,
+    Bars
+}
+
+impl MatrixStyle {
+    fn environment(&self) -> &str {
+        match self {
+            MatrixStyle::Paren => return "pmatrix",
+            MatrixStyle::Bracket => return "bmatrix",
+            MatrixStyle::Brace => return "Bmatrix",
+            MatrixStyle::Bars => return "vmatrix"
+        }
+    }
+}
+
+fn mathml_number(x: f64) -> String {
+    let number = round_and_format(x, false);
+    match number.strip_prefix('-') {
+        Some(n) => return format!("<mrow><mo>-</mo><mn>{}</mn></mrow>", n),
+        None => return format!("<mn>{}</mn>", number)
+    }
+}
+
+fn mathml_identifier(name: &str) -> String {
+    if name == "inf" {
+        return "<mi>&#x221E;</mi>".to_string();
+    }
+    for i in VAR_SYMBOLS {
+        if name == i.0 {
+            return format!("<mi>{}</mi>", i.1);
+        }
+    }
+    return format!("<mi>{}</mi>", name.replace('\\', ""));
+}
+
+fn mathml_table(rows: Vec<Vec<String>>, open: &str, close: &str) -> String {
+    let rows: Vec<String> = rows.iter().map(|r| format!("<mtr>{}</mtr>", r.iter().map(|c| format!("<mtd>{}</mtd>", c)).collect::<String>())).collect();
+    return format!("<mrow><mo>{}</mo><mtable>{}</mtable><mo>{}</mo></mrow>", open, rows.join(""), close);
 }
 
 /// specifies a Value that can be a Matrix, Vector or a Scalar.
@@ -156,10 +485,13 @@ impl Context {
 /// let x: Value = Value::Scalar(3.5);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Matrix(Vec<Vec<f64>>),
     Vector(Vec<f64>),
-    Scalar(f64)
+    Scalar(f64),
+    #[cfg(feature = "uncertainty")]
+    Uncertain(f64, f64)
 }
 
 impl Value {
@@ -169,7 +501,9 @@ impl Value {
         match self {
             Value::Scalar(a) => return Some(*a),
             Value::Matrix(_) => return None,
-            Value::Vector(_) => return None
+            Value::Vector(_) => return None,
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(..) => return None
         }
     }
     /// returns the vector if the value is a vector and None if it is a matrix or a
@@ -178,7 +512,9 @@ impl Value {
         match self {
             Value::Vector(a) => return Some(a.to_vec()),
             Value::Matrix(_) => return None,
-            Value::Scalar(_) => return None
+            Value::Scalar(_) => return None,
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(..) => return None
         }
     }
     /// returns the matrix if the value is a matrix and None if it is a scalar or a
@@ -187,7 +523,9 @@ impl Value {
         match self {
             Value::Matrix(a) => return Some(a.to_vec()),
             Value::Scalar(_) => return None,
-            Value::Vector(_) => return None
+            Value::Vector(_) => return None,
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(..) => return None
         }
     }
     /// return true if the value is a scalar.
@@ -232,7 +570,9 @@ impl Value {
                     new_matrix.push(row);
                 }
                 return Value::Matrix(new_matrix);
-            }
+            },
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, s) => return Value::Uncertain((a*10f64.powi(prec as i32)).round()/10f64.powi(prec as i32), (s*10f64.powi(prec as i32)).round()/10f64.powi(prec as i32))
         }
     }
     /// checks if any part of the value is infinite or NaN.
@@ -254,7 +594,9 @@ impl Value {
                         }
                     }
                 }
-            }
+            },
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, s) => {if !a.is_finite() || !s.is_finite() {return true}}
         }
         return false;
     }
@@ -292,6 +634,10 @@ impl Value {
             },
             Value::Scalar(s) => {
                 replace_string = s.to_string();
+            },
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, s) => {
+                replace_string = format!("({}+-{})", a, s);
             }
         }
 
@@ -300,7 +646,7 @@ impl Value {
     #[deprecated(since="0.4.0", note="Because of the complexity of Value, Values and ASTs this function can still be used to convert a single Value but will not be implemented for ASTs or Values in the forseeable future.")]
     /// converts the given value to unicode, using unicode symbols for vectors and matrices.
     pub fn as_unicode(&self) -> String {
-        self.pretty_print(None)
+        self.pretty_print(None, NumberFormat::Default, PREC-2)
     }
     #[deprecated(since="0.4.0", note="Because of the complexity of Value, Values and ASTs this function can still be used to convert a single Value but will not be implemented for ASTs or Values in the forseeable future.")]
     /// converts the given value to unicode, same as [as_unicode](Value::as_unicode) but with a variable name in
@@ -313,22 +659,41 @@ impl Value {
                 break;
             }
         }
-        self.pretty_print(Some(var_name_string))
+        self.pretty_print(Some(var_name_string), NumberFormat::Default, PREC-2)
+    }
+    /// converts the given value to a string in the same layout as [Display], but formats the numbers
+    /// using the given [NumberFormat] (e.g. `1500` as `1.5k` with [NumberFormat::SiPrefix]).
+    pub fn display_with(&self, format: NumberFormat) -> String {
+        self.pretty_print(None, format, PREC-2)
+    }
+    /// converts the given value to a string like [Value::display_with], but rounds the numbers to the
+    /// given number of decimal places instead of PREC-2 (see [PREC](crate::PREC)).
+    pub fn display_with_precision(&self, format: NumberFormat, digits: usize) -> String {
+        self.pretty_print(None, format, digits)
     }
-    fn pretty_print(&self, var_name: Option<String>) -> String {
+    fn pretty_print(&self, var_name: Option<String>, format: NumberFormat, digits: usize) -> String {
         match self {
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, u) => {
+                let mut output_buffer = String::new();
+                if var_name.is_some() {
+                    output_buffer += &format!("{} = ", var_name.unwrap())
+                }
+                output_buffer += &format!("{} ± {}", round_and_format_with_prec(*a, false, format, digits), round_and_format_with_prec(*u, false, format, digits));
+                return output_buffer;
+            },
             Value::Scalar(s) => {
                 let mut output_buffer = String::new();
                 if var_name.is_some() {
                     output_buffer += &format!("{} = ", var_name.unwrap())
                 }
-                output_buffer += &round_and_format(*s, false);
+                output_buffer += &round_and_format_with_prec(*s, false, format, digits);
                 return output_buffer;
             },
             Value::Vector(v) => {
                 let mut rounded_v: Vec<String> = vec![];
                 for i in 0..v.len() {
-                    rounded_v.push(round_and_format(v[i], false));
+                    rounded_v.push(round_and_format_with_prec(v[i], false, format, digits));
                 }
                 let max_width = rounded_v.iter().map(|x| x.len()).max().unwrap();
                 let v_middle = ((rounded_v.len() as f64/2.).ceil()-1.) as i32;
@@ -378,7 +743,7 @@ impl Value {
                 for i in 0..m.len() {
                     let mut row = vec![];
                     for j in 0..m[0].len() {
-                        row.push(round_and_format(m[i][j], false));
+                        row.push(round_and_format_with_prec(m[i][j], false, format, digits));
                     }
                     rounded_m.push(row);
                 }
@@ -435,7 +800,30 @@ impl Value {
     }
     /// converts the value to a latex expression using amsmath's p and bmatrix.
     pub fn as_latex(&self) -> String {
-        self.latex_print()
+        self.latex_print(PREC-2, MatrixStyle::Paren, MatrixStyle::Bracket)
+    }
+    /// converts the value to a latex expression like [Value::as_latex], but rounds to the given
+    /// number of decimal places instead of PREC-2.
+    pub fn as_latex_with_precision(&self, digits: usize) -> String {
+        self.latex_print(digits, MatrixStyle::Paren, MatrixStyle::Bracket)
+    }
+    /// converts the value to a latex expression like [Value::as_latex], but uses the delimiters
+    /// given by style for both vectors and matrices.
+    pub fn as_latex_with_style(&self, style: MatrixStyle) -> String {
+        self.latex_print(PREC-2, style, style)
+    }
+    /// converts the value to presentation MathML, using tables for vectors and matrices.
+    pub fn as_mathml(&self) -> String {
+        format!("<math>{}</math>", self.mathml_print())
+    }
+    pub(crate) fn mathml_print(&self) -> String {
+        match self {
+            Value::Scalar(s) => return mathml_number(*s),
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, u) => return format!("<mrow>{}<mo>&#xB1;</mo>{}</mrow>", mathml_number(*a), mathml_number(*u)),
+            Value::Vector(v) => return mathml_table(v.iter().map(|x| vec![mathml_number(*x)]).collect(), "(", ")"),
+            Value::Matrix(m) => return mathml_table(m.iter().map(|r| r.iter().map(|x| mathml_number(*x)).collect()).collect(), "[", "]")
+        }
     }
     /// converts the value to a latex expression, adding a variable name in front of it. The
     /// function also provides the option to add a "&" aligner before the "=".
@@ -449,45 +837,230 @@ impl Value {
 
         let mut var = var_name.into();
 
-        if var == "pi" {
-            var = "\\pi".to_string();
+        if var == "pi" || var == "tau" || var == "phi" {
+            var = format!("\\{}", var);
         }
 
-        return format!("{} {}= {}", aligner, var, self.as_latex());
+        return format!("{} {}= {}", var, aligner, self.as_latex());
     }
-    fn latex_print(&self) -> String {
+    fn latex_print(&self, digits: usize, vector_style: MatrixStyle, matrix_style: MatrixStyle) -> String {
         match self {
-            Value::Scalar(s) => return round_and_format(*s, true),
+            Value::Scalar(s) => return round_and_format_prec(*s, true, digits),
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, u) => return format!("{}\\pm {}", round_and_format_prec(*a, true, digits), round_and_format_prec(*u, true, digits)),
             Value::Vector(v) => {
-                let mut output_string = "\\begin{pmatrix}".to_string();
+                let mut output_string = format!("\\begin{{{}}}", vector_style.environment());
                 for i in 0..v.len() {
                     if i != v.len()-1 {
-                        output_string += &format!("{}\\\\ ", round_and_format(v[i], true));
+                        output_string += &format!("{}\\\\ ", round_and_format_prec(v[i], true, digits));
                     } else {
-                        output_string += &round_and_format(v[i], true);
+                        output_string += &round_and_format_prec(v[i], true, digits);
                     }
                 }
-                output_string += "\\end{pmatrix}";
+                output_string += &format!("\\end{{{}}}", vector_style.environment());
                 return output_string
             },
             Value::Matrix(m) => {
-                let mut output_string = "\\begin{bmatrix}".to_string();
+                let mut output_string = format!("\\begin{{{}}}", matrix_style.environment());
                 for i in 0..m.len(){
                     let mut row_string = "".to_string();
                     for j in 0..m[i].len() {
                         if j != m[i].len()-1 {
-                            row_string += &format!("{} & ", round_and_format(m[i][j], true));
+                            row_string += &format!("{} & ", round_and_format_prec(m[i][j], true, digits));
                         } else {
-                            row_string += &format!("{} \\\\", round_and_format(m[i][j], true));
+                            row_string += &format!("{} \\\\", round_and_format_prec(m[i][j], true, digits));
                         }
                     }
                     output_string += &row_string;
                 }
-                output_string += "\\end{bmatrix}";
+                output_string += &format!("\\end{{{}}}", matrix_style.environment());
                 return output_string;
             }
         }
     }
+    /// adds two values, returning an error if their dimensions don't match. This is the
+    /// non-panicking counterpart of the "+" operator.
+    pub fn try_add(&self, rhs: &Value) -> Result<Value, String> {
+        return maths::add(self, rhs);
+    }
+    /// subtracts two values, returning an error if their dimensions don't match. This is the
+    /// non-panicking counterpart of the "-" operator.
+    pub fn try_sub(&self, rhs: &Value) -> Result<Value, String> {
+        return maths::sub(self, rhs);
+    }
+    /// multiplies two values (see [SimpleOpType::Mult]), returning an error if their dimensions
+    /// don't match. This is the non-panicking counterpart of the "*" operator.
+    pub fn try_mul(&self, rhs: &Value) -> Result<Value, String> {
+        return maths::mult(self, rhs);
+    }
+    /// divides two values (see [SimpleOpType::Div]), returning an error if their dimensions don't
+    /// match. This is the non-panicking counterpart of the "/" operator.
+    pub fn try_div(&self, rhs: &Value) -> Result<Value, String> {
+        return maths::div(self, rhs);
+    }
+    /// negates the value. This is the non-panicking counterpart of the unary "-" operator.
+    pub fn try_neg(&self) -> Result<Value, String> {
+        return maths::neg(self);
+    }
+}
+
+// The operators panic on values with incompatible dimensions, just like indexing out of bounds
+// does. Use the try_ variants to handle these cases.
+
+impl Add for &Value {
+    type Output = Value;
+    /// adds two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be added (see [Value::try_add]).
+    fn add(self, rhs: &Value) -> Value {
+        self.try_add(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Add for Value {
+    type Output = Value;
+    /// adds two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be added (see [Value::try_add]).
+    fn add(self, rhs: Value) -> Value {
+        &self + &rhs
+    }
+}
+
+impl Sub for &Value {
+    type Output = Value;
+    /// subtracts two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be subtracted (see [Value::try_sub]).
+    fn sub(self, rhs: &Value) -> Value {
+        self.try_sub(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Sub for Value {
+    type Output = Value;
+    /// subtracts two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be subtracted (see [Value::try_sub]).
+    fn sub(self, rhs: Value) -> Value {
+        &self - &rhs
+    }
+}
+
+impl Mul for &Value {
+    type Output = Value;
+    /// multiplies two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be multiplied (see [Value::try_mul]).
+    fn mul(self, rhs: &Value) -> Value {
+        self.try_mul(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Mul for Value {
+    type Output = Value;
+    /// multiplies two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be multiplied (see [Value::try_mul]).
+    fn mul(self, rhs: Value) -> Value {
+        &self * &rhs
+    }
+}
+
+impl Div for &Value {
+    type Output = Value;
+    /// divides two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be divided (see [Value::try_div]).
+    fn div(self, rhs: &Value) -> Value {
+        self.try_div(rhs).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Div for Value {
+    type Output = Value;
+    /// divides two values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values can't be divided (see [Value::try_div]).
+    fn div(self, rhs: Value) -> Value {
+        &self / &rhs
+    }
+}
+
+impl Neg for &Value {
+    type Output = Value;
+    /// negates the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be negated (see [Value::try_neg]).
+    fn neg(self) -> Value {
+        self.try_neg().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Neg for Value {
+    type Output = Value;
+    /// negates the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be negated (see [Value::try_neg]).
+    fn neg(self) -> Value {
+        -&self
+    }
+}
+
+impl Display for Value {
+    /// formats the value using unicode symbols for vectors and matrices, spanning multiple lines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pretty_print(None, NumberFormat::Default, PREC-2))
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParserError;
+    /// parses a literal scalar, vector or matrix (e.g. "3.5", "-2", "[3, 4, 5]" or "[[1, 2], [3, 4]]").
+    /// Anything containing operations (other than a leading minus) or variables returns
+    /// [ParserError::NotALiteral].
+    fn from_str(s: &str) -> Result<Value, ParserError> {
+        fn is_literal_scalar(b: &AST) -> bool {
+            match b {
+                AST::Scalar(_) => return true,
+                AST::Operation(o) => return matches!(&**o, Operation::SimpleOperation { op_type: SimpleOpType::Neg, left: AST::Scalar(_), .. }),
+                _ => return false
+            }
+        }
+        let ast = parse(s)?;
+        let is_literal = match &ast {
+            AST::Vector(v) => v.iter().all(is_literal_scalar),
+            AST::Matrix(m) => m.iter().flatten().all(is_literal_scalar),
+            b => is_literal_scalar(b)
+        };
+        if !is_literal {
+            return Err(ParserError::NotALiteral(s.to_string()));
+        }
+        match eval(&ast, &Context::empty()).map(|v| v.to_vec()) {
+            Ok(v) if v.len() == 1 => return Ok(v[0].clone()),
+            _ => return Err(ParserError::NotALiteral(s.to_string()))
+        }
+    }
 }
 
 /// provides a wrapper around Vec of Value with some quality of life implementations.
@@ -498,6 +1071,7 @@ impl Value {
 /// let values = Values::from_vec(vec![Value::Scalar(3.)]);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Values(Vec<Value>);
 
 impl Values {
@@ -527,20 +1101,74 @@ impl Values {
     pub fn as_string(&self) -> String {
         format!("{{{}}}", self.clone().to_vec().iter().map(|v| v.as_string()).collect::<Vec<String>>().join(", "))
     }
+    /// converts the values to an aligned text table with one row per value and one column per
+    /// component. Matrices are flattened row by row. If headers are given, they are printed as the
+    /// first row, separated from the values by a line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let values = Values::from_vec(vec![Value::Vector(vec![1., 2.]), Value::Vector(vec![3., 4.])]);
+    /// let table = values.as_table(Some(vec!["x".to_string(), "y".to_string()]));
+    /// ```
+    pub fn as_table(&self, headers: Option<Vec<String>>) -> String {
+        let rows: Vec<Vec<String>> = self.0.iter().map(|v| {
+            match v {
+                Value::Scalar(s) => vec![round_and_format(*s, false)],
+                Value::Vector(v) => v.iter().map(|x| round_and_format(*x, false)).collect(),
+                Value::Matrix(m) => m.iter().flatten().map(|x| round_and_format(*x, false)).collect(),
+                #[cfg(feature = "uncertainty")]
+                Value::Uncertain(a, u) => vec![format!("{} ± {}", round_and_format(*a, false), round_and_format(*u, false))]
+            }
+        }).collect();
+
+        let columns = rows.iter().map(|r| r.len()).chain(headers.iter().map(|h| h.len())).max().unwrap_or(0);
+        let mut widths = vec![0; columns];
+        for r in rows.iter().chain(headers.iter()) {
+            for (i, c) in r.iter().enumerate() {
+                widths[i] = widths[i].max(c.chars().count());
+            }
+        }
+
+        let format_row = |r: &Vec<String>| -> String {
+            (0..columns).map(|i| format!("{:>w$}", r.get(i).map(|c| c.as_str()).unwrap_or(""), w = widths[i])).collect::<Vec<String>>().join(" | ")
+        };
+
+        let mut lines = vec![];
+        if let Some(h) = &headers {
+            lines.push(format_row(h));
+            lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("-+-"));
+        }
+        for r in &rows {
+            lines.push(format_row(r));
+        }
+
+        return lines.join("\n");
+    }
     /// converts the values to latex using "{}" and ";" to print multiple Values.
     pub fn as_latex(&self) -> String {
+        return self.as_latex_with_precision(PREC-2);
+    }
+    /// converts the values to latex like [Values::as_latex], but rounds to the given number of
+    /// decimal places instead of PREC-2.
+    pub fn as_latex_with_precision(&self, digits: usize) -> String {
         if self.len() == 1 {
-            return format!("{}", self.0[0].as_latex());
+            return format!("{}", self.0[0].as_latex_with_precision(digits));
         } else if self.len() <= 0 {
             return "No solutions".to_string();
         } else {
-            return format!("\\left\\{{{}\\right\\}}", self.clone().to_vec().iter().map(|v| v.as_latex()).collect::<Vec<String>>().join("; "));
+            return format!("\\left\\{{{}\\right\\}}", self.clone().to_vec().iter().map(|v| v.as_latex_with_precision(digits)).collect::<Vec<String>>().join("; "));
         }
     }
     /// converts the values to latex using "{}" and ";" to print multiple Values. This functions
     /// additionally adds a variable name in front of the Values. The function also provides the option to
     /// add a "&" aligner before the "=".
     pub fn as_latex_at_var<S: Into<String>>(&self, var_name: S, add_aligner: bool) -> String {
+        return self.as_latex_at_var_with_precision(var_name, add_aligner, PREC-2);
+    }
+    /// converts the values to latex with a variable name in front of them like
+    /// [Values::as_latex_at_var], but rounds to the given number of decimal places instead of PREC-2.
+    pub fn as_latex_at_var_with_precision<S: Into<String>>(&self, var_name: S, add_aligner: bool, digits: usize) -> String {
         let aligner;
         if add_aligner {
             aligner = "&";
@@ -550,20 +1178,34 @@ impl Values {
 
         let mut var = var_name.into();
 
-        if var == "pi" {
-            var = "\\pi".to_string();
+        if var == "pi" || var == "tau" || var == "phi" {
+            var = format!("\\{}", var);
         }
 
         if self.len() <= 0 {
             return format!("{}: No solutions", var);
-        } else if self.len() == 1 {
-            return format!("{} {}= {}", aligner, var, self.0[0].as_latex());
         } else {
-            return format!("{} {}= \\left\\{{{}\\right\\}}", var, aligner, self.clone().to_vec().iter().map(|v| v.as_latex()).collect::<Vec<String>>().join("; "));
+            return format!("{} {}= {}", var, aligner, self.as_latex_with_precision(digits));
+        }
+    }
+    pub(crate) fn mathml_print(&self) -> String {
+        if self.len() == 1 {
+            return self.0[0].mathml_print();
+        } else if self.len() <= 0 {
+            return "<mtext>No solutions</mtext>".to_string();
+        } else {
+            return format!("<mrow><mo>{{</mo>{}<mo>}}</mo></mrow>", self.0.iter().map(|v| v.mathml_print()).collect::<Vec<String>>().join("<mo>;</mo>"));
         }
     }
 }
 
+impl Display for Values {
+    /// formats the values using "{}" and "," to print multiple Values (see [Values::as_string]).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
+}
+
 /// used to construct an AST which is recursively evaluated by the [eval](crate::parser::eval) function.
 /// 
 /// Each node of the AST can be a:
@@ -576,6 +1218,7 @@ impl Values {
 /// - Function
 /// - Operation
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AST {
     Scalar(f64),
     Vector(Box<Vec<AST>>),
@@ -594,6 +1237,12 @@ impl AST {
     pub fn from_value(val: Value) -> AST {
         match val {
             Value::Scalar(s) => return AST::Scalar(s),
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(a, u) => return AST::from_operation(Operation::SimpleOperation {
+                op_type: SimpleOpType::Uncertainty,
+                left: AST::Scalar(a),
+                right: AST::Scalar(u)
+            }),
             Value::Vector(v) => {
                 let mut parsed_values = vec![];
                 for i in v {
@@ -622,10 +1271,684 @@ impl AST {
     pub fn from_operation(val: Operation) -> AST {
         return AST::Operation(Box::new(val));
     }
+    /// predicts the shape (rows, columns) of the result of the AST without evaluating it, using the
+    /// shapes of the variables in the context. Scalars have the shape (1, 1) and vectors of length n
+    /// the shape (n, 1).
+    ///
+    /// Returns None if the shape depends on the values, e.g. for function calls, advanced
+    /// operations or variables with differently shaped values. Returns an error if the shapes of
+    /// the operands of an operation are incompatible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let a = Variable::new("A", vec![Value::Matrix(vec![vec![0.; 3]; 2])]);
+    /// let b = Variable::new("B", vec![Value::Matrix(vec![vec![0.; 4]; 3])]);
+    /// let shape = parse("A*B")?.result_shape(&Context::from_vars(vec![a, b]))?;
+    ///
+    /// assert_eq!(shape, Some((2, 4)));
+    /// ```
+    pub fn result_shape(&self, context: &Context) -> Result<Option<(usize, usize)>, EvalError> {
+        match self.shape(context)? {
+            Some(Shape::Scalar) => return Ok(Some((1, 1))),
+            Some(Shape::Vector(n)) => return Ok(Some((n, 1))),
+            Some(Shape::Matrix(m, n)) => return Ok(Some((m, n))),
+            None => return Ok(None)
+        }
+    }
+    fn shape(&self, context: &Context) -> Result<Option<Shape>, EvalError> {
+        let incompatible = || EvalError::MathError("Operands have incompatible dimensions!".to_string());
+        let common_shape = |shapes: Vec<Option<Shape>>| -> Option<Shape> {
+            match shapes.first() {
+                Some(Some(first)) if shapes.iter().all(|s| s.as_ref() == Some(first)) => return Some(first.clone()),
+                _ => return None
+            }
+        };
+        match self {
+            AST::Scalar(_) => return Ok(Some(Shape::Scalar)),
+            AST::Vector(v) => return Ok(Some(Shape::Vector(v.len()))),
+            AST::Matrix(m) => return Ok(Some(Shape::Matrix(m.len(), m.first().map(|r| r.len()).unwrap_or(0)))),
+            AST::List(l) => return Ok(common_shape(l.iter().map(|a| a.shape(context)).collect::<Result<Vec<Option<Shape>>, EvalError>>()?)),
+            AST::Variable(v) => {
+                match context.get_var(v) {
+                    Some(var) => return Ok(common_shape(var.values.0.iter().map(|v| Some(Shape::of(v))).collect())),
+                    None => {
+                        if let Some((base, index)) = split_solution_index(v) {
+                            if let Some(value) = context.get_var(base).and_then(|var| var.values.get(index)) {
+                                return Ok(Some(Shape::of(value)));
+                            }
+                        }
+                        return Err(EvalError::NoVariable(v.to_string()));
+                    }
+                }
+            },
+            AST::Function { .. } => return Ok(None),
+            AST::Operation(o) => {
+                let (op_type, left, right) = match &**o {
+                    Operation::SimpleOperation { op_type, left, right } => (op_type, left, right),
+                    Operation::AdvancedOperation(_) => return Ok(None)
+                };
+                let ls = match left.shape(context)? {
+                    Some(s) => s,
+                    None => return Ok(None)
+                };
+                match op_type {
+                    SimpleOpType::Neg | SimpleOpType::Parenths | SimpleOpType::Heaviside | SimpleOpType::Sign => return Ok(Some(ls)),
+                    SimpleOpType::Abs => return Ok(Some(Shape::Scalar)),
+                    SimpleOpType::Not | SimpleOpType::Factorial | SimpleOpType::Sin | SimpleOpType::Cos | SimpleOpType::Tan
+                    | SimpleOpType::Sqrt | SimpleOpType::Ln | SimpleOpType::Log1p | SimpleOpType::Expm1 | SimpleOpType::Arcsin
+                    | SimpleOpType::Arccos | SimpleOpType::Arctan | SimpleOpType::Cot | SimpleOpType::Sec | SimpleOpType::Csc
+                    | SimpleOpType::Arsinh | SimpleOpType::Arcosh | SimpleOpType::Artanh => {
+                        match ls {
+                            Shape::Scalar => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::SpectralRadius | SimpleOpType::Det => {
+                        match ls {
+                            Shape::Matrix(m, n) if m == n => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Norm2 => {
+                        match ls {
+                            Shape::Matrix(..) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Identity => {
+                        match ls {
+                            Shape::Scalar => return Ok(None),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Diag => {
+                        match ls {
+                            Shape::Vector(n) => return Ok(Some(Shape::Matrix(n, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::SteadyState => {
+                        match ls {
+                            Shape::Matrix(m, n) if m == n => return Ok(Some(Shape::Vector(n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Rank => {
+                        match ls {
+                            Shape::Matrix(..) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Rref => {
+                        match ls {
+                            Shape::Matrix(m, n) => return Ok(Some(Shape::Matrix(m, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Mean | SimpleOpType::VSum | SimpleOpType::VProd | SimpleOpType::VMin | SimpleOpType::VMax => {
+                        match ls {
+                            Shape::Vector(_) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Clamp => {
+                        match ls {
+                            Shape::Scalar => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    // the shape of the result depends on the values of the other two operands
+                    SimpleOpType::Linspace => {
+                        match ls {
+                            Shape::Scalar => return Ok(None),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Slice | SimpleOpType::Reshape => {
+                        match ls {
+                            Shape::Vector(_) => return Ok(None),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    _ => {}
+                }
+                let rs = match right.shape(context)? {
+                    Some(s) => s,
+                    None => return Ok(None)
+                };
+                match op_type {
+                    SimpleOpType::Add | SimpleOpType::Sub | SimpleOpType::AddSub => {
+                        if ls != rs {
+                            return Err(incompatible());
+                        }
+                        return Ok(Some(ls));
+                    },
+                    SimpleOpType::Mult | SimpleOpType::HiddenMult => {
+                        match (ls, rs) {
+                            (Shape::Scalar, s) | (s, Shape::Scalar) => return Ok(Some(s)),
+                            (Shape::Vector(a), Shape::Vector(b)) if a == b => return Ok(Some(Shape::Scalar)),
+                            (Shape::Matrix(m, k), Shape::Vector(n)) if k == n => return Ok(Some(Shape::Vector(m))),
+                            (Shape::Matrix(m, k), Shape::Matrix(l, n)) if k == l => return Ok(Some(Shape::Matrix(m, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Div => {
+                        match (ls, rs) {
+                            (s, Shape::Scalar) => return Ok(Some(s)),
+                            (Shape::Vector(a), Shape::Vector(b)) if a == b => return Ok(Some(Shape::Scalar)),
+                            (Shape::Vector(n), Shape::Matrix(k, l)) if n == k && k == l => return Ok(Some(Shape::Vector(n))),
+                            (Shape::Matrix(m, n), Shape::Matrix(k, l)) if n == k && k == l => return Ok(Some(Shape::Matrix(m, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::LeftDiv => {
+                        match (ls, rs) {
+                            (Shape::Scalar, s) => return Ok(Some(s)),
+                            (Shape::Matrix(m, n), Shape::Vector(k)) if m == n && n == k => return Ok(Some(Shape::Vector(k))),
+                            (Shape::Matrix(m, n), Shape::Matrix(k, l)) if m == n && n == k => return Ok(Some(Shape::Matrix(k, l))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Cross => {
+                        match (ls, rs) {
+                            (Shape::Vector(a), Shape::Vector(b)) if a <= 3 && b <= 3 => return Ok(Some(Shape::Vector(3))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Get => {
+                        match (ls, rs) {
+                            (Shape::Vector(_), Shape::Scalar) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Dirac => {
+                        match rs {
+                            Shape::Scalar => return Ok(Some(ls)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Zeros | SimpleOpType::Ones | SimpleOpType::Range | SimpleOpType::ContinuedFraction => {
+                        // the size depends on the values of the arguments
+                        match (ls, rs) {
+                            (Shape::Scalar, Shape::Scalar) => return Ok(None),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Row => {
+                        match (ls, rs) {
+                            (Shape::Matrix(_, n), Shape::Scalar) => return Ok(Some(Shape::Vector(n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Col => {
+                        match (ls, rs) {
+                            (Shape::Matrix(m, _), Shape::Scalar) => return Ok(Some(Shape::Vector(m))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Concat => {
+                        match (ls, rs) {
+                            (Shape::Vector(a), Shape::Vector(b)) => return Ok(Some(Shape::Vector(a+b))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::VStack => {
+                        match (ls, rs) {
+                            (Shape::Matrix(m, n), Shape::Matrix(k, l)) if n == l => return Ok(Some(Shape::Matrix(m+k, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::HStack => {
+                        match (ls, rs) {
+                            (Shape::Matrix(m, n), Shape::Matrix(k, l)) if m == k => return Ok(Some(Shape::Matrix(m, n+l))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Outer => {
+                        match (ls, rs) {
+                            (Shape::Vector(m), Shape::Vector(n)) => return Ok(Some(Shape::Matrix(m, n))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Kron => {
+                        match (ls, rs) {
+                            (Shape::Matrix(m, n), Shape::Matrix(k, l)) => return Ok(Some(Shape::Matrix(m*k, n*l))),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    SimpleOpType::Var | SimpleOpType::Std => {
+                        match (ls, rs) {
+                            (Shape::Vector(_), Shape::Scalar) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    },
+                    _ => {
+                        // the remaining operations (comparisons, logic, powers, roots and the remaining functions of
+                        // two arguments) only take scalars
+                        match (ls, rs) {
+                            (Shape::Scalar, Shape::Scalar) => return Ok(Some(Shape::Scalar)),
+                            _ => return Err(incompatible())
+                        }
+                    }
+                }
+            }
+        }
+    }
+    fn children(&self) -> Vec<&AST> {
+        match self {
+            AST::Scalar(_) | AST::Variable(_) => return vec![],
+            AST::Vector(v) => return v.iter().collect(),
+            AST::List(l) => return l.iter().collect(),
+            AST::Matrix(m) => return m.iter().flatten().collect(),
+            AST::Function { inputs, .. } => return inputs.iter().collect(),
+            AST::Operation(o) => {
+                match &**o {
+                    Operation::SimpleOperation { op_type, left, right } if op_type.arity() == 3 => return operands(left, right),
+                    Operation::SimpleOperation { left, right, .. } => return vec![left, right],
+                    Operation::AdvancedOperation(a) => {
+                        match a {
+                            AdvancedOperation::Integral { expr, lower_bound, upper_bound, .. }
+                            | AdvancedOperation::Sum { expr, lower_bound, upper_bound, .. }
+                            | AdvancedOperation::Product { expr, lower_bound, upper_bound, .. } => return vec![expr, lower_bound, upper_bound],
+                            AdvancedOperation::Derivative { expr, at, .. } | AdvancedOperation::Gradient { expr, at, .. } => return vec![expr, at],
+                            AdvancedOperation::Equation { equations, .. } => return equations.iter().flat_map(|(l, r)| [l, r]).collect()
+                        }
+                    }
+                }
+            }
+        }
+    }
+    fn children_mut(&mut self) -> Vec<&mut AST> {
+        match self {
+            AST::Scalar(_) | AST::Variable(_) => return vec![],
+            AST::Vector(v) => return v.iter_mut().collect(),
+            AST::List(l) => return l.iter_mut().collect(),
+            AST::Matrix(m) => return m.iter_mut().flatten().collect(),
+            AST::Function { inputs, .. } => return inputs.iter_mut().collect(),
+            AST::Operation(o) => {
+                match &mut **o {
+                    Operation::SimpleOperation { op_type, left, right } => {
+                        match (op_type.arity(), right) {
+                            (3, AST::Vector(v)) => return std::iter::once(left).chain(v.iter_mut()).collect(),
+                            (_, right) => return vec![left, right]
+                        }
+                    },
+                    Operation::AdvancedOperation(a) => {
+                        match a {
+                            AdvancedOperation::Integral { expr, lower_bound, upper_bound, .. }
+                            | AdvancedOperation::Sum { expr, lower_bound, upper_bound, .. }
+                            | AdvancedOperation::Product { expr, lower_bound, upper_bound, .. } => return vec![expr, lower_bound, upper_bound],
+                            AdvancedOperation::Derivative { expr, at, .. } | AdvancedOperation::Gradient { expr, at, .. } => return vec![expr, at],
+                            AdvancedOperation::Equation { equations, .. } => return equations.iter_mut().flat_map(|(l, r)| [l, r]).collect()
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// calls f on every node of the AST in pre-order, i.e. on a node before its children
+    /// (left to right).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut operations = 0;
+    /// parse("3x+sin(y)")?.visit(&mut |a| if let AST::Operation(_) = a {operations += 1});
+    ///
+    /// assert_eq!(operations, 3);
+    /// ```
+    pub fn visit<F: FnMut(&AST)>(&self, f: &mut F) {
+        f(self);
+        for c in self.children() {
+            c.visit(f);
+        }
+    }
+    /// calls f on every node of the AST in pre-order like [AST::visit], but allows modifying the
+    /// nodes. If f replaces a node, the children of the new node are visited.
+    pub fn visit_mut<F: FnMut(&mut AST)>(&mut self, f: &mut F) {
+        f(self);
+        for c in self.children_mut() {
+            c.visit_mut(f);
+        }
+    }
+    /// returns a description of the tree in the DOT language of Graphviz, which can be rendered
+    /// with e.g. `dot -Tsvg`. Every node is labeled with its value, its variable name or the type of
+    /// its operation and has an edge to each of its children.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let dot = parse("3*(x+1)")?.to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph AST {"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        self.dot_nodes(&mut nodes, &mut edges);
+
+        let mut output_string = "digraph AST {\n".to_string();
+        for (i, label) in nodes.iter().enumerate() {
+            output_string += &format!("    n{} [label=\"{}\"];\n", i, label.replace('\\', "\\\\").replace('"', "\\\""));
+        }
+        for (from, to) in edges {
+            output_string += &format!("    n{} -> n{};\n", from, to);
+        }
+        output_string += "}\n";
+        return output_string;
+    }
+    // adds the labels of the node and its children to nodes in pre-order together with the edges
+    // between them
+    fn dot_nodes(&self, nodes: &mut Vec<String>, edges: &mut Vec<(usize, usize)>) {
+        let label = match self {
+            AST::Scalar(s) => s.to_string(),
+            AST::Variable(v) => v.to_string(),
+            AST::Vector(_) => "Vector".to_string(),
+            AST::Matrix(_) => "Matrix".to_string(),
+            AST::List(_) => "List".to_string(),
+            AST::Function { name, .. } => format!("{}()", name),
+            AST::Operation(o) => {
+                match &**o {
+                    Operation::SimpleOperation { op_type, .. } => format!("{:?}", op_type),
+                    Operation::AdvancedOperation(a) => {
+                        match a {
+                            AdvancedOperation::Integral { in_terms_of, .. } => format!("Integral in {}", in_terms_of),
+                            AdvancedOperation::Derivative { in_terms_of, .. } => format!("Derivative in {}", in_terms_of),
+                            AdvancedOperation::Sum { in_terms_of, .. } => format!("Sum over {}", in_terms_of),
+                            AdvancedOperation::Product { in_terms_of, .. } => format!("Product over {}", in_terms_of),
+                            AdvancedOperation::Gradient { in_terms_of, .. } => format!("Gradient in {}", in_terms_of.join(", ")),
+                            AdvancedOperation::Equation { search_vars, .. } => format!("Equation in {}", search_vars.join(", "))
+                        }
+                    }
+                }
+            }
+        };
+        let id = nodes.len();
+        nodes.push(label);
+
+        let mut children = self.children();
+        if let AST::Operation(o) = self {
+            if let Operation::SimpleOperation { op_type, .. } = &**o {
+                // the right side of an operation with a single operand is just a placeholder
+                children.truncate(op_type.arity());
+            }
+        }
+        for c in children {
+            edges.push((id, nodes.len()));
+            c.dot_nodes(nodes, edges);
+        }
+    }
+    /// returns the names of all variables the AST depends on. Variables that are bound by an
+    /// advanced operation (e.g. the x in I(x^2, x, 0, 1) or the search variables of an equation)
+    /// are not free inside of it and therefore not included.
+    pub fn free_variables(&self) -> HashSet<String> {
+        let bound_in = |expr: &AST, bound: &[String]| -> HashSet<String> {
+            return expr.free_variables().into_iter().filter(|v| !bound.contains(v)).collect();
+        };
+        let mut vars = HashSet::new();
+        match self {
+            AST::Variable(v) => {vars.insert(v.to_string());},
+            AST::Operation(o) => {
+                let a = match &**o {
+                    Operation::AdvancedOperation(a) => a,
+                    Operation::SimpleOperation { left, right, .. } => {
+                        vars.extend(left.free_variables());
+                        vars.extend(right.free_variables());
+                        return vars;
+                    }
+                };
+                match a {
+                    AdvancedOperation::Integral { expr, in_terms_of, lower_bound, upper_bound }
+                    | AdvancedOperation::Sum { expr, in_terms_of, lower_bound, upper_bound }
+                    | AdvancedOperation::Product { expr, in_terms_of, lower_bound, upper_bound } => {
+                        vars.extend(bound_in(expr, std::slice::from_ref(in_terms_of)));
+                        vars.extend(lower_bound.free_variables());
+                        vars.extend(upper_bound.free_variables());
+                    },
+                    AdvancedOperation::Derivative { expr, in_terms_of, at, .. } => {
+                        vars.extend(bound_in(expr, std::slice::from_ref(in_terms_of)));
+                        vars.extend(at.free_variables());
+                    },
+                    AdvancedOperation::Gradient { expr, in_terms_of, at } => {
+                        vars.extend(bound_in(expr, in_terms_of));
+                        vars.extend(at.free_variables());
+                    },
+                    AdvancedOperation::Equation { equations, search_vars } => {
+                        for (l, r) in equations {
+                            vars.extend(bound_in(l, search_vars));
+                            vars.extend(bound_in(r, search_vars));
+                        }
+                    }
+                }
+            },
+            _ => {
+                for c in self.children() {
+                    vars.extend(c.free_variables());
+                }
+            }
+        }
+        return vars;
+    }
+    /// returns every branch of the AST, in which each a&b (see [SimpleOpType::AddSub]) is replaced
+    /// by either a+b or a-b. The first branch is the principal one, only using a+b.
+    pub(crate) fn add_sub_branches(&self) -> Vec<AST> {
+        if let AST::Operation(o) = self {
+            if let Operation::SimpleOperation { op_type: SimpleOpType::AddSub, left, right } = &**o {
+                let mut branches = vec![];
+                for op_type in [SimpleOpType::Add, SimpleOpType::Sub] {
+                    for l in left.add_sub_branches() {
+                        for r in right.add_sub_branches() {
+                            branches.push(AST::from_operation(Operation::SimpleOperation { op_type: op_type.clone(), left: l.clone(), right: r }));
+                        }
+                    }
+                }
+                return branches;
+            }
+        }
+        let mut branches = vec![self.clone()];
+        let children = self.children();
+        for i in 0..children.len() {
+            let child_branches = children[i].add_sub_branches();
+            if child_branches.len() == 1 {
+                continue;
+            }
+            let mut new_branches = vec![];
+            for b in &branches {
+                for c in &child_branches {
+                    let mut new_branch = b.clone();
+                    *new_branch.children_mut()[i] = c.clone();
+                    new_branches.push(new_branch);
+                }
+            }
+            branches = new_branches;
+        }
+        return branches;
+    }
+    /// replaces every occurrence of the variable with the given name by the replacement and returns
+    /// the new AST. Replacements that are operations get wrapped in parentheses. Occurrences that are
+    /// bound by an advanced operation (e.g. the x in I(x^2, x, 0, 1)) are left untouched, see
+    /// [AST::free_variables].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let substituted = parse("x^2")?.substitute("x", &parse("t+1")?);
+    ///
+    /// assert_eq!(substituted, parse("(t+1)^2")?);
+    /// ```
+    pub fn substitute(&self, name: &str, replacement: &AST) -> AST {
+        let sub = |a: &AST| a.substitute(name, replacement);
+        let bound = |a: &AST, bound: bool| if bound {a.clone()} else {a.substitute(name, replacement)};
+        match self {
+            AST::Variable(v) if v == name => {
+                // keep the precedence of the replacement, e.g. x^2 -> (t+1)^2
+                match replacement {
+                    AST::Operation(o) if !matches!(**o, Operation::SimpleOperation { op_type: SimpleOpType::Parenths, .. }) => {
+                        return AST::from_operation(Operation::SimpleOperation {
+                            op_type: SimpleOpType::Parenths,
+                            left: replacement.clone(),
+                            right: AST::Scalar(0.)
+                        });
+                    },
+                    _ => return replacement.clone()
+                }
+            },
+            AST::Scalar(_) | AST::Variable(_) => return self.clone(),
+            AST::Vector(v) => return AST::Vector(Box::new(v.iter().map(sub).collect())),
+            AST::Matrix(m) => return AST::Matrix(Box::new(m.iter().map(|r| r.iter().map(sub).collect()).collect())),
+            AST::List(l) => return AST::List(l.iter().map(sub).collect()),
+            AST::Function { name: fun_name, inputs } => return AST::Function { name: fun_name.clone(), inputs: Box::new(inputs.iter().map(sub).collect()) },
+            AST::Operation(o) => {
+                match &**o {
+                    Operation::SimpleOperation { op_type, left, right } => return AST::from_operation(Operation::SimpleOperation {
+                        op_type: op_type.clone(),
+                        left: sub(left),
+                        right: sub(right)
+                    }),
+                    Operation::AdvancedOperation(a) => {
+                        let advanced = match a {
+                            AdvancedOperation::Integral { expr, in_terms_of, lower_bound, upper_bound } => AdvancedOperation::Integral {
+                                expr: bound(expr, in_terms_of == name), in_terms_of: in_terms_of.clone(), lower_bound: sub(lower_bound), upper_bound: sub(upper_bound)
+                            },
+                            AdvancedOperation::Sum { expr, in_terms_of, lower_bound, upper_bound } => AdvancedOperation::Sum {
+                                expr: bound(expr, in_terms_of == name), in_terms_of: in_terms_of.clone(), lower_bound: sub(lower_bound), upper_bound: sub(upper_bound)
+                            },
+                            AdvancedOperation::Product { expr, in_terms_of, lower_bound, upper_bound } => AdvancedOperation::Product {
+                                expr: bound(expr, in_terms_of == name), in_terms_of: in_terms_of.clone(), lower_bound: sub(lower_bound), upper_bound: sub(upper_bound)
+                            },
+                            AdvancedOperation::Derivative { expr, in_terms_of, at, order } => AdvancedOperation::Derivative {
+                                expr: bound(expr, in_terms_of == name), in_terms_of: in_terms_of.clone(), at: sub(at), order: *order
+                            },
+                            AdvancedOperation::Gradient { expr, in_terms_of, at } => AdvancedOperation::Gradient {
+                                expr: bound(expr, in_terms_of.iter().any(|v| v == name)), in_terms_of: in_terms_of.clone(), at: sub(at)
+                            },
+                            AdvancedOperation::Equation { equations, search_vars } => {
+                                let is_bound = search_vars.iter().any(|v| v == name);
+                                AdvancedOperation::Equation {
+                                    equations: equations.iter().map(|(l, r)| (bound(l, is_bound), bound(r, is_bound))).collect(),
+                                    search_vars: search_vars.clone()
+                                }
+                            }
+                        };
+                        return AST::from_operation(Operation::AdvancedOperation(advanced));
+                    }
+                }
+            }
+        }
+    }
+    /// simplifies the AST, which makes repeated evaluation faster. Constant scalar sub-expressions
+    /// get folded (2*3+x -> 6+x), parentheses get removed and x*1, 1*x, x/1 and -(-x) get collapsed
+    /// to x. x+0, 0+x, x-0 and x^1 are only collapsed if x is known to be a scalar, as they fail for
+    /// vectors and matrices. Variables (including pi and e), functions and operations that depend
+    /// on the angle mode of the context (sin(90), arctan(1), ...) are left intact, as their values
+    /// depend on the context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let simplified = parse("(2*3+x)*1")?.simplify();
+    ///
+    /// assert_eq!(simplified, parse("6+x")?);
+    /// ```
+    pub fn simplify(&self) -> AST {
+        let mut simplified = self.clone();
+        simplified.simplify_rec();
+        return simplified;
+    }
+    fn simplify_rec(&mut self) {
+        for c in self.children_mut() {
+            c.simplify_rec();
+        }
+        let (op_type, left, right) = match self {
+            AST::Operation(o) => match &**o {
+                Operation::SimpleOperation { op_type, left, right } => (op_type.clone(), left.clone(), right.clone()),
+                Operation::AdvancedOperation(_) => return
+            },
+            _ => return
+        };
+
+        if op_type == SimpleOpType::Parenths {
+            *self = left;
+            return;
+        }
+
+        if let (AST::Scalar(_), AST::Scalar(_), false) = (&left, &right, depends_on_angle_mode(&op_type)) {
+            // operations that fail (or have multiple results) are left for the evaluation
+            if let Ok(res) = crate::eval(self, &Context::empty()) {
+                if let [Value::Scalar(s)] = res.to_vec()[..] {
+                    *self = AST::Scalar(s);
+                    return;
+                }
+            }
+        }
+
+        let is = |a: &AST, x: f64| *a == AST::Scalar(x);
+        // adding 0 and raising to the power of 1 fail for vectors and matrices, so collapsing them
+        // would turn an invalid expression into a valid one
+        let is_scalar = |a: &AST| matches!(a.shape(&Context::empty()), Ok(Some(Shape::Scalar)));
+        match op_type {
+            SimpleOpType::Mult | SimpleOpType::HiddenMult if is(&right, 1.) => *self = left,
+            SimpleOpType::Mult | SimpleOpType::HiddenMult if is(&left, 1.) => *self = right,
+            SimpleOpType::Add if is(&right, 0.) && is_scalar(&left) => *self = left,
+            SimpleOpType::Add if is(&left, 0.) && is_scalar(&right) => *self = right,
+            SimpleOpType::Sub if is(&right, 0.) && is_scalar(&left) => *self = left,
+            SimpleOpType::Div if is(&right, 1.) => *self = left,
+            SimpleOpType::Pow if is(&right, 1.) && is_scalar(&left) => *self = left,
+            SimpleOpType::Neg => {
+                if let AST::Operation(o) = &left {
+                    if let Operation::SimpleOperation { op_type: SimpleOpType::Neg, left: inner, .. } = &**o {
+                        *self = inner.clone();
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+    // whether the AST has to be put in parentheses when it is printed as an operand of the given
+    // operation, so that parsing the printed string results in the same tree again
+    fn needs_parenths(&self, parent: &SimpleOpType, is_right: bool) -> bool {
+        let op_types = binary_op_types();
+        let op_type = match self {
+            AST::Scalar(s) => return s.is_sign_negative(),
+            AST::Operation(o) => match &**o {
+                Operation::SimpleOperation { op_type, .. } => op_type,
+                Operation::AdvancedOperation(_) => return false
+            },
+            _ => return false
+        };
+        if *op_type == SimpleOpType::Neg {
+            return true;
+        }
+        let child = op_types.iter().position(|o| o == op_type);
+        let Some(parent) = op_types.iter().position(|o| o == parent) else {
+            // the operand of a prefix or postfix operation (-a, !a, a!)
+            return child.is_some() || matches!(op_type, SimpleOpType::Not | SimpleOpType::Factorial);
+        };
+        let Some(child) = child else {
+            return false;
+        };
+        if is_comparison(&op_types[child]) && is_comparison(&op_types[parent]) {
+            // a<b<c is a chained comparison
+            return true;
+        }
+        if child != parent {
+            return child < parent;
+        }
+        // subtractions and multiplications are split at their last occurrence, all other
+        // operations at their first one
+        if matches!(op_types[parent], SimpleOpType::Sub | SimpleOpType::Mult) {
+            return is_right;
+        }
+        return !is_right;
+    }
     /// converts the AST to a string using crude symbols for operations, vectors and matrices.
+    ///
+    /// Parentheses are inserted where the precedence of the operations requires them, so that
+    /// parsing the returned string results in an equivalent AST.
     pub fn as_string(&self) -> String {
         match self {
-            AST::Scalar(s) => return round_and_format(*s, false),
+            AST::Scalar(s) => return s.to_string(),
             AST::Vector(v) => return format!("[{}]", v.iter().map(|a| a.as_string()).collect::<Vec<String>>().join(", ")),
             AST::Matrix(m) => return format!("[{}]", m.iter().map(|v| "[".to_string() + &v.iter().map(|v| v.as_string()).collect::<Vec<String>>().join(", ") + "]").collect::<Vec<String>>().join(", ")),
             AST::List(l) => return format!("{{{}}}", l.iter().map(|a| a.as_string()).collect::<Vec<String>>().join(", ")),
@@ -634,30 +1957,108 @@ impl AST {
             AST::Operation(o) => {
                 match &**o  {
                     Operation::SimpleOperation {op_type, left, right} => {
-                        let lv = &left.as_string();
-                        let rv = &right.as_string(); 
+                        let operand = |a: &AST, is_right: bool| {
+                            if a.needs_parenths(op_type, is_right) {
+                                return format!("({})", a.as_string());
+                            }
+                            return a.as_string();
+                        };
+                        let lv = &operand(left, false);
+                        let rv = &operand(right, true);
                         match op_type {
-                            SimpleOpType::Get => return format!("{}_{}", lv, rv),
+                            SimpleOpType::Or => return format!("{} || {}", lv, rv),
+                            SimpleOpType::And => return format!("{} && {}", lv, rv),
+                            SimpleOpType::Not => return format!("!{}", lv),
+                            SimpleOpType::Less => return format!("{} < {}", lv, rv),
+                            SimpleOpType::Greater => return format!("{} > {}", lv, rv),
+                            SimpleOpType::LessEq => return format!("{} <= {}", lv, rv),
+                            SimpleOpType::GreaterEq => return format!("{} >= {}", lv, rv),
+                            SimpleOpType::Get => return format!("{}?{}", lv, rv),
+                            SimpleOpType::Factorial => return format!("{}!", lv),
                             SimpleOpType::Add => return format!("{} + {}", lv, rv),
                             SimpleOpType::Sub => return format!("{} - {}", lv, rv),
-                            SimpleOpType::AddSub => return format!("{} +- {}", lv, rv),
+                            SimpleOpType::AddSub => return format!("{} & {}", lv, rv),
+                            #[cfg(feature = "uncertainty")]
+                            SimpleOpType::Uncertainty => return format!("{}+-{}", lv, rv),
                             SimpleOpType::Mult => return format!("{} * {}", lv, rv),
                             SimpleOpType::Neg => return format!("-{}", lv),
                             SimpleOpType::Div => return format!("{} / {}", lv, rv),
-                            SimpleOpType::HiddenMult => return format!("{}{}", lv, rv),
-                            SimpleOpType::Pow => return format!("{}^({})", lv, rv),
-                            SimpleOpType::Cross => return format!("{}x{}", lv, rv),
-                            SimpleOpType::Abs => return format!("|{}|", lv),
-                            SimpleOpType::Sin => return format!("sin({})", lv),
-                            SimpleOpType::Cos => return format!("cos({})", lv),
-                            SimpleOpType::Tan => return format!("tan({})", lv),
-                            SimpleOpType::Sqrt => return format!("sqrt({})", lv),
-                            SimpleOpType::Root => return format!("root({}, {})", lv, rv),
-                            SimpleOpType::Ln => return format!("ln({})", lv),
-                            SimpleOpType::Arcsin => return format!("arcsin({})", lv),
-                            SimpleOpType::Arccos => return format!("arccos({})", lv),
-                            SimpleOpType::Arctan => return format!("arctan({})", lv),
-                            SimpleOpType::Parenths => return format!("({})", lv),
+                            SimpleOpType::LeftDiv => return format!("{} \\ {}", lv, rv),
+                            SimpleOpType::HiddenMult => {
+                                // the parser only detects a hidden multiplication after a number or
+                                // between two parentheses
+                                if matches!(left, AST::Scalar(_)) && rv.starts_with(|c: char| c.is_alphabetic() || c == '\\' || c == '(') {
+                                    return format!("{}{}", lv, rv);
+                                }
+                                let wrap = |s: &String| if s.starts_with('(') && s.ends_with(')') {s.to_string()} else {format!("({})", s)};
+                                return format!("{}{}", wrap(lv), wrap(rv));
+                            },
+                            SimpleOpType::Pow => return format!("{}^({})", lv, right.as_string()),
+                            SimpleOpType::Cross => return format!("{}#{}", lv, rv),
+                            SimpleOpType::Abs => return format!("abs({})", left.as_string()),
+                            SimpleOpType::Sin => return format!("sin({})", left.as_string()),
+                            SimpleOpType::Cos => return format!("cos({})", left.as_string()),
+                            SimpleOpType::Tan => return format!("tan({})", left.as_string()),
+                            SimpleOpType::Sqrt => return format!("sqrt({})", left.as_string()),
+                            SimpleOpType::Root => return format!("root({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Ln => return format!("ln({})", left.as_string()),
+                            SimpleOpType::Log1p => return format!("log1p({})", left.as_string()),
+                            SimpleOpType::Expm1 => return format!("expm1({})", left.as_string()),
+                            SimpleOpType::Arcsin => return format!("arcsin({})", left.as_string()),
+                            SimpleOpType::Arccos => return format!("arccos({})", left.as_string()),
+                            SimpleOpType::Arctan => return format!("arctan({})", left.as_string()),
+                            SimpleOpType::Cot => return format!("cot({})", left.as_string()),
+                            SimpleOpType::Sec => return format!("sec({})", left.as_string()),
+                            SimpleOpType::Csc => return format!("csc({})", left.as_string()),
+                            SimpleOpType::Arsinh => return format!("arsinh({})", left.as_string()),
+                            SimpleOpType::Arcosh => return format!("arcosh({})", left.as_string()),
+                            SimpleOpType::Artanh => return format!("artanh({})", left.as_string()),
+                            SimpleOpType::SpectralRadius => return format!("spectral_radius({})", left.as_string()),
+                            SimpleOpType::Norm2 => return format!("norm2({})", left.as_string()),
+                            SimpleOpType::Heaviside => return format!("heaviside({})", left.as_string()),
+                            SimpleOpType::Sign => return format!("sign({})", left.as_string()),
+                            SimpleOpType::Dirac => return format!("dirac({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Det => return format!("det({})", left.as_string()),
+                            SimpleOpType::SteadyState => return format!("steady_state({})", left.as_string()),
+                            SimpleOpType::Rank => return format!("rank({})", left.as_string()),
+                            SimpleOpType::Rref => return format!("rref({})", left.as_string()),
+                            SimpleOpType::Row => return format!("row({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Col => return format!("col({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Concat => return format!("concat({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::VStack => return format!("vstack({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::HStack => return format!("hstack({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Outer => return format!("outer({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Kron => return format!("kron({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Linspace => return format!("linspace({})", join_operands(left, right, AST::as_string, ", ")),
+                            SimpleOpType::Clamp => return format!("clamp({})", join_operands(left, right, AST::as_string, ", ")),
+                            SimpleOpType::Slice => return format!("slice({})", join_operands(left, right, AST::as_string, ", ")),
+                            SimpleOpType::Reshape => return format!("reshape({})", join_operands(left, right, AST::as_string, ", ")),
+                            SimpleOpType::Identity => return format!("identity({})", left.as_string()),
+                            SimpleOpType::Zeros => return format!("zeros({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Ones => return format!("ones({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Diag => return format!("diag({})", left.as_string()),
+                            SimpleOpType::Range => return format!("range({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::ContinuedFraction => return format!("continued_fraction({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Atan2 => return format!("atan2({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Min => return format!("min({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Max => return format!("max({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Gcd => return format!("gcd({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Lcm => return format!("lcm({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Choose => return format!("choose({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Perm => return format!("perm({}, {})", left.as_string(), right.as_string()),
+                            SimpleOpType::Mean => return format!("mean({})", left.as_string()),
+                            SimpleOpType::VSum => return format!("vsum({})", left.as_string()),
+                            SimpleOpType::VProd => return format!("vprod({})", left.as_string()),
+                            SimpleOpType::VMin => return format!("vmin({})", left.as_string()),
+                            SimpleOpType::VMax => return format!("vmax({})", left.as_string()),
+                            SimpleOpType::Var | SimpleOpType::Std => {
+                                let name = if *op_type == SimpleOpType::Var {"var"} else {"std"};
+                                if *right == AST::Scalar(0.) {
+                                    return format!("{}({})", name, left.as_string());
+                                }
+                                return format!("{}({}, {})", name, left.as_string(), right.as_string());
+                            },
+                            SimpleOpType::Parenths => return format!("({})", left.as_string()),
                         }
                     },
                     Operation::AdvancedOperation(a) => {
@@ -668,14 +2069,34 @@ impl AST {
                                 let eupper_b = &upper_bound.as_string();
                                 return format!("I({}, {}, {}, {})", eexpr, in_terms_of, elower_b, eupper_b);
                             },
-                            AdvancedOperation::Derivative {expr, in_terms_of, at} => {
+                            AdvancedOperation::Derivative {expr, in_terms_of, at, order} => {
                                 let eexpr = &expr.as_string();
                                 let eat = &at.as_string();
+                                if *order != 1 {
+                                    return format!("D({}, {}, {}, {})", eexpr, in_terms_of, eat, order);
+                                }
                                 return format!("D({}, {}, {})", eexpr, in_terms_of, eat);
                             },
-                            AdvancedOperation::Equation { equations, .. } => {
+                            AdvancedOperation::Sum {expr, in_terms_of, lower_bound, upper_bound} => {
+                                let eexpr = &expr.as_string();
+                                let elower_b = &lower_bound.as_string();
+                                let eupper_b = &upper_bound.as_string();
+                                return format!("sum({}, {}, {}, {})", eexpr, in_terms_of, elower_b, eupper_b);
+                            },
+                            AdvancedOperation::Product {expr, in_terms_of, lower_bound, upper_bound} => {
+                                let eexpr = &expr.as_string();
+                                let elower_b = &lower_bound.as_string();
+                                let eupper_b = &upper_bound.as_string();
+                                return format!("prod({}, {}, {}, {})", eexpr, in_terms_of, elower_b, eupper_b);
+                            },
+                            AdvancedOperation::Gradient {expr, in_terms_of, at} => {
+                                let eexpr = &expr.as_string();
+                                let eat = &at.as_string();
+                                return format!("grad({}, [{}], {})", eexpr, in_terms_of.join(", "), eat);
+                            },
+                            AdvancedOperation::Equation { equations, search_vars } => {
                                 let eqs: Vec<String> = equations.iter().map(|e| format!("{}={}", e.0.as_string(), e.1.as_string())).collect();
-                                return format!("eq({})", eqs.join(","));
+                                return format!("eq({}, {})", eqs.join(", "), search_vars.join(", "));
                             }
                         }
                     }
@@ -733,8 +2154,11 @@ impl AST {
             },
             AST::List(l) => return format!("\\left\\{{{}\\right\\}}", l.iter().map(|a| a.latex_print()).collect::<Vec<String>>().join("; ")),
             AST::Variable(v) => {
-                if v == "pi" {
-                    return "\\pi".to_string();
+                if v == "pi" || v == "tau" || v == "phi" {
+                    return format!("\\{}", v);
+                }
+                if v == "inf" {
+                    return "\\infty".to_string();
                 }
                 return v.to_string()
             },
@@ -756,13 +2180,24 @@ impl AST {
                         let lv = &left.latex_print();
                         let rv = &right.latex_print(); 
                         match op_type {
+                            SimpleOpType::Or => return format!("{}\\lor {}", lv, rv),
+                            SimpleOpType::And => return format!("{}\\land {}", lv, rv),
+                            SimpleOpType::Not => return format!("\\lnot {}", lv),
+                            SimpleOpType::Less => return format!("{}<{}", lv, rv),
+                            SimpleOpType::Greater => return format!("{}>{}", lv, rv),
+                            SimpleOpType::LessEq => return format!("{}\\leq {}", lv, rv),
+                            SimpleOpType::GreaterEq => return format!("{}\\geq {}", lv, rv),
                             SimpleOpType::Get => return format!("{}_{{{}}}", lv, rv),
+                            SimpleOpType::Factorial => return format!("{}!", lv),
                             SimpleOpType::Add => return format!("{}+{}", lv, rv),
                             SimpleOpType::Sub => return format!("{}-{}", lv, rv),
                             SimpleOpType::AddSub => return format!("{}\\pm{}", lv, rv),
+                            #[cfg(feature = "uncertainty")]
+                            SimpleOpType::Uncertainty => return format!("{}\\pm {}", lv, rv),
                             SimpleOpType::Mult => return format!("{}\\cdot {}", lv, rv),
                             SimpleOpType::Neg => return format!("-{}", lv),
                             SimpleOpType::Div => return format!("\\frac{{{}}}{{{}}}", lv, rv),
+                            SimpleOpType::LeftDiv => return format!("{}^{{-1}}\\cdot {}", lv, rv),
                             SimpleOpType::HiddenMult => return format!("{}{}", lv, rv),
                             SimpleOpType::Pow => return format!("{}^{{{}}}", lv, rv),
                             SimpleOpType::Cross => return format!("{}\\times {}", lv, rv),
@@ -773,9 +2208,64 @@ impl AST {
                             SimpleOpType::Sqrt => return format!("\\sqrt{{{}}}", lv),
                             SimpleOpType::Root => return format!("\\sqrt[{}]{{{}}}", rv, lv),
                             SimpleOpType::Ln => return format!("\\ln{{({})}}", lv),
+                            SimpleOpType::Log1p => return format!("\\ln{{(1+{})}}", lv),
+                            SimpleOpType::Expm1 => return format!("e^{{{}}}-1", lv),
                             SimpleOpType::Arcsin => return format!("\\arcsin{{({})}}", lv),
                             SimpleOpType::Arccos => return format!("\\arccos{{({})}}", lv),
                             SimpleOpType::Arctan => return format!("\\arctan{{({})}}", lv),
+                            SimpleOpType::Cot => return format!("\\cot{{({})}}", lv),
+                            SimpleOpType::Sec => return format!("\\sec{{({})}}", lv),
+                            SimpleOpType::Csc => return format!("\\csc{{({})}}", lv),
+                            SimpleOpType::Arsinh => return format!("\\operatorname{{arsinh}}{{({})}}", lv),
+                            SimpleOpType::Arcosh => return format!("\\operatorname{{arcosh}}{{({})}}", lv),
+                            SimpleOpType::Artanh => return format!("\\operatorname{{artanh}}{{({})}}", lv),
+                            SimpleOpType::SpectralRadius => return format!("\\rho{{({})}}", lv),
+                            SimpleOpType::Norm2 => return format!("\\left\\|{}\\right\\|_{{2}}", lv),
+                            SimpleOpType::Heaviside => return format!("H{{({})}}", lv),
+                            SimpleOpType::Sign => return format!("\\operatorname{{sgn}}{{({})}}", lv),
+                            SimpleOpType::Dirac => return format!("\\delta_{{{}}}{{({})}}", rv, lv),
+                            SimpleOpType::Det => return format!("\\det{{({})}}", lv),
+                            SimpleOpType::SteadyState => return format!("\\operatorname{{steady\\_state}}{{({})}}", lv),
+                            SimpleOpType::Rank => return format!("\\operatorname{{rank}}{{({})}}", lv),
+                            SimpleOpType::Rref => return format!("\\operatorname{{rref}}{{({})}}", lv),
+                            SimpleOpType::Row => return format!("\\operatorname{{row}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Col => return format!("\\operatorname{{col}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Concat => return format!("\\operatorname{{concat}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::VStack => return format!("\\operatorname{{vstack}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::HStack => return format!("\\operatorname{{hstack}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Linspace => return format!("\\operatorname{{linspace}}{{({})}}", join_operands(left, right, AST::latex_print, ", ")),
+                            SimpleOpType::Clamp => return format!("\\operatorname{{clamp}}{{({})}}", join_operands(left, right, AST::latex_print, ", ")),
+                            SimpleOpType::Slice => return format!("\\operatorname{{slice}}{{({})}}", join_operands(left, right, AST::latex_print, ", ")),
+                            SimpleOpType::Reshape => return format!("\\operatorname{{reshape}}{{({})}}", join_operands(left, right, AST::latex_print, ", ")),
+                            SimpleOpType::Outer | SimpleOpType::Kron => {
+                                let operand = |a: &AST, s: &String| if is_compound(a) {format!("\\left({}\\right)", s)} else {s.to_string()};
+                                return format!("{}\\otimes {}", operand(left, lv), operand(right, rv));
+                            },
+                            SimpleOpType::Identity => return format!("I_{{{}}}", lv),
+                            SimpleOpType::Zeros => return format!("0_{{{}\\times {}}}", lv, rv),
+                            SimpleOpType::Ones => return format!("1_{{{}\\times {}}}", lv, rv),
+                            SimpleOpType::Diag => return format!("\\operatorname{{diag}}{{({})}}", lv),
+                            SimpleOpType::Range => return format!("\\operatorname{{range}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::ContinuedFraction => return format!("\\operatorname{{continued\\_fraction}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Atan2 => return format!("\\operatorname{{atan2}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Min => return format!("\\min\\left({}, {}\\right)", lv, rv),
+                            SimpleOpType::Max => return format!("\\max\\left({}, {}\\right)", lv, rv),
+                            SimpleOpType::Gcd => return format!("\\gcd{{({}, {})}}", lv, rv),
+                            SimpleOpType::Lcm => return format!("\\operatorname{{lcm}}{{({}, {})}}", lv, rv),
+                            SimpleOpType::Choose => return format!("\\binom{{{}}}{{{}}}", lv, rv),
+                            SimpleOpType::Perm => return format!("{{}}^{{{}}}P_{{{}}}", lv, rv),
+                            SimpleOpType::Mean => return format!("\\operatorname{{mean}}{{({})}}", lv),
+                            SimpleOpType::VSum => return format!("\\operatorname{{sum}}{{({})}}", lv),
+                            SimpleOpType::VProd => return format!("\\operatorname{{prod}}{{({})}}", lv),
+                            SimpleOpType::VMin => return format!("\\min{{({})}}", lv),
+                            SimpleOpType::VMax => return format!("\\max{{({})}}", lv),
+                            SimpleOpType::Var | SimpleOpType::Std => {
+                                let name = if *op_type == SimpleOpType::Var {"var"} else {"std"};
+                                if *right == AST::Scalar(0.) {
+                                    return format!("\\operatorname{{{}}}{{({})}}", name, lv);
+                                }
+                                return format!("\\operatorname{{{}}}{{({}, {})}}", name, lv, rv);
+                            },
                             SimpleOpType::Parenths => return format!("\\left({}\\right)", lv),
                         }
                     },
@@ -787,11 +2277,31 @@ impl AST {
                                 let eupper_b = &upper_bound.latex_print();
                                 return format!("\\int_{{{}}}^{{{}}}{} d{}", elower_b, eupper_b, eexpr, in_terms_of);
                             },
-                            AdvancedOperation::Derivative {expr, in_terms_of, at} => {
+                            AdvancedOperation::Derivative {expr, in_terms_of, at, order} => {
                                 let eexpr = &expr.latex_print();
                                 let eat = &at.latex_print();
+                                if *order != 1 {
+                                    return format!("\\frac{{\\partial^{{{}}}}}{{\\partial {}^{{{}}}}}\\left({}\\right)_{{\\text{{at }}{} = {}}}", order, in_terms_of, order, eexpr, in_terms_of, eat);
+                                }
                                 return format!("\\frac{{\\partial}}{{\\partial {}}}\\left({}\\right)_{{\\text{{at }}{} = {}}}", in_terms_of, eexpr, in_terms_of, eat);
                             },
+                            AdvancedOperation::Sum {expr, in_terms_of, lower_bound, upper_bound} => {
+                                let eexpr = &expr.latex_print();
+                                let elower_b = &lower_bound.latex_print();
+                                let eupper_b = &upper_bound.latex_print();
+                                return format!("\\sum_{{{}={}}}^{{{}}} {}", in_terms_of, elower_b, eupper_b, eexpr);
+                            },
+                            AdvancedOperation::Product {expr, in_terms_of, lower_bound, upper_bound} => {
+                                let eexpr = &expr.latex_print();
+                                let elower_b = &lower_bound.latex_print();
+                                let eupper_b = &upper_bound.latex_print();
+                                return format!("\\prod_{{{}={}}}^{{{}}} {}", in_terms_of, elower_b, eupper_b, eexpr);
+                            },
+                            AdvancedOperation::Gradient {expr, in_terms_of, at} => {
+                                let eexpr = &expr.latex_print();
+                                let eat = &at.latex_print();
+                                return format!("\\nabla\\left({}\\right)_{{\\text{{at }}\\left({}\\right) = {}}}", eexpr, in_terms_of.join(", "), eat);
+                            },
                             AdvancedOperation::Equation { equations, .. } => {
                                 let eqs: Vec<String> = equations.iter().map(|e| format!("{}&={}", e.0.latex_print(), e.1.latex_print())).collect();
                                 return format!("\\left|\\begin{{align}}{}\\end{{align}}\\right|", eqs.join("\\\\ \n "))
@@ -802,29 +2312,212 @@ impl AST {
             }
         }
     }
+    /// converts the AST to presentation MathML, mirroring the structure of [AST::as_latex].
+    pub fn as_mathml(&self) -> String {
+        format!("<math>{}</math>", self.mathml_print())
+    }
+    pub(crate) fn mathml_print(&self) -> String {
+        let parenths = |s: &String| format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", s);
+        let named = |name: &str, s: &String| format!("<mrow><mi>{}</mi><mo>&#x2061;</mo>{}</mrow>", name, parenths(s));
+        match self {
+            AST::Scalar(s) => return mathml_number(*s),
+            AST::Vector(v) => return mathml_table(v.iter().map(|e| vec![e.mathml_print()]).collect(), "(", ")"),
+            AST::Matrix(m) => return mathml_table(m.iter().map(|r| r.iter().map(|e| e.mathml_print()).collect()).collect(), "[", "]"),
+            AST::List(l) => return format!("<mrow><mo>{{</mo>{}<mo>}}</mo></mrow>", l.iter().map(|a| a.mathml_print()).collect::<Vec<String>>().join("<mo>;</mo>")),
+            AST::Variable(v) => return mathml_identifier(v),
+            AST::Function { name, inputs } => {
+                let inputs_str = inputs.iter().map(|i| i.mathml_print()).collect::<Vec<String>>().join("<mo>,</mo>");
+                return format!("<mrow>{}<mo>&#x2061;</mo>{}</mrow>", mathml_identifier(name), parenths(&inputs_str));
+            },
+            AST::Operation(o) => {
+                match &**o {
+                    Operation::SimpleOperation {op_type, left, right} => {
+                        let lv = &left.mathml_print();
+                        let rv = &right.mathml_print();
+                        let infix = |op: &str| format!("<mrow>{}<mo>{}</mo>{}</mrow>", lv, op, rv);
+                        match op_type {
+                            SimpleOpType::Or => return infix("&#x2228;"),
+                            SimpleOpType::And => return infix("&#x2227;"),
+                            SimpleOpType::Not => return format!("<mrow><mo>&#xAC;</mo>{}</mrow>", lv),
+                            SimpleOpType::Less => return infix("&lt;"),
+                            SimpleOpType::Greater => return infix("&gt;"),
+                            SimpleOpType::LessEq => return infix("&#x2264;"),
+                            SimpleOpType::GreaterEq => return infix("&#x2265;"),
+                            SimpleOpType::Get => return format!("<msub>{}{}</msub>", lv, rv),
+                            SimpleOpType::Factorial => return format!("<mrow>{}<mo>!</mo></mrow>", lv),
+                            SimpleOpType::Add => return infix("+"),
+                            SimpleOpType::Sub => return infix("-"),
+                            SimpleOpType::AddSub => return infix("&#xB1;"),
+                            #[cfg(feature = "uncertainty")]
+                            SimpleOpType::Uncertainty => return infix("&#xB1;"),
+                            SimpleOpType::Mult => return infix("&#x22C5;"),
+                            SimpleOpType::Neg => return format!("<mrow><mo>-</mo>{}</mrow>", lv),
+                            SimpleOpType::Div => return format!("<mfrac>{}{}</mfrac>", lv, rv),
+                            SimpleOpType::LeftDiv => return format!("<mrow><msup>{}<mrow><mo>-</mo><mn>1</mn></mrow></msup><mo>&#x22C5;</mo>{}</mrow>", lv, rv),
+                            SimpleOpType::HiddenMult => return infix("&#x2062;"),
+                            SimpleOpType::Pow => return format!("<msup>{}{}</msup>", lv, rv),
+                            SimpleOpType::Cross => return infix("&#xD7;"),
+                            SimpleOpType::Abs => return format!("<mrow><mo>|</mo>{}<mo>|</mo></mrow>", lv),
+                            SimpleOpType::Sin => return named("sin", lv),
+                            SimpleOpType::Cos => return named("cos", lv),
+                            SimpleOpType::Tan => return named("tan", lv),
+                            SimpleOpType::Sqrt => return format!("<msqrt>{}</msqrt>", lv),
+                            SimpleOpType::Root => return format!("<mroot>{}{}</mroot>", lv, rv),
+                            SimpleOpType::Ln => return named("ln", lv),
+                            SimpleOpType::Log1p => return named("ln", &format!("<mrow><mn>1</mn><mo>+</mo>{}</mrow>", lv)),
+                            SimpleOpType::Expm1 => return format!("<mrow><msup><mi>e</mi>{}</msup><mo>-</mo><mn>1</mn></mrow>", lv),
+                            SimpleOpType::Arcsin => return named("arcsin", lv),
+                            SimpleOpType::Arccos => return named("arccos", lv),
+                            SimpleOpType::Arctan => return named("arctan", lv),
+                            SimpleOpType::Cot => return named("cot", lv),
+                            SimpleOpType::Sec => return named("sec", lv),
+                            SimpleOpType::Csc => return named("csc", lv),
+                            SimpleOpType::Arsinh => return named("arsinh", lv),
+                            SimpleOpType::Arcosh => return named("arcosh", lv),
+                            SimpleOpType::Artanh => return named("artanh", lv),
+                            SimpleOpType::SpectralRadius => return named("&#x3C1;", lv),
+                            SimpleOpType::Norm2 => return format!("<msub><mrow><mo>&#x2016;</mo>{}<mo>&#x2016;</mo></mrow><mn>2</mn></msub>", lv),
+                            SimpleOpType::Heaviside => return named("H", lv),
+                            SimpleOpType::Sign => return named("sgn", lv),
+                            SimpleOpType::Dirac => return format!("<mrow><msub><mi>&#x3B4;</mi>{}</msub><mo>&#x2061;</mo>{}</mrow>", rv, parenths(lv)),
+                            SimpleOpType::Det => return named("det", lv),
+                            SimpleOpType::SteadyState => return named("steady_state", lv),
+                            SimpleOpType::Rank => return named("rank", lv),
+                            SimpleOpType::Rref => return named("rref", lv),
+                            SimpleOpType::Row => return named("row", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Col => return named("col", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Concat => return named("concat", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::VStack => return named("vstack", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::HStack => return named("hstack", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Linspace => return named("linspace", &join_operands(left, right, AST::mathml_print, "<mo>,</mo>")),
+                            SimpleOpType::Clamp => return named("clamp", &join_operands(left, right, AST::mathml_print, "<mo>,</mo>")),
+                            SimpleOpType::Slice => return named("slice", &join_operands(left, right, AST::mathml_print, "<mo>,</mo>")),
+                            SimpleOpType::Reshape => return named("reshape", &join_operands(left, right, AST::mathml_print, "<mo>,</mo>")),
+                            SimpleOpType::Outer | SimpleOpType::Kron => {
+                                let operand = |a: &AST, s: &String| if is_compound(a) {parenths(s)} else {s.to_string()};
+                                return format!("<mrow>{}<mo>&#x2297;</mo>{}</mrow>", operand(left, lv), operand(right, rv));
+                            },
+                            SimpleOpType::Identity => return format!("<msub><mi>I</mi>{}</msub>", lv),
+                            SimpleOpType::Zeros => return format!("<msub><mn>0</mn><mrow>{}<mo>&#xD7;</mo>{}</mrow></msub>", lv, rv),
+                            SimpleOpType::Ones => return format!("<msub><mn>1</mn><mrow>{}<mo>&#xD7;</mo>{}</mrow></msub>", lv, rv),
+                            SimpleOpType::Diag => return named("diag", lv),
+                            SimpleOpType::Range => return named("range", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::ContinuedFraction => return named("continued_fraction", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Atan2 => return named("atan2", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Min => return named("min", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Max => return named("max", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Gcd => return named("gcd", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Lcm => return named("lcm", &format!("{}<mo>,</mo>{}", lv, rv)),
+                            SimpleOpType::Choose => return format!("<mrow><mo>(</mo><mfrac linethickness=\"0\">{}{}</mfrac><mo>)</mo></mrow>", lv, rv),
+                            SimpleOpType::Perm => return format!("<mmultiscripts><mi>P</mi>{}<none/><mprescripts/><none/>{}</mmultiscripts>", rv, lv),
+                            SimpleOpType::Mean => return named("mean", lv),
+                            SimpleOpType::VSum => return named("sum", lv),
+                            SimpleOpType::VProd => return named("prod", lv),
+                            SimpleOpType::VMin => return named("min", lv),
+                            SimpleOpType::VMax => return named("max", lv),
+                            SimpleOpType::Var | SimpleOpType::Std => {
+                                let name = if *op_type == SimpleOpType::Var {"var"} else {"std"};
+                                if *right == AST::Scalar(0.) {
+                                    return named(name, lv);
+                                }
+                                return named(name, &format!("{}<mo>,</mo>{}", lv, rv));
+                            },
+                            SimpleOpType::Parenths => return parenths(lv),
+                        }
+                    },
+                    Operation::AdvancedOperation(a) => {
+                        let at_point = |var: String, at: &AST| format!("<mrow><mtext>at&#xA0;</mtext>{}<mo>=</mo>{}</mrow>", var, at.mathml_print());
+                        match a {
+                            AdvancedOperation::Integral {expr, in_terms_of, lower_bound, upper_bound} => {
+                                return format!("<mrow><msubsup><mo>&#x222B;</mo>{}{}</msubsup>{}<mi>d</mi>{}</mrow>", lower_bound.mathml_print(), upper_bound.mathml_print(), expr.mathml_print(), mathml_identifier(in_terms_of));
+                            },
+                            AdvancedOperation::Derivative {expr, in_terms_of, at, order} => {
+                                let var = mathml_identifier(in_terms_of);
+                                let fraction = if *order != 1 {
+                                    format!("<mfrac><msup><mo>&#x2202;</mo><mn>{}</mn></msup><mrow><mo>&#x2202;</mo><msup>{}<mn>{}</mn></msup></mrow></mfrac>", order, var, order)
+                                } else {
+                                    format!("<mfrac><mo>&#x2202;</mo><mrow><mo>&#x2202;</mo>{}</mrow></mfrac>", var)
+                                };
+                                return format!("<mrow>{}<msub>{}{}</msub></mrow>", fraction, parenths(&expr.mathml_print()), at_point(var, at));
+                            },
+                            AdvancedOperation::Sum {expr, in_terms_of, lower_bound, upper_bound} => {
+                                return format!("<mrow><munderover><mo>&#x2211;</mo><mrow>{}<mo>=</mo>{}</mrow>{}</munderover>{}</mrow>", mathml_identifier(in_terms_of), lower_bound.mathml_print(), upper_bound.mathml_print(), expr.mathml_print());
+                            },
+                            AdvancedOperation::Product {expr, in_terms_of, lower_bound, upper_bound} => {
+                                return format!("<mrow><munderover><mo>&#x220F;</mo><mrow>{}<mo>=</mo>{}</mrow>{}</munderover>{}</mrow>", mathml_identifier(in_terms_of), lower_bound.mathml_print(), upper_bound.mathml_print(), expr.mathml_print());
+                            },
+                            AdvancedOperation::Gradient {expr, in_terms_of, at} => {
+                                let vars = in_terms_of.iter().map(|v| mathml_identifier(v)).collect::<Vec<String>>().join("<mo>,</mo>");
+                                return format!("<mrow><mo>&#x2207;</mo><msub>{}{}</msub></mrow>", parenths(&expr.mathml_print()), at_point(parenths(&vars), at));
+                            },
+                            AdvancedOperation::Equation { equations, .. } => {
+                                let eqs: Vec<Vec<String>> = equations.iter().map(|e| vec![format!("<mrow>{}<mo>=</mo>{}</mrow>", e.0.mathml_print(), e.1.mathml_print())]).collect();
+                                return mathml_table(eqs, "|", "|");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// specifies the type of operation for the [SimpleOperation](Operation::SimpleOperation) struct.
 /// 
 /// This enum only contains simple mathematical operations with a left and right side or a maximum
-/// of two arguments. For more advanced operations, see [AdvancedOpType].
+/// of three arguments. For more advanced operations, see [AdvancedOpType].
+///
+/// Operations with a single argument (e.g. sin) only use the left side, the right side is a
+/// placeholder. Operations with three arguments (clamp, linspace, slice and reshape) store the first
+/// argument on the left side and the second and third argument as a two element [AST::Vector] on
+/// the right side, e.g. clamp(x, 0, 1) has x on the left and \[0, 1\] on the right.
 /// 
 /// The order of the enum also represents the reverse order of the operation priority.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SimpleOpType { 
+    /// Check if at least one of two conditions is true (a||b). Conditions are scalars where 0 is
+    /// false and everything else is true. The right side is only evaluated if needed
+    Or,
+    /// Check if two conditions are both true (a&&b). Chained comparisons are evaluated as
+    /// multiple comparisons combined by "and" (a<b<c is a<b&&b<c). The middle operand is evaluated
+    /// for each comparison separately, so if it has multiple values (e.g. a<b&c<d), every
+    /// combination of them is evaluated like in a<b&c&&b&c<d
+    And,
+    /// Check if a scalar is less than another scalar (a<b). Comparisons return 1 for true and 0
+    /// for false and can be chained (a<b<c)
+    Less,
+    /// Check if a scalar is greater than another scalar (a>b)
+    Greater,
+    /// Check if a scalar is less than or equal to another scalar (a<=b)
+    LessEq,
+    /// Check if a scalar is greater than or equal to another scalar (a>=b)
+    GreaterEq,
     /// Add two scalars, vectors, or matrices (a+b)
     Add,
     /// Subtract two scalars, vectors, or matrices (a-b)
     Sub,
     /// Add and subtract two scalars, vectors or matrices (a&b)
     AddSub,
+    /// Give a scalar an uncertainty, which is propagated through all following calculations
+    /// (a+-b without whitespace between + and -, or a±b). Only available with the "uncertainty"
+    /// feature
+    #[cfg(feature = "uncertainty")]
+    Uncertainty,
     /// Negate a scalar, vector or matrix or expression in parentheses (-(3*4))
     Neg,
+    /// Negate a condition (!a)
+    Not,
     /// Multiply a scalar, vector or matrix with each other (Dotproduct, Matrix multiplication,
     /// Scalar multiplication, ...) (a*b)
     Mult,
-    /// Divide two scalars or a vector or matrix with a scalar (a/b)
+    /// Divide two scalars or a vector or matrix with a scalar (a/b). Dividing a matrix A by a
+    /// matrix B multiplies A with the inverse of B and dividing a vector b by a matrix A
+    /// multiplies the inverse of A with b.
     Div,
+    /// Left divide a vector or matrix b by a matrix A, which multiplies the inverse of A with b
+    /// (A\b). A "\" followed by the name of a greek letter (e.g. \alpha) starts a name instead.
+    LeftDiv,
     /// Calculate the cross product using "#" (V1#V2), only works with dim(V) <= 3. When dim(V) < 3
     /// the vector gets augmented with zeros
     Cross,
@@ -834,13 +2527,27 @@ pub enum SimpleOpType {
     Pow,
     /// Index into vector using "?" ([3, 4, 5]?1 = 4)
     Get,
+    /// Calculate the factorial of a non-negative integer (n!). Results that are too large to be
+    /// represented return an [Overflow](crate::errors::EvalError::Overflow) error
+    Factorial,
     /// Calculate the sin of a scalar (sin(a))
     Sin,
     /// Calculate the cos of a scalar (cos(a))
     Cos,
     /// Calculate the tan of a scalar (tan(a))
     Tan,
-    /// Calculate the absolute value of a scalar or the length of a vector (abs(a))
+    /// Calculate the cot (1/tan) of a scalar (cot(a)). At its poles the result is inf, even if the
+    /// pole isn't exactly representable (e.g. pi)
+    Cot,
+    /// Calculate the sec (1/cos) of a scalar (sec(a)). At its poles the result is inf, even if the
+    /// pole isn't exactly representable (e.g. pi/2)
+    Sec,
+    /// Calculate the csc (1/sin) of a scalar (csc(a)). At its poles the result is inf, even if the
+    /// pole isn't exactly representable (e.g. pi)
+    Csc,
+    /// Calculate the absolute value of a scalar, the length of a vector or the Frobenius norm (the
+    /// square root of the sum of the squared entries) of a matrix (abs(a)). In all three cases it
+    /// gets printed as |a|
     Abs,
     /// Calculate the square root of a scalar (sqrt(a))
     Sqrt,
@@ -848,34 +2555,201 @@ pub enum SimpleOpType {
     Root,
     /// Calculate the natural log of a scalar (ln(a))
     Ln,
+    /// Calculate the natural log of 1 plus a scalar, accurate for scalars near zero (log1p(a))
+    Log1p,
+    /// Calculate e to the power of a scalar minus 1, accurate for scalars near zero (expm1(a))
+    Expm1,
     /// Calculate the arcsin of a scalar (arcsin(a))
     Arcsin,
     /// Calculate the arccos of a scalar (arccos(a))
     Arccos,
     /// Calculate the arctan of a scalar (arctan(a))
     Arctan, 
+    /// Calculate the inverse hyperbolic sine of a scalar (arsinh(a))
+    Arsinh,
+    /// Calculate the inverse hyperbolic cosine of a scalar a >= 1 (arcosh(a))
+    Arcosh,
+    /// Calculate the inverse hyperbolic tangent of a scalar -1 < a < 1 (artanh(a))
+    Artanh,
+    /// Calculate the spectral radius (the largest absolute eigenvalue) of a square matrix
+    /// (spectral_radius(A)). A spectral radius below 1 means that A^n converges to 0
+    SpectralRadius,
+    /// Calculate the induced 2-norm (the largest singular value) of a matrix (norm2(A)), which is
+    /// the square root of the spectral radius of AᵀA
+    Norm2,
+    /// Calculate the Heaviside step function of a scalar, which is 0 for a<0, 1 for a>0 and 0.5 at 0,
+    /// element-wise for vectors and matrices (heaviside(a))
+    Heaviside,
+    /// Calculate the sign of a scalar, which is -1 for a<0, 1 for a>0 and 0 at 0 (unlike
+    /// f64::signum, which is 1 at 0), element-wise for vectors and matrices (sign(a))
+    Sign,
+    /// Approximate the Dirac delta of a scalar with a normal distribution of the given width (standard
+    /// deviation), element-wise for vectors and matrices (dirac(a, width))
+    Dirac,
+    /// Calculate the determinant of a square matrix (det(A))
+    Det,
+    /// Create the n×n identity matrix (identity(n))
+    Identity,
+    /// Create an m×n matrix filled with zeros (zeros(m, n))
+    Zeros,
+    /// Create an m×n matrix filled with ones (ones(m, n))
+    Ones,
+    /// Create a diagonal matrix with the entries of a vector on its diagonal (diag(v))
+    Diag,
+    /// Create a vector of the integers from a to b (both included) (range(a, b))
+    Range,
+    /// Create a vector of n evenly spaced points from a to b (both included) (linspace(a, b, n))
+    Linspace,
+    /// Calculate at most n coefficients of the continued fraction of a scalar
+    /// (continued_fraction(x, n)). See [continued_fraction_as_latex](crate::latex::continued_fraction_as_latex)
+    /// for rendering them
+    ContinuedFraction,
+    /// Calculate the angle between the positive x-axis and the point (x, y), taking the quadrant of
+    /// the point into account (atan2(y, x))
+    Atan2,
+    /// Calculate the steady state (stationary distribution) of a Markov chain given by its column
+    /// stochastic transition matrix (steady_state(M))
+    SteadyState,
+    /// Calculate the rank (the number of linearly independent rows) of a matrix (rank(M))
+    Rank,
+    /// Bring a matrix into reduced row echelon form (rref(M))
+    Rref,
+    /// Extract the row with the given index of a matrix as a vector (row(M, i))
+    Row,
+    /// Extract the column with the given index of a matrix as a vector (col(M, j))
+    Col,
+    /// Join two vectors end-to-end (concat(a, b))
+    Concat,
+    /// Stack two matrices with the same number of columns on top of each other (vstack(A, B))
+    VStack,
+    /// Place two matrices with the same number of rows next to each other (hstack(A, B))
+    HStack,
+    /// Calculate the outer product of two vectors, which is a matrix (outer(a, b))
+    Outer,
+    /// Calculate the Kronecker product of two matrices, which is the block matrix made up of the
+    /// entries of A multiplied with B (kron(A, B))
+    Kron,
+    /// Extract the entries of a vector from index a up to, but not including, index b (slice(v, a,
+    /// b)). Negative indices count from the end of the vector
+    Slice,
+    /// Arrange the entries of a vector in an m×n matrix (reshape(v, m, n)). The matrix gets filled
+    /// column by column, or row by row with the row-major feature, just like a matrix literal
+    Reshape,
+    /// Calculate the arithmetic mean of the entries of a vector (mean(v))
+    Mean,
+    /// Sum up the entries of a vector (vsum(v))
+    VSum,
+    /// Multiply the entries of a vector (vprod(v))
+    VProd,
+    /// Find the smallest entry of a vector (vmin(v))
+    VMin,
+    /// Find the largest entry of a vector (vmax(v))
+    VMax,
+    /// Find the smaller of two scalars (min(a, b)). For the smallest entry of a vector see [VMin](SimpleOpType::VMin)
+    Min,
+    /// Find the larger of two scalars (max(a, b)). For the largest entry of a vector see [VMax](SimpleOpType::VMax)
+    Max,
+    /// Restrict a scalar x to the range from lo to hi (clamp(x, lo, hi))
+    Clamp,
+    /// Calculate the greatest common divisor of two integers (gcd(a, b))
+    Gcd,
+    /// Calculate the least common multiple of two integers (lcm(a, b))
+    Lcm,
+    /// Calculate the number of ways to choose k out of n elements without order (choose(n, k))
+    Choose,
+    /// Calculate the number of ways to choose k out of n elements with order (perm(n, k))
+    Perm,
+    /// Calculate the population variance of the entries of a vector (var(v)). An optional second
+    /// argument reduces the divisor by the given delta degrees of freedom, so var(v, 1) is the
+    /// sample variance
+    Var,
+    /// Calculate the standard deviation of the entries of a vector, the square root of [SimpleOpType::Var]
+    /// (std(v) for the population and std(v, 1) for the sample standard deviation)
+    Std,
     /// Prioritise expressions in parentheses (3*(5+5))
     Parenths
 }
 
+impl SimpleOpType {
+    /// returns the number of operands of the operation. Operations with a single operand only use
+    /// the left side of the [SimpleOperation](Operation::SimpleOperation), while operations with
+    /// three operands store the second and third one as a vector on the right side.
+    pub(crate) fn arity(&self) -> usize {
+        match self {
+            SimpleOpType::Neg | SimpleOpType::Not | SimpleOpType::Factorial | SimpleOpType::Sin | SimpleOpType::Cos
+            | SimpleOpType::Tan | SimpleOpType::Abs | SimpleOpType::Sqrt | SimpleOpType::Ln | SimpleOpType::Log1p
+            | SimpleOpType::Expm1 | SimpleOpType::Arcsin | SimpleOpType::Arccos | SimpleOpType::Arctan | SimpleOpType::Cot
+            | SimpleOpType::Sec | SimpleOpType::Csc | SimpleOpType::Arsinh | SimpleOpType::Arcosh | SimpleOpType::Artanh
+            | SimpleOpType::SpectralRadius | SimpleOpType::Norm2 | SimpleOpType::Heaviside | SimpleOpType::Sign | SimpleOpType::Det | SimpleOpType::Identity
+            | SimpleOpType::Diag | SimpleOpType::SteadyState | SimpleOpType::Rank | SimpleOpType::Rref | SimpleOpType::Mean
+            | SimpleOpType::VSum | SimpleOpType::VProd | SimpleOpType::VMin | SimpleOpType::VMax | SimpleOpType::Parenths => return 1,
+            SimpleOpType::Or | SimpleOpType::And | SimpleOpType::Less | SimpleOpType::Greater | SimpleOpType::LessEq
+            | SimpleOpType::GreaterEq | SimpleOpType::Add | SimpleOpType::Sub | SimpleOpType::AddSub | SimpleOpType::Mult
+            | SimpleOpType::Div | SimpleOpType::LeftDiv | SimpleOpType::Cross | SimpleOpType::HiddenMult | SimpleOpType::Pow
+            | SimpleOpType::Get | SimpleOpType::Root | SimpleOpType::Dirac | SimpleOpType::Zeros | SimpleOpType::Ones
+            | SimpleOpType::Range | SimpleOpType::ContinuedFraction | SimpleOpType::Atan2 | SimpleOpType::Row | SimpleOpType::Col
+            | SimpleOpType::Concat | SimpleOpType::VStack | SimpleOpType::HStack | SimpleOpType::Outer | SimpleOpType::Kron
+            | SimpleOpType::Min | SimpleOpType::Max | SimpleOpType::Gcd | SimpleOpType::Lcm | SimpleOpType::Choose
+            | SimpleOpType::Perm | SimpleOpType::Var | SimpleOpType::Std => return 2,
+            SimpleOpType::Linspace | SimpleOpType::Clamp | SimpleOpType::Slice | SimpleOpType::Reshape => return 3,
+            #[cfg(feature = "uncertainty")]
+            SimpleOpType::Uncertainty => return 2
+        }
+    }
+}
+
 /// specifies the type of operation for the [AdvancedOperation] struct.
 ///
 /// This enum only contains advanced operations with more than 2 arguments. For simple operations,
 /// see [SimpleOpType].
 #[derive(Clone, Debug, PartialEq)]
 pub enum AdvancedOpType {
-    /// Calculate the derivative of a function f in respect to n at a value m (D(f, n, m))
+    /// Calculate the derivative of a function f in respect to n at a value m (D(f, n, m)). An
+    /// optional fourth argument specifies the order of the derivative (D(f, n, m, k)).
     Derivative,
     /// Calculate the integral of a function f in respect to n with the bounds a and b (I(f, n, a, b))
     Integral,
+    /// Calculate the sum of a function f with the integer index n running from a to b (sum(f, n,
+    /// a, b)). An upper bound of inf sums up to infinity, unless inf is defined as a variable.
+    Sum,
+    /// Calculate the product of a function f with the integer index n running from a to b (prod(f,
+    /// n, a, b))
+    Product,
+    /// Calculate the gradient of a function f in respect to the variables x, y, ... at a point p
+    /// (grad(f, [x, y, ...], p))
+    Gradient,
     /// Solve the given equation(s) in terms of the given variable(s) (eq(eq_1, eq_2, eq_3, ..., x, y,
     /// z, ...))
     Equation,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Scalar,
+    Vector(usize),
+    Matrix(usize, usize)
+}
+
+impl Shape {
+    fn of(value: &Value) -> Shape {
+        match value {
+            Value::Scalar(_) => return Shape::Scalar,
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(..) => return Shape::Scalar,
+            Value::Vector(v) => return Shape::Vector(v.len()),
+            Value::Matrix(m) => return Shape::Matrix(m.len(), m.first().map(|r| r.len()).unwrap_or(0))
+        }
+    }
+}
+
 /// used to specify an operation in a parsed string. It is used together with [AST] to
 /// construct an AST from a mathematical expression.
+///
+/// See [SimpleOpType] for how operations with one or three arguments are stored in a
+/// [SimpleOperation](Operation::SimpleOperation). [AST::visit] and [AST::visit_mut] only visit the
+/// arguments themselves and not the vector holding the second and third argument.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     SimpleOperation {
         op_type: SimpleOpType,
@@ -888,6 +2762,7 @@ pub enum Operation {
 /// used to specify an advanced operation for more complex mathematical operations, such as
 /// functions with more than two inputs and the equation solver.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AdvancedOperation{
     Integral {
         expr: AST,
@@ -898,6 +2773,24 @@ pub enum AdvancedOperation{
     Derivative {
         expr: AST,
         in_terms_of: String,
+        at: AST,
+        order: usize
+    },
+    Sum {
+        expr: AST,
+        in_terms_of: String,
+        lower_bound: AST,
+        upper_bound: AST
+    },
+    Product {
+        expr: AST,
+        in_terms_of: String,
+        lower_bound: AST,
+        upper_bound: AST
+    },
+    Gradient {
+        expr: AST,
+        in_terms_of: Vec<String>,
         at: AST
     },
     Equation {
@@ -0,0 +1,191 @@
+//! a limited arbitrary precision evaluator for scalar expressions (requires the feature `bignum`).
+//!
+//! The regular evaluator works with f64, which limits results to roughly 15 significant digits.
+//! [eval_scalar_bignum] evaluates an already parsed expression using a decimal float with a
+//! configurable number of significant digits instead. It is a separate evaluator, [Value] stays
+//! f64-backed with or without this feature.
+//!
+//! Only scalar expressions built from the basic arithmetic operations, sqrt, root, ln and abs are
+//! supported, everything else returns an [EvalError::MathError] naming the unsupported operation.
+//! The constants of [Context::default] (pi, tau, e and phi) are computed to the requested
+//! precision, as long as they haven't been redefined. Other number literals and context variables
+//! are stored as f64 and converted using their shortest decimal representation, meaning 0.1 is
+//! exactly 0.1.
+//!
+//! # Example
+//!
+//! ```
+//! let res = eval_scalar_bignum(&parse("1/3")?, &Context::empty(), 20)?;
+//!
+//! assert_eq!(res.to_string(), "0.33333333333333333333");
+//! ```
+
+use std::str::FromStr;
+
+use dashu_float::{round::mode::HalfAway, DBig};
+
+use crate::{basetypes::{Operation, SimpleOpType, AST, DEFAULT_CONSTANTS}, errors::EvalError, parser::FUNCTIONS, Context, Value};
+
+/// the number of extra digits used while computing constants, so that rounding errors don't
+/// reach the requested digits.
+const GUARD_DIGITS: usize = 10;
+
+/// evaluates a scalar expression in the given context to the given number of significant digits.
+///
+/// Expressions containing vectors, matrices, functions, multiple results or operations without an
+/// arbitrary precision implementation return an [EvalError::MathError].
+pub fn eval_scalar_bignum(b: &AST, context: &Context, digits: usize) -> Result<DBig, EvalError> {
+    if digits == 0 {
+        return Err(EvalError::MathError("Precision has to be at least one digit!".to_string()));
+    }
+    return eval_rec(b, context, digits);
+}
+
+fn from_f64(x: f64, digits: usize) -> Result<DBig, EvalError> {
+    if !x.is_finite() {
+        return Err(EvalError::NaNOrInf);
+    }
+    let value = DBig::from_str(&x.to_string()).map_err(|_| EvalError::NaNOrInf)?;
+    return Ok(value.with_precision(digits).value());
+}
+
+fn unsupported(name: &str) -> EvalError {
+    return EvalError::MathError(format!("Can't evaluate {} in arbitrary precision! Only +, -, *, /, ^, sqrt, root, ln and abs are supported.", name));
+}
+
+fn one(digits: usize) -> DBig {
+    return DBig::ONE.with_precision(digits).value();
+}
+
+/// calculates atan(1/x) using its taylor series.
+fn atan_inv(x: u32, digits: usize) -> DBig {
+    let x_squared = DBig::from(x*x);
+    let mut power = one(digits)/DBig::from(x);
+    let mut res = power.clone();
+    // every term is smaller than the previous one by a factor of at least x^2
+    let terms = (digits as f64/(2.*(x as f64).log10())).ceil() as u32 + 1;
+    for k in 1..=terms {
+        power /= &x_squared;
+        if k % 2 == 0 {
+            res += &power/DBig::from(2*k+1);
+        } else {
+            res -= &power/DBig::from(2*k+1);
+        }
+    }
+    return res;
+}
+
+/// calculates pi using Machin's formula pi = 16*atan(1/5)-4*atan(1/239).
+fn pi(digits: usize) -> DBig {
+    return DBig::from(16u8)*atan_inv(5, digits)-DBig::from(4u8)*atan_inv(239, digits);
+}
+
+/// calculates a constant of [Context::default] to the given number of significant digits.
+fn constant(name: &str, digits: usize) -> Option<DBig> {
+    let working = digits+GUARD_DIGITS;
+    let value = match name {
+        "pi" => pi(working),
+        "tau" => DBig::from(2u8)*pi(working),
+        "e" => one(working).exp(),
+        "phi" => (one(working)+dashu_float::Context::<HalfAway>::new(working).sqrt(DBig::from(5u8).repr()).value())/DBig::from(2u8),
+        _ => return None
+    };
+    return Some(value.with_precision(digits).value());
+}
+
+fn eval_rec(b: &AST, context: &Context, digits: usize) -> Result<DBig, EvalError> {
+    match b {
+        AST::Scalar(s) => return from_f64(*s, digits),
+        AST::Variable(v) => {
+            let var = match context.get_var(v) {
+                Some(var) => var,
+                None => return Err(EvalError::NoVariable(v.to_string()))
+            };
+            match (var.values.len(), var.values.get(0)) {
+                (1, Some(Value::Scalar(s))) => {
+                    if DEFAULT_CONSTANTS.iter().any(|(name, value)| name == v && value == s) {
+                        if let Some(c) = constant(v, digits) {
+                            return Ok(c);
+                        }
+                    }
+                    return from_f64(*s, digits);
+                },
+                _ => return Err(EvalError::MathError(format!("Variable {} has to hold a single scalar!", v)))
+            }
+        },
+        AST::Operation(o) => {
+            let (op_type, left, right) = match &**o {
+                Operation::SimpleOperation { op_type, left, right } => (op_type, left, right),
+                Operation::AdvancedOperation(_) => return Err(unsupported("Advanced operations"))
+            };
+            let lv = eval_rec(left, context, digits)?;
+            match op_type {
+                SimpleOpType::Parenths => return Ok(lv),
+                SimpleOpType::Neg => return Ok(-lv),
+                SimpleOpType::Abs => {
+                    if lv < DBig::ZERO {
+                        return Ok(-lv);
+                    }
+                    return Ok(lv);
+                },
+                SimpleOpType::Sqrt => {
+                    if lv < DBig::ZERO {
+                        return Err(EvalError::MathError("Can't take the square root of a negative number!".to_string()));
+                    }
+                    return Ok(dashu_float::Context::<HalfAway>::new(digits).sqrt(lv.repr()).value());
+                },
+                SimpleOpType::Ln => {
+                    if lv <= DBig::ZERO {
+                        return Err(EvalError::MathError("Can't take the logarithm of a non-positive number!".to_string()));
+                    }
+                    return Ok(lv.ln());
+                },
+                _ => {}
+            }
+            let rv = eval_rec(right, context, digits)?;
+            match op_type {
+                SimpleOpType::Add => return Ok(lv + rv),
+                SimpleOpType::Sub => return Ok(lv - rv),
+                SimpleOpType::Mult | SimpleOpType::HiddenMult => return Ok(lv * rv),
+                SimpleOpType::Div => {
+                    if rv == DBig::ZERO {
+                        return Err(EvalError::MathError("Can't divide by zero!".to_string()));
+                    }
+                    return Ok(lv / rv);
+                },
+                SimpleOpType::Pow => return pow(lv, rv),
+                SimpleOpType::Root => {
+                    if rv == DBig::ZERO {
+                        return Err(EvalError::MathError("Can't take the zeroth root!".to_string()));
+                    }
+                    let one = from_f64(1., digits)?;
+                    return pow(lv, one / rv);
+                },
+                _ => {
+                    let name = match FUNCTIONS.iter().find(|f| f.1 == *op_type) {
+                        Some(f) => f.0.to_string(),
+                        None => format!("{:?}", op_type)
+                    };
+                    return Err(unsupported(&name));
+                }
+            }
+        },
+        _ => return Err(unsupported("Vectors, matrices, lists and functions"))
+    }
+}
+
+fn pow(base: DBig, exp: DBig) -> Result<DBig, EvalError> {
+    if base == DBig::ZERO && exp < DBig::ZERO {
+        return Err(EvalError::MathError("Can't raise zero to a negative power!".to_string()));
+    }
+    if exp.repr().is_int() {
+        return Ok(base.powi(exp.to_int().value()));
+    }
+    if base < DBig::ZERO {
+        return Err(EvalError::MathError("Can't raise a negative number to a non-integer power!".to_string()));
+    }
+    if base == DBig::ZERO {
+        return Ok(base);
+    }
+    return Ok(base.powf(&exp));
+}
@@ -0,0 +1,42 @@
+//! a serializable record of a complete computation (requires the feature `serde`).
+//!
+//! A [Computation] bundles the input expression, its parsed [AST], the [Context] it has been
+//! evaluated in and the results, so e.g. a web frontend receives everything it needs to display a
+//! calculation in a single payload.
+//!
+//! Keep in mind that some formats can't represent every f64, e.g. JSON has no representation for
+//! inf or NaN.
+//!
+//! # Example
+//!
+//! ```
+//! let computation = Computation::from_eval("3x", &Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(2.)])]))?;
+//! let json = serde_json::to_string(&computation)?;
+//! ```
+
+use crate::{basetypes::AST, errors::QuickEvalError, parse_with_aliases, eval, Context, Values};
+
+/// describes a complete computation: the expression, the parsed AST, the context used and the
+/// evaluated results.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Computation {
+    pub expr: String,
+    pub ast: AST,
+    pub context: Context,
+    pub results: Values
+}
+
+impl Computation {
+    /// parses and evaluates the given expression in the given context like
+    /// [quick_eval](crate::quick_eval), keeping the AST and a copy of the context.
+    pub fn from_eval<S: Into<String>>(expr: S, context: &Context) -> Result<Computation, QuickEvalError> {
+        if !context.duplicate_names().is_empty() {
+            return Err(QuickEvalError::DuplicateVars);
+        }
+        let expr = expr.into();
+        let ast = parse_with_aliases(expr.clone(), &context.aliases)?;
+        let results = eval(&ast, context)?;
+
+        return Ok(Computation { expr, ast, context: context.clone(), results });
+    }
+}
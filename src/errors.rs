@@ -1,4 +1,4 @@
-use std::fmt::{self, Display};
+use std::{error::Error, fmt::{self, Display}};
 
 /// provides an enum with the corresponding From implementations in order to use as a convenient return
 /// error type for this library.
@@ -26,6 +26,25 @@ impl MathLibError {
     }
 }
 
+impl Display for MathLibError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_reason())
+    }
+}
+
+impl Error for MathLibError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MathLibError::ParserError(e) => return Some(e),
+            MathLibError::EvalError(e) => return Some(e),
+            MathLibError::QuickEvalError(e) => return Some(e),
+            #[cfg(feature = "output")]
+            MathLibError::LatexError(e) => return Some(e),
+            MathLibError::Other(_) => return None,
+        }
+    }
+}
+
 impl From<ParserError> for MathLibError {
     fn from(value: ParserError) -> Self {
         MathLibError::ParserError(value)
@@ -66,6 +85,8 @@ pub enum ParserError {
     InvalidVariableName(String),
     InvalidFunctionName(String),
     WrongNumberOfArgs(String),
+    AmbiguousNumber(String),
+    NotALiteral(String),
 }
 
 impl ParserError {
@@ -84,6 +105,8 @@ impl ParserError {
             ParserError::InvalidVariableName(s) => return format!("Found invalid variable name: {}!", s),
             ParserError::InvalidFunctionName(s) => return format!("Found invalid function name: {}!", s),
             ParserError::WrongNumberOfArgs(s) => return format!("Wrong number of arguments for {} operation!", s),
+            ParserError::AmbiguousNumber(s) => return format!("Found ambiguous number {}! Numbers can't be separated by whitespace, for mixed numbers use an explicit sum like 1+1/2.", s),
+            ParserError::NotALiteral(s) => return format!("{} is not a literal scalar, vector or matrix!", s),
         }
     } 
 }
@@ -94,6 +117,33 @@ impl Display for ParserError {
     }
 }
 
+impl Error for ParserError {}
+
+/// describes an interpretation chosen by the parser for an ambiguous input (see
+/// [parse_with_warnings](fn@crate::parser::parse_with_warnings)).
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParserWarning {
+    /// a hidden multiplication (e.g. 2x) together with its interpretation (2*x).
+    HiddenMult(String, String),
+    /// a name made up of multiple letters (e.g. xy), which is read as a single variable.
+    MultiLetterVariable(String),
+}
+
+impl ParserWarning {
+    pub fn get_reason(&self) -> String {
+        match self {
+            ParserWarning::HiddenMult(s, i) => return format!("Interpreted {} as {}!", s, i),
+            ParserWarning::MultiLetterVariable(s) => return format!("Interpreted {} as a single variable! If you meant a product, write it explicitly: {}.", s, s.chars().map(|c| c.to_string()).collect::<Vec<String>>().join("*")),
+        }
+    }
+}
+
+impl Display for ParserWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_reason())
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum EvalError {
     NonScalarInVector,
@@ -101,6 +151,8 @@ pub enum EvalError {
     RecursiveFunction,
     VectorInEq,
     MatrixInEq,
+    #[cfg(feature = "uncertainty")]
+    UncertainInEq,
     NothingToDoEq,
     UnderdeterminedSystem,
     InfiniteSolutions,
@@ -110,6 +162,8 @@ pub enum EvalError {
     NoVariable(String),
     NoFunction(String),
     WrongNumberOfArgs((usize, usize)),
+    Overflow(String),
+    Cancelled,
     MathError(String),
 }
 
@@ -121,6 +175,8 @@ impl EvalError {
             EvalError::NonScalarInMatrix => return "Matrices can only contain scalars!".to_string(),
             EvalError::VectorInEq => return "Can't have vectors in equations! Please convert your equation into a system of equations!".to_string(),
             EvalError::MatrixInEq => return "Can't have matrices in equations!".to_string(),
+            #[cfg(feature = "uncertainty")]
+            EvalError::UncertainInEq => return "Can't have uncertain values in equations!".to_string(),
             EvalError::NothingToDoEq => return "Nothing to do!".to_string(),
             EvalError::UnderdeterminedSystem => return "Underdetermined system of equations!".to_string(),
             EvalError::InfiniteSolutions => return "Infinite Solutions!".to_string(),
@@ -130,6 +186,8 @@ impl EvalError {
             EvalError::NoVariable(s) => return format!("Could not find variable {}!", s),
             EvalError::NoFunction(s) => return format!("Could not find function {}!", s),
             EvalError::WrongNumberOfArgs((e, g)) => return format!("Wrong number of arguments! Expected {} arguments, {} were given!", e, g),
+            EvalError::Overflow(s) => return format!("The result of {} is too large to be represented!", s),
+            EvalError::Cancelled => return "The computation has been cancelled!".to_string(),
             EvalError::MathError(s) => return s.to_string(),
         }
     }
@@ -141,6 +199,8 @@ impl Display for EvalError {
     }
 }
 
+impl Error for EvalError {}
+
 impl From<String> for EvalError {
     fn from(value: String) -> Self {
         EvalError::MathError(value)
@@ -149,6 +209,8 @@ impl From<String> for EvalError {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum QuickEvalError {
+    /// a variable or function has been defined multiple times in the context (see
+    /// [Context::duplicate_names](crate::Context::duplicate_names) for the names)
     DuplicateVars,
     ParserError(ParserError),
     EvalError(EvalError)
@@ -157,7 +219,7 @@ pub enum QuickEvalError {
 impl QuickEvalError {
     pub fn get_reason(&self) -> String {
         match self {
-            QuickEvalError::DuplicateVars => return "Can't specify e and pi twice!".to_string(),
+            QuickEvalError::DuplicateVars => return "Can't specify a variable or function twice!".to_string(),
             QuickEvalError::EvalError(e) => return e.get_reason(),
             QuickEvalError::ParserError(e) => return e.get_reason()
         }
@@ -170,6 +232,16 @@ impl Display for QuickEvalError {
     }
 }
 
+impl Error for QuickEvalError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            QuickEvalError::DuplicateVars => return None,
+            QuickEvalError::ParserError(e) => return Some(e),
+            QuickEvalError::EvalError(e) => return Some(e)
+        }
+    }
+}
+
 impl From<EvalError> for QuickEvalError {
     fn from(value: EvalError) -> Self {
         QuickEvalError::EvalError(value)
@@ -201,6 +273,16 @@ impl LatexError {
     }
 }
 
+#[cfg(feature = "output")]
+impl Display for LatexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_reason())
+    }
+}
+
+#[cfg(feature = "output")]
+impl Error for LatexError {}
+
 #[cfg(feature = "output")]
 impl From<mathjax_svg::Error> for LatexError {
     fn from(value: mathjax_svg::Error) -> Self {
@@ -1,4 +1,6 @@
-use crate::PREC;
+use std::ops::ControlFlow;
+
+use crate::{basetypes::NumberFormat, errors::EvalError, PREC};
 
 #[doc(hidden)]
 pub fn center_in_string(f: String, n: i32) -> String {
@@ -33,13 +35,19 @@ pub fn center_in_string(f: String, n: i32) -> String {
 
 #[doc(hidden)]
 pub fn round_and_format(x: f64, latex: bool) -> String {
-    if (x*10f64.powi(PREC as i32-2)).round()/10f64.powi(PREC as i32-2) == 0. && !latex && x != 0. {
+    return round_and_format_prec(x, latex, PREC-2);
+}
+
+#[doc(hidden)]
+pub fn round_and_format_prec(x: f64, latex: bool, digits: usize) -> String {
+    let digits = digits as i32;
+    if (x*10f64.powi(digits)).round()/10f64.powi(digits) == 0. && !latex && x != 0. {
         let mut scientific = format!("{:+e}", x);
         if scientific.chars().nth(0).unwrap() == '+' {
             scientific = scientific[1..].to_string();
         }
         return scientific;
-    } else if (x*10f64.powi(PREC as i32-2)).round()/10f64.powi(PREC as i32-2) == 0. && x != 0. {
+    } else if (x*10f64.powi(digits)).round()/10f64.powi(digits) == 0. && x != 0. {
         let mut scientific = format!("{:+e}", x);
         if scientific.chars().nth(0).unwrap() == '+' {
             scientific = scientific[1..].to_string();
@@ -48,7 +56,7 @@ pub fn round_and_format(x: f64, latex: bool) -> String {
         let right = scientific.split("e").nth(1).unwrap();
         return format!("{}\\cdot 10^{{{}}}", left, right);
     } else {
-        let rounded = (x*10f64.powi(PREC as i32-2)).round()/10f64.powi(PREC as i32-2);
+        let rounded = (x*10f64.powi(digits)).round()/10f64.powi(digits);
         let rounded_string;
         if rounded == 0. && rounded.to_string().len() > 1 {
             rounded_string = rounded.to_string()[1..].to_string();
@@ -59,6 +67,66 @@ pub fn round_and_format(x: f64, latex: bool) -> String {
     }
 }
 
+#[doc(hidden)]
+pub fn round_and_format_with(x: f64, latex: bool, format: NumberFormat) -> String {
+    return round_and_format_with_prec(x, latex, format, PREC-2);
+}
+
+#[doc(hidden)]
+pub fn round_and_format_with_prec(x: f64, latex: bool, format: NumberFormat, digits: usize) -> String {
+    match format {
+        NumberFormat::Default => return round_and_format_prec(x, latex, digits),
+        NumberFormat::SiPrefix => return round_and_format_si(x, latex, digits)
+    }
+}
+
+const SI_PREFIXES: [(&str, &str); 17] = [
+    ("y", "y"), ("z", "z"), ("a", "a"), ("f", "f"), ("p", "p"), ("n", "n"), ("µ", "\\mu"), ("m", "m"), ("", ""),
+    ("k", "k"), ("M", "M"), ("G", "G"), ("T", "T"), ("P", "P"), ("E", "E"), ("Z", "Z"), ("Y", "Y")
+];
+
+fn round_and_format_si(x: f64, latex: bool, digits: usize) -> String {
+    if x == 0. || !x.is_finite() {
+        return round_and_format_prec(x, latex, digits);
+    }
+
+    let mut exponent = (x.abs().log10()/3.).floor() as i32;
+    if !(-8..=8).contains(&exponent) {
+        return round_and_format_prec(x, latex, digits);
+    }
+
+    let round = |m: f64| (m*10f64.powi(digits as i32)).round()/10f64.powi(digits as i32);
+    let mut mantissa = round(x/10f64.powi(exponent*3));
+    // rounding might push the mantissa up to the next prefix, e.g. 999.9999999999999 -> 1000
+    if mantissa.abs() >= 1000. && exponent < 8 {
+        exponent += 1;
+        mantissa = round(x/10f64.powi(exponent*3));
+    }
+
+    let prefix = SI_PREFIXES[(exponent+8) as usize];
+    if latex {
+        if prefix.1.is_empty() {
+            return mantissa.to_string();
+        }
+        return format!("{}\\,\\mathrm{{{}}}", mantissa, prefix.1);
+    }
+    return format!("{}{}", mantissa, prefix.0);
+}
+
+#[doc(hidden)]
+pub fn split_solution_index(name: &str) -> Option<(&str, usize)> {
+    let (base, index) = name.rsplit_once('_')?;
+    let index = index.strip_prefix('{').and_then(|i| i.strip_suffix('}')).unwrap_or(index);
+    if base.is_empty() || index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    return Some((base, index.parse().ok()?));
+}
+
+/// builds the cartesian product of the given inputs in lexicographic order: the first input varies
+/// slowest and the last input varies fastest, e.g. [[1, 2], [3, 4]] results in [1, 3], [1, 4],
+/// [2, 3], [2, 4]. The evaluator relies on this order for all multi-valued results (see
+/// [eval](crate::eval)), so it must not change.
 #[doc(hidden)]
 pub fn cart_prod<T: Clone>(arr: &Vec<Vec<T>>) -> Vec<Vec<T>> {
     let mut results: Vec<Vec<T>> = vec![vec![]];
@@ -98,3 +166,17 @@ pub fn get_args(chars: &[char]) -> Vec<String> {
     args.push(buffer);
     args
 }
+
+#[doc(hidden)]
+pub fn report_progress(progress: Option<&dyn Fn(f64) -> ControlFlow<()>>, done: usize, total: usize) -> Result<(), EvalError> {
+    if let Some(p) = progress {
+        // only report about every percent to keep the overhead of the callback low
+        let interval = (total/100).max(1);
+        if done % interval == 0 || done == total {
+            if p(done as f64/total as f64).is_break() {
+                return Err(EvalError::Cancelled);
+            }
+        }
+    }
+    return Ok(());
+}
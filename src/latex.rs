@@ -1,13 +1,14 @@
 #[cfg(feature = "output")]
 use crate::errors::LatexError;
 
-use crate::{basetypes::AST, Values};
+use crate::{basetypes::AST, Value, Values, PREC};
 
 #[cfg(feature = "output")]
 /// converts the given latex string to a png image with the given height in pixels, returned as its raw bytes. 
-/// This function allows for a change of line color. The line color is defined by a hex string
-/// e.g. "#FFFFFF". The background is always transparent.
-pub fn png_from_latex<S: Into<String>>(latex: String, height: u32, line_color: S) -> Result<Vec<u8>, LatexError> {
+/// This function allows for a change of line color and background color. Both are defined by a hex
+/// string e.g. "#FFFFFF" (optionally with an alpha channel e.g. "#FFFFFF80"). If no background color
+/// is given, the background is transparent.
+pub fn png_from_latex<S: Into<String>>(latex: String, height: u32, line_color: S, background_color: Option<&str>) -> Result<Vec<u8>, LatexError> {
     use resvg::{render, tiny_skia::Pixmap, usvg::{Options, Transform, Tree}};
 
     let svg = svg_from_latex(latex, line_color)?;
@@ -20,11 +21,30 @@ pub fn png_from_latex<S: Into<String>>(latex: String, height: u32, line_color: S
 
     let mut pixmap = Pixmap::new(dest_width as u32, height as u32).unwrap();
 
+    if let Some(c) = background_color {
+        pixmap.fill(color_from_hex(c)?);
+    }
+
     render(&tree, Transform::from_row(width_scale, 0., 0., height_scale, 0., 0.), &mut pixmap.as_mut());
 
     Ok(pixmap.encode_png().ok().unwrap())
 }
 
+#[cfg(feature = "output")]
+fn color_from_hex(hex: &str) -> Result<resvg::tiny_skia::Color, LatexError> {
+    let invalid = || LatexError::LatexToImageError(format!("Invalid color {}", hex));
+    let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+    if (digits.len() != 6 && digits.len() != 8) || !digits.is_ascii() {
+        return Err(invalid());
+    }
+    let mut channels = vec![];
+    for i in (0..digits.len()).step_by(2) {
+        channels.push(u8::from_str_radix(&digits[i..i+2], 16).map_err(|_| invalid())?);
+    }
+    let alpha = channels.get(3).copied().unwrap_or(255);
+    return Ok(resvg::tiny_skia::Color::from_rgba8(channels[0], channels[1], channels[2], alpha));
+}
+
 #[cfg(feature = "output")]
 /// converts the given latex string to an svg string. The function also takes a line color, which
 /// is given as a hex string e.g. "#FFFFFF".
@@ -43,12 +63,15 @@ pub fn svg_from_latex<S: Into<String>>(latex: String, line_color: S) -> Result<S
 /// - Calculation, specified by the AST of the calculation, its results and a possible variable name in which it is saved.
 /// - Function declaration, specified by the AST, the names of the input variables and the name of the
 /// function.
+/// - System of equations, specified by the left and right hand sides of the equations, the
+/// solutions and a possible variable name in which they are saved.
 ///
 /// # Example
 /// ```
 /// let step = Step::Calc { term: parsed_expr, result: res, variable_save: Some("x".to_string()) };
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Step {
     Calc{
         term: AST,
@@ -59,6 +82,11 @@ pub enum Step {
         term: AST,
         inputs: Vec<String>,
         name: String
+    },
+    Equ{
+        eqs: Vec<(AST, AST)>,
+        results: Values,
+        variable_save: Option<String>
     }
 }
 
@@ -87,11 +115,19 @@ impl Step {
             }, 
             Step::Fun{term, inputs, name} => {
                 return term.as_latex_at_fun(name, inputs.iter().collect(), true) + &format!(" \\tag{{{}}}\\label{{eq:{}}} \\\\ \\\\ \n", equation_number, equation_number);
+            },
+            Step::Equ{eqs, results, variable_save} => {
+                return Step::equ_as_aligned_latex(eqs, results, variable_save, PREC-2) + &format!(" \\tag{{{}}}\\label{{eq:{}}} \\\\ \\\\ \n", equation_number, equation_number);
             }
         }
     }
     /// converts a step to latex. This function also adds a "&" aligner before the "=".
     pub fn as_latex(&self) -> String {
+        return self.as_latex_with_precision(PREC-2);
+    }
+    /// converts a step to latex like [Step::as_latex], but rounds the results to the given number
+    /// of decimal places instead of PREC-2.
+    pub fn as_latex_with_precision(&self, digits: usize) -> String {
         match self {
             Step::Calc{term, result, variable_save} => {
                 let mut aligner = "&";
@@ -101,7 +137,7 @@ impl Step {
                     aligner = "";
                 }
                 let expression = term.as_latex();
-                let res = result.as_latex();
+                let res = result.as_latex_with_precision(digits);
 
                 if expression != res {
                     latex += &format!("{} {}= {}", expression, aligner, res);
@@ -111,7 +147,8 @@ impl Step {
 
                 return latex;
             },
-            Step::Fun{term, inputs, name} => return term.as_latex_at_fun(name, inputs.iter().collect(), true)
+            Step::Fun{term, inputs, name} => return term.as_latex_at_fun(name, inputs.iter().collect(), true),
+            Step::Equ{eqs, results, variable_save} => return Step::equ_as_aligned_latex(eqs, results, variable_save, digits)
         }
     }
     /// converts a step to inline latex (without the "&" aligner).
@@ -133,21 +170,132 @@ impl Step {
 
                 return latex;
             },
-            Step::Fun{term, inputs, name} => return term.as_latex_at_fun(name, inputs.iter().collect(), true)
+            Step::Fun{term, inputs, name} => return term.as_latex_at_fun(name, inputs.iter().collect(), true),
+            Step::Equ{eqs, results, variable_save} => {
+                let eqs: Vec<String> = eqs.iter().map(|e| format!("{} = {}", e.0.as_latex(), e.1.as_latex())).collect();
+                let res = match variable_save {
+                    Some(v) => results.as_latex_at_var(v, false),
+                    None => results.as_latex()
+                };
+
+                return format!("\\begin{{cases}}{}\\end{{cases}} \\Rightarrow {}", eqs.join(" \\\\ "), res);
+            }
+        }
+    }
+    /// converts a step to presentation MathML, following the layout of [Step::as_latex_inline].
+    pub fn as_mathml(&self) -> String {
+        let body = match self {
+            Step::Calc{term, result, variable_save} => {
+                let mut mathml = "".to_string();
+                if let Some(v) = variable_save {
+                    mathml += &format!("{}<mo>=</mo>", AST::Variable(v.to_string()).mathml_print());
+                }
+                let expression = term.mathml_print();
+                let res = result.mathml_print();
+
+                if expression != res {
+                    mathml += &format!("{}<mo>=</mo>{}", expression, res);
+                } else {
+                    mathml += &expression;
+                }
+
+                mathml
+            },
+            Step::Fun{term, inputs, name} => {
+                let fun = AST::Function { name: name.to_string(), inputs: Box::new(inputs.iter().map(|i| AST::Variable(i.to_string())).collect()) };
+                format!("{}<mo>=</mo>{}", fun.mathml_print(), term.mathml_print())
+            },
+            Step::Equ{eqs, results, variable_save} => {
+                let rows: Vec<String> = eqs.iter().map(|e| format!("<mtr><mtd><mrow>{}<mo>=</mo>{}</mrow></mtd></mtr>", e.0.mathml_print(), e.1.mathml_print())).collect();
+                let res = match variable_save {
+                    Some(v) => format!("{}<mo>=</mo>{}", AST::Variable(v.to_string()).mathml_print(), results.mathml_print()),
+                    None => results.mathml_print()
+                };
+
+                format!("<mrow><mo>{{</mo><mtable columnalign=\"left\">{}</mtable></mrow><mo>&#x21D2;</mo>{}", rows.join(""), res)
+            }
+        };
+
+        return format!("<math><mrow>{}</mrow></math>", body);
+    }
+    /// renders every equation on its own line aligned on the "=" with the solutions below.
+    fn equ_as_aligned_latex(eqs: &[(AST, AST)], results: &Values, variable_save: &Option<String>, digits: usize) -> String {
+        let mut lines: Vec<String> = eqs.iter().map(|e| format!("{} &= {}", e.0.as_latex(), e.1.as_latex())).collect();
+        match variable_save {
+            Some(v) => lines.push(format!("\\Rightarrow {}", results.as_latex_at_var_with_precision(v, true, digits))),
+            None => lines.push(format!("&\\Rightarrow {}", results.as_latex_with_precision(digits)))
         }
+
+        return lines.join(" \\\\ \n");
+    }
+}
+
+/// renders the coefficients of a continued fraction (e.g. the result of continued_fraction(x, n))
+/// as nested fractions using amsmath's \cfrac, e.g. [1, 2, 3] as 1+\cfrac{1}{2+\cfrac{1}{3}}.
+///
+/// # Example
+///
+/// ```
+/// let latex = continued_fraction_as_latex(&[1., 2., 3.]);
+///
+/// assert_eq!(latex, "1+\\cfrac{1}{2+\\cfrac{1}{3}}");
+/// ```
+pub fn continued_fraction_as_latex(coefficients: &[f64]) -> String {
+    let mut latex = match coefficients.last() {
+        Some(c) => Value::Scalar(*c).as_latex(),
+        None => return "".to_string()
+    };
+    for c in coefficients[..coefficients.len()-1].iter().rev() {
+        latex = format!("{}+\\cfrac{{1}}{{{}}}", Value::Scalar(*c).as_latex(), latex);
     }
+
+    return latex;
 }
 
-/// describes the type of export done by the [export()] function:
+/// describes the type of export done by the [export_history()] function:
 ///
-/// - Pdf: Save as a pdf file.
+/// - Pdf: Save as a pdf file, compiled in-process using tectonic.
+/// - PdfWith: Save as a pdf file, compiled using an external pdflatex compatible binary. The binary
+/// is given either as a path or as a name that is looked up in the PATH (e.g. "pdflatex").
 /// - Tex: Save as the generated .tex file.
 #[cfg(feature = "output")]
 pub enum ExportType {
     Pdf,
+    PdfWith(String),
     Tex
 }
 
+/// compiles the given latex document to a pdf using an external pdflatex compatible binary (e.g.
+/// "pdflatex", "xelatex" or "lualatex"), returning the raw bytes of the pdf. The binary is given
+/// either as a path or as a name that is looked up in the PATH.
+#[cfg(feature = "output")]
+pub fn pdf_from_latex_with<S: Into<String>>(latex: String, binary: S) -> Result<Vec<u8>, LatexError> {
+    use std::{fs, process::Command, time::{SystemTime, UNIX_EPOCH}};
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("math_utils_lib_{}_{}", std::process::id(), nanos));
+    fs::create_dir_all(&dir).map_err(|e| LatexError::LatexToPdfError(e.to_string()))?;
+
+    let result = (|| {
+        fs::write(dir.join("export.tex"), latex).map_err(|e| LatexError::LatexToPdfError(e.to_string()))?;
+
+        let output = Command::new(binary.into())
+            .current_dir(&dir)
+            .args(["-interaction=nonstopmode", "-halt-on-error", "export.tex"])
+            .output()
+            .map_err(|e| LatexError::LatexToPdfError(e.to_string()))?;
+        if !output.status.success() {
+            return Err(LatexError::LatexToPdfError(String::from_utf8_lossy(&output.stdout).to_string()));
+        }
+
+        return fs::read(dir.join("export.pdf")).map_err(|e| LatexError::LatexToPdfError(e.to_string()));
+    })();
+
+    let _ = fs::remove_dir_all(&dir);
+
+    return result;
+}
+
 /// exports a history of [Step] to a file named <file_name> with the file type defined
 /// by export_type (see [ExportType] for further details).
 #[cfg(feature = "output")]
@@ -163,6 +311,9 @@ pub fn export_history(history: Vec<Step>, export_type: ExportType) -> Result<Vec
             let pdf = tectonic::latex_to_pdf(output_string)?;
             return Ok(pdf.to_vec());
         },
+        ExportType::PdfWith(binary) => {
+            return pdf_from_latex_with(output_string, binary);
+        },
         ExportType::Tex => {
             return Ok(output_string.into_bytes());
         },
@@ -30,6 +30,23 @@ doc = "**Doc images not enabled**. Compile with feature `doc-images` and Rust ve
 //!
 //! If you want to use "?", take a look at [MathLibError].
 //!
+//! Parsing and evaluating are separate stages with their own error types: [parse()] returns a
+//! [ParserError](errors::ParserError) and [eval()] returns an [EvalError](errors::EvalError). If an
+//! expression is evaluated more than once, parse it once and evaluate the AST as often as needed,
+//! handling each error type where it can occur:
+//!
+//! ```rust
+//! let ast = parse("3x")?; // ParserError
+//!
+//! for i in 0..3 {
+//!     let x = Variable::new("x", vec![Value::Scalar(i as f64)]);
+//!     let res = eval(&ast, &Context::from_vars(vec![x]))?; // EvalError
+//! }
+//! ```
+//!
+//! [quick_eval()] combines both stages and returns a [QuickEvalError], while [quick_eval_unified()]
+//! returns a [MathLibError] with the parser and evaluation errors as separate variants.
+//!
 //! ## Examples
 //! ```rust
 //! let res = quick_eval("3*3", &Context::empty())?.to_vec();
@@ -79,7 +96,7 @@ doc = "**Doc images not enabled**. Compile with feature `doc-images` and Rust ve
 //!
 //! let step = Step::Calc { term: parsed_expr, result: res, variable_save: Some("x".to_string()) };
 //!
-//! let png = png_from_latex(step.as_latex_inline(), 200, "#FFFFFF")?;
+//! let png = png_from_latex(step.as_latex_inline(), 200, "#FFFFFF", None)?;
 //! ```
 //!
 //! Output (Please turn on dark mode to view the image, as the background is transparent):
@@ -117,23 +134,37 @@ pub mod parser;
 pub mod errors;
 pub mod roots;
 pub mod solver;
+pub mod session;
+#[cfg(feature = "bignum")]
+pub mod bignum;
+#[cfg(feature = "serde")]
+pub mod computation;
 
 #[cfg(test)]
 mod tests;
 
-pub use basetypes::{Value, Values, Variable, Context};
+pub use basetypes::{Value, Values, Variable, Context, AngleMode};
 pub use latex::Step;
 #[cfg(feature = "output")]
-pub use latex::{export_history, ExportType, svg_from_latex, png_from_latex};
-pub use parser::{parse, eval};
+pub use latex::{export_history, ExportType, svg_from_latex, png_from_latex, pdf_from_latex_with};
+pub use parser::{parse, parse_with_warnings, parse_with_aliases, normalize_decimal_separator, eval, eval_with_stats, eval_many, compile};
 pub use errors::MathLibError;
+pub use session::Session;
+#[cfg(feature = "bignum")]
+pub use bignum::eval_scalar_bignum;
+#[cfg(feature = "serde")]
+pub use computation::Computation;
 
 #[cfg(feature = "high-prec")]
-/// defines the precision used by the equation solver and the printing precision, which is PREC-2.
+/// defines the default precision used by the equation solver and the default printing precision,
+/// which is PREC-2. Both can be set independently using [RootFinder::with_precision](roots::RootFinder::with_precision)
+/// and [Value::display_with_precision].
 pub const PREC: usize = 13;
 
 #[cfg(not(feature = "high-prec"))]
-/// defines the precision used by the equation solver and the printing precision, which is PREC - 2.
+/// defines the default precision used by the equation solver and the default printing precision,
+/// which is PREC - 2. Both can be set independently using [RootFinder::with_precision](roots::RootFinder::with_precision)
+/// and [Value::display_with_precision].
 pub const PREC: usize = 8;
 
 /// evaluates a given expression in the given context. If you just want the AST, have a look at [parse()].
@@ -142,6 +173,9 @@ pub const PREC: usize = 8;
 /// the possible operations, take a look at [SimpleOpType](basetypes::SimpleOpType) and
 /// [AdvancedOpType](basetypes::AdvancedOpType).
 ///
+/// A context that defines the same variable or function name twice is rejected with
+/// [QuickEvalError::DuplicateVars], as only the first definition would be used.
+///
 /// # Examples
 ///
 /// ```
@@ -157,7 +191,31 @@ pub const PREC: usize = 8;
 /// assert_eq!(res, vec![Value::Scalar(9.)]);
 /// ```
 pub fn quick_eval<S: Into<String>>(expr: S, context: &Context) -> Result<Values, QuickEvalError> {
+    if !context.duplicate_names().is_empty() {
+        return Err(QuickEvalError::DuplicateVars);
+    }
     let expr = expr.into();
-    let b_tree = parse(expr)?; 
+    let b_tree = parse_with_aliases(expr, &context.aliases)?; 
     Ok(eval(&b_tree, &context)?)
 }
+
+/// evaluates a given expression in the given context like [quick_eval()], but returns a
+/// [MathLibError] instead of a [QuickEvalError]. Parser errors are returned as
+/// [MathLibError::ParserError], evaluation errors as [MathLibError::EvalError] and duplicate
+/// definitions in the context as [MathLibError::QuickEvalError].
+///
+/// # Example
+///
+/// ```
+/// let res = quick_eval_unified("3*", &Context::empty());
+///
+/// assert!(matches!(res, Err(MathLibError::ParserError(_))));
+/// ```
+pub fn quick_eval_unified<S: Into<String>>(expr: S, context: &Context) -> Result<Values, MathLibError> {
+    match quick_eval(expr, context) {
+        Ok(v) => return Ok(v),
+        Err(QuickEvalError::ParserError(e)) => return Err(MathLibError::ParserError(e)),
+        Err(QuickEvalError::EvalError(e)) => return Err(MathLibError::EvalError(e)),
+        Err(e) => return Err(MathLibError::QuickEvalError(e))
+    }
+}
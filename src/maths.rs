@@ -1,13 +1,19 @@
-use crate::basetypes::Value;
+use crate::basetypes::{AngleMode, Value};
 
 pub mod add_sub;
 pub mod mult_div;
 pub mod cross_pow;
 pub mod calculus;
+pub mod linalg;
+pub mod special;
+#[cfg(feature = "uncertainty")]
+pub mod uncertain;
 
 #[doc(hidden)]
 pub fn add(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::add(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => return add_sub::sadd(a, b),
         (Value::Vector(a), Value::Vector(b)) => return add_sub::vadd(a, b),
         (Value::Matrix(a), Value::Matrix(b)) => return add_sub::madd(a, b),
@@ -23,6 +29,8 @@ pub fn add(lv: &Value, rv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn sub(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::sub(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => return add_sub::sadd(a, &(b * (-1.))),
         (Value::Vector(a), Value::Vector(b)) => return add_sub::vsub(a, b),
         (Value::Matrix(a), Value::Matrix(b)) => return add_sub::msub(a, b),
@@ -38,6 +46,8 @@ pub fn sub(lv: &Value, rv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn mult(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::mult(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => return mult_div::ssmult(a, b),
         (Value::Vector(a), Value::Scalar(b)) => return mult_div::svmult(b, a),
         (Value::Scalar(a), Value::Vector(b)) => return mult_div::svmult(a, b),
@@ -53,6 +63,8 @@ pub fn mult(lv: &Value, rv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn neg(lv: &Value) -> Result<Value, String> {
     match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(Value::Uncertain(-a, *s)),
         Value::Scalar(a) => return Ok(Value::Scalar(-1.*a)),
         Value::Vector(a) => return Ok(Value::Vector(a.iter().map(|x| -1.*x).collect())),
         Value::Matrix(a) => return Ok(Value::Matrix(a.iter().map(|x| x.iter().map(|y| -1.*y).collect()).collect()))
@@ -62,6 +74,8 @@ pub fn neg(lv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn div(lv: &Value, rv: &Value) -> Result<Value, String> {
     match(lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::div(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => return mult_div::ssdiv(a, b),
         (Value::Vector(a), Value::Scalar(b)) => return mult_div::vsdiv(a, b),
         (Value::Matrix(a), Value::Scalar(b)) => return mult_div::msdiv(a, b),
@@ -69,8 +83,23 @@ pub fn div(lv: &Value, rv: &Value) -> Result<Value, String> {
         (Value::Scalar(_), Value::Vector(_)) => return Err("Can't divide scalar by vector!".to_string()),
         (Value::Scalar(_), Value::Matrix(_)) => return Err("Can't divide scalar by matrix!".to_string()),
         (Value::Matrix(_), Value::Vector(_)) => return Err("Can't divide matrix by vector!".to_string()),
-        (Value::Vector(_), Value::Matrix(_)) => return Err("Can't divide vector by matrix!".to_string()),
-        (Value::Matrix(_), Value::Matrix(_)) => return Err("Can't divide matrix by matrix!".to_string()),
+        (Value::Vector(a), Value::Matrix(b)) => return mult_div::mvmult(&linalg::inv_m(b)?, a),
+        (Value::Matrix(a), Value::Matrix(b)) => return mult_div::mmmult(a, &linalg::inv_m(b)?),
+    }
+}
+
+#[doc(hidden)]
+pub fn left_div(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) => return div(rv, lv),
+        #[cfg(feature = "uncertainty")]
+        (Value::Matrix(_), Value::Uncertain(..)) => return Err("Can't left divide uncertain value by matrix!".to_string()),
+        (Value::Matrix(a), Value::Vector(b)) => return mult_div::mvmult(&linalg::inv_m(a)?, b),
+        (Value::Matrix(a), Value::Matrix(b)) => return mult_div::mmmult(&linalg::inv_m(a)?, b),
+        (Value::Scalar(_), _) => return div(rv, lv),
+        (Value::Matrix(_), Value::Scalar(_)) => return Err("Can't left divide scalar by matrix!".to_string()),
+        (Value::Vector(_), _) => return Err("Can't left divide by vector!".to_string()),
     }
 }
 
@@ -86,6 +115,9 @@ pub fn cross(lv: &Value, rv: &Value) -> Result<Value, String> {
 pub fn get(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
         (Value::Vector(a), Value::Scalar(b)) => {
+            if a.is_empty() {
+                return Err("Can't index into an empty vector!".to_string());
+            }
             if b % 1. != 0. || b.is_sign_negative() {
                 return Err("Index must be a positive Integer!".to_string());
             }
@@ -98,71 +130,300 @@ pub fn get(lv: &Value, rv: &Value) -> Result<Value, String> {
     }
 }
 
+#[doc(hidden)]
+pub fn slice(v: &Value, start: &Value, end: &Value) -> Result<Value, String> {
+    let a = match v {
+        Value::Vector(a) => a,
+        _ => return Err("Can only slice a vector!".to_string())
+    };
+    let len = a.len() as f64;
+    // negative indices count from the end of the vector
+    let index = |i: &Value| -> Result<usize, String> {
+        match i {
+            Value::Scalar(i) if i.fract() == 0. => {
+                let i = if *i < 0. { len + i } else { *i };
+                if i < 0. || i > len {
+                    return Err("Index out of bounds for vector!".to_string());
+                }
+                return Ok(i as usize);
+            },
+            _ => return Err("Index must be an Integer!".to_string())
+        }
+    };
+    let (s, e) = (index(start)?, index(end)?);
+    if s > e {
+        return Err("The start of a slice can't be after its end!".to_string());
+    }
+    return Ok(Value::Vector(a[s..e].to_vec()));
+}
+
+fn bool_to_value(b: bool) -> Value {
+    if b {
+        return Value::Scalar(1.);
+    } else {
+        return Value::Scalar(0.);
+    }
+}
+
+#[doc(hidden)]
+pub fn and(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(*a != 0. && *b != 0.)),
+        _ => return Err("Can only combine scalar conditions!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn or(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(*a != 0. || *b != 0.)),
+        _ => return Err("Can only combine scalar conditions!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn not(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Scalar(a) => return Ok(bool_to_value(*a == 0.)),
+        _ => return Err("Can only negate a scalar condition!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn less(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(a < b)),
+        _ => return Err("Can only compare scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn greater(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(a > b)),
+        _ => return Err("Can only compare scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn less_eq(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(a <= b)),
+        _ => return Err("Can only compare scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn greater_eq(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(bool_to_value(a >= b)),
+        _ => return Err("Can only compare scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn factorial(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(..) => return Err("Can't take factorial of uncertain value!".to_string()),
+        Value::Scalar(a) => {
+            if a % 1. != 0. || a.is_sign_negative() {
+                return Err("Can only take the factorial of a non-negative integer!".to_string());
+            }
+            let mut res = 1f64;
+            let mut i = 2.;
+            while i <= *a && res.is_finite() {
+                res *= i;
+                i += 1.;
+            }
+            return Ok(Value::Scalar(res));
+        },
+        Value::Vector(_) => return Err("Can't take factorial of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take factorial of matrix!".to_string())
+    }
+}
+
 #[doc(hidden)]
 pub fn pow(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::pow(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => return cross_pow::sspow(a, b),
         _ => return Err("Can only raise scalar to the power of scalar!".to_string())
     }
 }
 
 #[doc(hidden)]
-pub fn sin(lv: &Value) -> Result<Value, String> {
+pub fn sin(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.sin())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), f64::sin, f64::cos)),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.to_radians(*a).sin())),
         Value::Vector(_) => return Err("Can't take sin of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take sin of matrix!".to_string())
     }
 }
 
 #[doc(hidden)]
-pub fn cos(lv: &Value) -> Result<Value, String> {
+pub fn cos(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.cos())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), f64::cos, |x| -x.sin())),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.to_radians(*a).cos())),
         Value::Vector(_) => return Err("Can't take cos of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take cos of matrix!".to_string())
     }
 }
 
 #[doc(hidden)]
-pub fn tan(lv: &Value) -> Result<Value, String> {
+pub fn tan(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.tan())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), f64::tan, |x| 1./x.cos().powi(2))),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.to_radians(*a).tan())),
         Value::Vector(_) => return Err("Can't take tan of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take tan of matrix!".to_string())
     }
 }
 
+/// calculates 1/f(x) for cot, sec and csc, whose poles lie at (k + offset)*pi. An angle that only
+/// misses a pole because pi isn't exactly representable (e.g. pi/2 for sec) counts as the pole, so
+/// the result is inf instead of a large finite value.
+fn reciprocal_trig(x: f64, f: fn(f64) -> f64, offset: f64) -> f64 {
+    let pole = ((x/std::f64::consts::PI - offset).round() + offset)*std::f64::consts::PI;
+    if (x - pole).abs() <= 4.*f64::EPSILON*pole.abs() {
+        return f64::INFINITY;
+    }
+    return 1./f(x);
+}
+
 #[doc(hidden)]
-pub fn arcsin(lv: &Value) -> Result<Value, String> {
+pub fn cot(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.asin())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), |x| reciprocal_trig(x, f64::tan, 0.), |x| -1./x.sin().powi(2))),
+        Value::Scalar(a) => return Ok(Value::Scalar(reciprocal_trig(mode.to_radians(*a), f64::tan, 0.))),
+        Value::Vector(_) => return Err("Can't take cot of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take cot of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn sec(lv: &Value, mode: AngleMode) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), |x| reciprocal_trig(x, f64::cos, 0.5), |x| x.sin()/x.cos().powi(2))),
+        Value::Scalar(a) => return Ok(Value::Scalar(reciprocal_trig(mode.to_radians(*a), f64::cos, 0.5))),
+        Value::Vector(_) => return Err("Can't take sec of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take sec of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn csc(lv: &Value, mode: AngleMode) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(mode.to_radians(*a), mode.to_radians(*s), |x| reciprocal_trig(x, f64::sin, 0.), |x| -x.cos()/x.sin().powi(2))),
+        Value::Scalar(a) => return Ok(Value::Scalar(reciprocal_trig(mode.to_radians(*a), f64::sin, 0.))),
+        Value::Vector(_) => return Err("Can't take csc of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take csc of matrix!".to_string())
+    }
+}
+
+/// converts an uncertain angle in radians (including its uncertainty) to the given angle mode.
+#[cfg(feature = "uncertainty")]
+fn uncertain_from_radians(v: Value, mode: AngleMode) -> Value {
+    match v {
+        Value::Uncertain(a, s) => return Value::Uncertain(mode.from_radians(a), mode.from_radians(s)),
+        _ => return v
+    }
+}
+
+#[doc(hidden)]
+pub fn arcsin(lv: &Value, mode: AngleMode) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain_from_radians(uncertain::apply(*a, *s, f64::asin, |x| 1./(1.-x.powi(2)).sqrt()), mode)),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.from_radians(a.asin()))),
         Value::Vector(_) => return Err("Can't take arcsin of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take arcsin of matrxi!".to_string())
     }
 }
 
 #[doc(hidden)]
-pub fn arccos(lv: &Value) -> Result<Value, String> {
+pub fn arccos(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.acos())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain_from_radians(uncertain::apply(*a, *s, f64::acos, |x| -1./(1.-x.powi(2)).sqrt()), mode)),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.from_radians(a.acos()))),
         Value::Vector(_) => return Err("Can't take arccos of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take arccos of matrix!".to_string())
     }
 }
 
 #[doc(hidden)]
-pub fn arctan(lv: &Value) -> Result<Value, String> {
+pub fn arctan(lv: &Value, mode: AngleMode) -> Result<Value, String> {
     match lv {
-        Value::Scalar(a) => return Ok(Value::Scalar(a.atan())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain_from_radians(uncertain::apply(*a, *s, f64::atan, |x| 1./(1.+x.powi(2))), mode)),
+        Value::Scalar(a) => return Ok(Value::Scalar(mode.from_radians(a.atan()))),
         Value::Vector(_) => return Err("Can't take arctan of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take arctan of matrix!".to_string())
     }
 }
 
+#[doc(hidden)]
+pub fn atan2(lv: &Value, rv: &Value, mode: AngleMode) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(y), Value::Scalar(x)) => return Ok(Value::Scalar(mode.from_radians(y.atan2(*x)))),
+        _ => return Err("Can only take atan2 of two scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn arsinh(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::asinh, |x| 1./(x.powi(2)+1.).sqrt())),
+        Value::Scalar(a) => return Ok(Value::Scalar(a.asinh())),
+        Value::Vector(_) => return Err("Can't take arsinh of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take arsinh of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn arcosh(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, _) if *a < 1. => return Err("Can only take arcosh of a scalar >= 1!".to_string()),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::acosh, |x| 1./(x.powi(2)-1.).sqrt())),
+        Value::Scalar(a) if *a < 1. => return Err("Can only take arcosh of a scalar >= 1!".to_string()),
+        Value::Scalar(a) => return Ok(Value::Scalar(a.acosh())),
+        Value::Vector(_) => return Err("Can't take arcosh of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take arcosh of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn artanh(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, _) if a.abs() >= 1. => return Err("Can only take artanh of a scalar between -1 and 1 (exclusive)!".to_string()),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::atanh, |x| 1./(1.-x.powi(2)))),
+        Value::Scalar(a) if a.abs() >= 1. => return Err("Can only take artanh of a scalar between -1 and 1 (exclusive)!".to_string()),
+        Value::Scalar(a) => return Ok(Value::Scalar(a.atanh())),
+        Value::Vector(_) => return Err("Can't take artanh of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take artanh of matrix!".to_string())
+    }
+}
+
 #[doc(hidden)]
 pub fn abs(lv: &Value) -> Result<Value, String> {
     match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::abs, f64::signum)),
         Value::Scalar(a) => {
             if *a < 0. {return Ok(Value::Scalar(a*(-1.)));}
             else {return Ok(Value::Scalar(*a));}
@@ -174,13 +435,22 @@ pub fn abs(lv: &Value) -> Result<Value, String> {
             }
             return Ok(Value::Scalar(sum.sqrt()));
         },
-        Value::Matrix(_) => return Err("Can't take abs of matrix!".to_string())
+        Value::Matrix(a) => {
+            // the Frobenius norm, i.e. the length of the matrix seen as a vector of its entries
+            let mut sum = 0.;
+            for i in a.iter().flatten() {
+                sum += i.powi(2);
+            }
+            return Ok(Value::Scalar(sum.sqrt()));
+        }
     }
 }
 
 #[doc(hidden)]
 pub fn sqrt(lv: &Value) -> Result<Value, String> {
     match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::sqrt, |x| 0.5/x.sqrt())),
         Value::Scalar(a) => return Ok(Value::Scalar(a.sqrt())),
         Value::Vector(_) => return Err("Can't take sqrt of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take sqrt of matrix!".to_string())
@@ -190,6 +460,8 @@ pub fn sqrt(lv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn root(lv: &Value, rv: &Value) -> Result<Value, String> {
     match (lv, rv) {
+        #[cfg(feature = "uncertainty")]
+        (Value::Uncertain(..), _) | (_, Value::Uncertain(..)) => return uncertain::root(lv, rv),
         (Value::Scalar(a), Value::Scalar(b)) => {
             return Ok(Value::Scalar(a.powf(1./b)));
         },
@@ -200,8 +472,540 @@ pub fn root(lv: &Value, rv: &Value) -> Result<Value, String> {
 #[doc(hidden)]
 pub fn ln(lv: &Value) -> Result<Value, String> {
     match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::ln, |x| 1./x)),
         Value::Scalar(a) => return Ok(Value::Scalar(a.ln())),
         Value::Vector(_) => return Err("Can't take ln of vector!".to_string()),
         Value::Matrix(_) => return Err("Can't take ln of matrix!".to_string())
     }
 }
+
+#[doc(hidden)]
+pub fn log1p(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::ln_1p, |x| 1./(1.+x))),
+        Value::Scalar(a) => return Ok(Value::Scalar(a.ln_1p())),
+        Value::Vector(_) => return Err("Can't take log1p of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take log1p of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn expm1(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, f64::exp_m1, f64::exp)),
+        Value::Scalar(a) => return Ok(Value::Scalar(a.exp_m1())),
+        Value::Vector(_) => return Err("Can't take expm1 of vector!".to_string()),
+        Value::Matrix(_) => return Err("Can't take expm1 of matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn norm2(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) if !a.is_empty() => {
+            // AᵀA and AAᵀ have the same non-zero eigenvalues, so the layout of the matrix doesn't matter
+            let transposed: Vec<Vec<f64>> = (0..a[0].len()).map(|j| a.iter().map(|r| r[j]).collect()).collect();
+            match mult_div::mmmult(&transposed, a)? {
+                Value::Matrix(ata) => return Ok(Value::Scalar(linalg::spectral_radius(&ata)?.sqrt())),
+                _ => return Err("A matrix times a matrix has to be a matrix!".to_string())
+            }
+        },
+        _ => return Err("Can only calculate the 2-norm of a matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn spectral_radius(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Scalar(linalg::spectral_radius(a)?)),
+        _ => return Err("Can only calculate the spectral radius of a square matrix!".to_string())
+    }
+}
+
+/// the maximum number of entries a generated vector or matrix may have, so that huge sizes are
+/// rejected before trying to allocate them.
+const MAX_GENERATED_ENTRIES: usize = 10_000_000;
+
+fn matrix_entries(m: usize, n: usize) -> Result<usize, String> {
+    match m.checked_mul(n) {
+        Some(entries) if entries <= MAX_GENERATED_ENTRIES => return Ok(entries),
+        _ => return Err(format!("A {}×{} matrix is too large!", m, n))
+    }
+}
+
+fn matrix_size(x: &Value) -> Result<usize, String> {
+    match x {
+        Value::Scalar(s) if *s >= 1. && s.fract() == 0. => return Ok(*s as usize),
+        _ => return Err("The size of a matrix has to be a positive integer!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn reshape(v: &Value, rows: &Value, cols: &Value) -> Result<Value, String> {
+    let a = match v {
+        Value::Vector(a) => a,
+        _ => return Err("Can only reshape a vector!".to_string())
+    };
+    let (m, n) = (matrix_size(rows)?, matrix_size(cols)?);
+    if matrix_entries(m, n)? != a.len() {
+        return Err(format!("Can't reshape a vector with {} entries into a {}×{} matrix!", a.len(), m, n));
+    }
+    // fill the matrix in the same order as a matrix literal
+    #[cfg(not(feature = "row-major"))]
+    return Ok(Value::Matrix((0..m).map(|i| (0..n).map(|j| a[j*m+i]).collect()).collect()));
+    #[cfg(feature = "row-major")]
+    return Ok(Value::Matrix(a.chunks(n).map(|r| r.to_vec()).collect()));
+}
+
+#[doc(hidden)]
+pub fn identity(lv: &Value) -> Result<Value, String> {
+    let n = matrix_size(lv)?;
+    matrix_entries(n, n)?;
+    return Ok(Value::Matrix((0..n).map(|i| (0..n).map(|j| if i == j {1.} else {0.}).collect()).collect()));
+}
+
+#[doc(hidden)]
+pub fn zeros(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (m, n) = (matrix_size(lv)?, matrix_size(rv)?);
+    matrix_entries(m, n)?;
+    return Ok(Value::Matrix(vec![vec![0.; n]; m]));
+}
+
+#[doc(hidden)]
+pub fn ones(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (m, n) = (matrix_size(lv)?, matrix_size(rv)?);
+    matrix_entries(m, n)?;
+    return Ok(Value::Matrix(vec![vec![1.; n]; m]));
+}
+
+#[doc(hidden)]
+pub fn diag(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) => {
+            matrix_entries(v.len(), v.len())?;
+            return Ok(Value::Matrix((0..v.len()).map(|i| (0..v.len()).map(|j| if i == j {v[i]} else {0.}).collect()).collect()));
+        },
+        _ => return Err("Can only build a diagonal matrix from a vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn range(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (start, end) = match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) if a.fract() == 0. && b.fract() == 0. => (*a, *b),
+        _ => return Err("The bounds of a range have to be integers!".to_string())
+    };
+    if start > end {
+        return Err("The start of a range can't be larger than its end!".to_string());
+    }
+    if end-start >= MAX_GENERATED_ENTRIES as f64 {
+        return Err("The range is too large!".to_string());
+    }
+    return Ok(Value::Vector((0..=(end-start) as usize).map(|i| start + i as f64).collect()));
+}
+
+#[doc(hidden)]
+pub fn continued_fraction(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let mut x = match lv {
+        Value::Scalar(x) if x.is_finite() => *x,
+        _ => return Err("Can only calculate the continued fraction of a finite scalar!".to_string())
+    };
+    let depth = match rv {
+        Value::Scalar(d) if *d >= 1. && d.fract() == 0. => *d as usize,
+        _ => return Err("The depth of a continued fraction has to be a positive integer!".to_string())
+    };
+    let mut coefficients = vec![];
+    while coefficients.len() < depth {
+        let a = x.floor();
+        coefficients.push(a);
+        // stop once the remainder is lost in the rounding errors of the previous steps
+        if x - a <= 1e-9*x.abs().max(1.) {
+            break;
+        }
+        x = 1./(x - a);
+    }
+    return Ok(Value::Vector(coefficients));
+}
+
+#[doc(hidden)]
+pub fn clamp(x: &Value, lower: &Value, upper: &Value) -> Result<Value, String> {
+    match (x, lower, upper) {
+        (Value::Scalar(x), Value::Scalar(lo), Value::Scalar(hi)) => {
+            if lo > hi {
+                return Err("The lower bound of clamp can't be larger than its upper bound!".to_string());
+            }
+            return Ok(Value::Scalar(x.max(*lo).min(*hi)));
+        },
+        _ => return Err("Can only clamp a scalar between two scalars!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn linspace(start: &Value, end: &Value, count: &Value) -> Result<Value, String> {
+    let (a, b) = match (start, end) {
+        (Value::Scalar(a), Value::Scalar(b)) if a.is_finite() && b.is_finite() => (*a, *b),
+        _ => return Err("The bounds of linspace have to be finite scalars!".to_string())
+    };
+    let n = match count {
+        Value::Scalar(n) if *n >= 1. && n.fract() == 0. => *n as usize,
+        _ => return Err("The number of points of linspace has to be a positive integer!".to_string())
+    };
+    if n > MAX_GENERATED_ENTRIES {
+        return Err("The number of points of linspace is too large!".to_string());
+    }
+    if n == 1 {
+        return Ok(Value::Vector(vec![a]));
+    }
+    // the last point is set explicitly, so it isn't affected by rounding errors
+    let mut points: Vec<f64> = (0..n-1).map(|i| a + (b-a)*i as f64/(n-1) as f64).collect();
+    points.push(b);
+
+    return Ok(Value::Vector(points));
+}
+
+#[doc(hidden)]
+pub fn steady_state(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Vector(linalg::steady_state(a)?)),
+        _ => return Err("Can only calculate the steady state of a square matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn rank(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Scalar(special::rank(a) as f64)),
+        _ => return Err("Can only calculate the rank of a matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn rref(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Matrix(special::rref(a))),
+        _ => return Err("Can only calculate the reduced row echelon form of a matrix!".to_string())
+    }
+}
+
+/// returns the index stored in a scalar if it is a non-negative integer below len.
+fn matrix_index(i: &Value, len: usize) -> Result<usize, String> {
+    match i {
+        Value::Scalar(i) if i.fract() == 0. && !i.is_sign_negative() => {
+            if *i as usize >= len {
+                return Err("Index out of bounds for matrix!".to_string());
+            }
+            return Ok(*i as usize);
+        },
+        _ => return Err("Index must be a positive Integer!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn row(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Vector(a[matrix_index(rv, a.len())?].clone())),
+        _ => return Err("Can only extract a row of a matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn col(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => {
+            let j = matrix_index(rv, a.first().map(|r| r.len()).unwrap_or(0))?;
+            return Ok(Value::Vector(a.iter().map(|r| r[j]).collect()));
+        },
+        _ => return Err("Can only extract a column of a matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn concat(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Vector(a), Value::Vector(b)) => return Ok(Value::Vector([a.as_slice(), b.as_slice()].concat())),
+        (Value::Matrix(_), Value::Matrix(_)) => return Err("Can only concatenate vectors! To join matrices use vstack or hstack.".to_string()),
+        _ => return Err("Can only concatenate vectors!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn vstack(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Matrix(a), Value::Matrix(b)) => {
+            if a.first().map(|r| r.len()) != b.first().map(|r| r.len()) {
+                return Err("Can only stack matrices with the same number of columns on top of each other!".to_string());
+            }
+            return Ok(Value::Matrix([a.as_slice(), b.as_slice()].concat()));
+        },
+        _ => return Err("Can only stack matrices!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn hstack(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Matrix(a), Value::Matrix(b)) => {
+            if a.len() != b.len() {
+                return Err("Can only place matrices with the same number of rows next to each other!".to_string());
+            }
+            return Ok(Value::Matrix(a.iter().zip(b).map(|(r, s)| [r.as_slice(), s.as_slice()].concat()).collect()));
+        },
+        _ => return Err("Can only stack matrices!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn outer(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Vector(a), Value::Vector(b)) if !a.is_empty() && !b.is_empty() => return mult_div::vvouter(a, b),
+        _ => return Err("Can only calculate the outer product of two non-empty vectors!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn kron(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Matrix(a), Value::Matrix(b)) => return mult_div::mmkron(a, b),
+        _ => return Err("Can only calculate the Kronecker product of two matrices!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn det(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Matrix(a) => return Ok(Value::Scalar(linalg::det_m(a)?)),
+        _ => return Err("Can only calculate the determinant of a square matrix!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn mean(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) if !v.is_empty() => return Ok(Value::Scalar(v.iter().sum::<f64>()/v.len() as f64)),
+        _ => return Err("Can only calculate the mean of a non-empty vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn vsum(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) => return Ok(Value::Scalar(v.iter().sum())),
+        _ => return Err("Can only sum up the entries of a vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn vprod(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) => return Ok(Value::Scalar(v.iter().product())),
+        _ => return Err("Can only multiply the entries of a vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn vmin(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) if !v.is_empty() => return Ok(Value::Scalar(v.iter().copied().fold(f64::INFINITY, f64::min))),
+        _ => return Err("Can only find the minimum of a non-empty vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn vmax(lv: &Value) -> Result<Value, String> {
+    match lv {
+        Value::Vector(v) if !v.is_empty() => return Ok(Value::Scalar(v.iter().copied().fold(f64::NEG_INFINITY, f64::max))),
+        _ => return Err("Can only find the maximum of a non-empty vector!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn min(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(Value::Scalar(a.min(*b))),
+        _ => return Err("Can only find the minimum of two scalars! For the smallest entry of a vector use vmin.".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn max(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(b)) => return Ok(Value::Scalar(a.max(*b))),
+        _ => return Err("Can only find the maximum of two scalars! For the largest entry of a vector use vmax.".to_string())
+    }
+}
+
+/// returns the value of a scalar if it is an integer.
+fn integer(v: &Value) -> Option<f64> {
+    match v {
+        Value::Scalar(a) if a.is_finite() && a.fract() == 0. => return Some(*a),
+        _ => return None
+    }
+}
+
+/// calculates the greatest common divisor of two non-negative integers using the Euclidean algorithm.
+fn euclid(mut a: f64, mut b: f64) -> f64 {
+    while b != 0. {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
+#[doc(hidden)]
+pub fn gcd(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (integer(lv), integer(rv)) {
+        (Some(a), Some(b)) => return Ok(Value::Scalar(euclid(a.abs(), b.abs()))),
+        _ => return Err("Can only find the greatest common divisor of two integers!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn lcm(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (a, b) = match (integer(lv), integer(rv)) {
+        (Some(a), Some(b)) => (a.abs(), b.abs()),
+        _ => return Err("Can only find the least common multiple of two integers!".to_string())
+    };
+    if a == 0. || b == 0. {
+        return Ok(Value::Scalar(0.));
+    }
+    // divide first, so that the intermediate result doesn't get larger than necessary
+    return Ok(Value::Scalar(a/euclid(a, b)*b));
+}
+
+/// returns n and k if they are non-negative integers with k <= n.
+fn n_and_k(lv: &Value, rv: &Value) -> Result<(f64, f64), String> {
+    match (integer(lv), integer(rv)) {
+        (Some(n), Some(k)) if n >= 0. && k >= 0. => {
+            if k > n {
+                return Err("Can't choose more elements than there are (k > n)!".to_string());
+            }
+            return Ok((n, k));
+        },
+        _ => return Err("Can only choose a non-negative integer number of elements from a non-negative integer number of elements!".to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn choose(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (n, k) = n_and_k(lv, rv)?;
+    let k = k.min(n-k);
+    // every intermediate result is itself a binomial coefficient, so the division is exact
+    let mut res = 1f64;
+    // an integer counter, as adding 1 to an f64 above 2^53 doesn't change it
+    for j in 0..(k as u64) {
+        if !res.is_finite() {
+            break;
+        }
+        let i = (j+1) as f64;
+        res = res*(n-k+i)/i;
+    }
+    return Ok(Value::Scalar(res));
+}
+
+#[doc(hidden)]
+pub fn perm(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let (n, k) = n_and_k(lv, rv)?;
+    let mut res = 1f64;
+    // an integer counter, as adding 1 to an f64 above 2^53 doesn't change it
+    for j in 0..(k as u64) {
+        if !res.is_finite() {
+            break;
+        }
+        res *= n-j as f64;
+    }
+    return Ok(Value::Scalar(res));
+}
+
+#[doc(hidden)]
+pub fn var(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return Ok(Value::Scalar(variance(lv, rv, "variance")?));
+}
+
+#[doc(hidden)]
+pub fn std(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return Ok(Value::Scalar(variance(lv, rv, "standard deviation")?.sqrt()));
+}
+
+// divides the sum of squared deviations by the number of entries minus the delta degrees of
+// freedom given by rv (0 for the population and 1 for the sample variance)
+fn variance(lv: &Value, rv: &Value, name: &str) -> Result<f64, String> {
+    let v = match lv {
+        Value::Vector(v) => v,
+        _ => return Err(format!("Can only calculate the {} of a vector!", name))
+    };
+    let ddof = match rv {
+        Value::Scalar(d) if *d >= 0. && d.fract() == 0. => *d,
+        _ => return Err(format!("The delta degrees of freedom of the {} have to be a non-negative integer!", name))
+    };
+    if v.len() as f64 <= ddof {
+        return Err(format!("The vector needs more entries than the delta degrees of freedom to calculate its {}!", name));
+    }
+    let mean = v.iter().sum::<f64>()/v.len() as f64;
+    let squares: f64 = v.iter().map(|x| (x - mean).powi(2)).sum();
+
+    return Ok(squares/(v.len() as f64 - ddof));
+}
+
+fn step(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x < 0. {
+        return 0.;
+    } else if x > 0. {
+        return 1.;
+    }
+    return 0.5;
+}
+
+#[doc(hidden)]
+pub fn heaviside(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, step, |_| 0.)),
+        Value::Scalar(a) => return Ok(Value::Scalar(step(*a))),
+        Value::Vector(a) => return Ok(Value::Vector(a.iter().map(|x| step(*x)).collect())),
+        Value::Matrix(a) => return Ok(Value::Matrix(a.iter().map(|c| c.iter().map(|x| step(*x)).collect()).collect()))
+    }
+}
+
+// by convention the sign of 0 is 0, NaN stays NaN
+fn sgn(x: f64) -> f64 {
+    if x < 0. {
+        return -1.;
+    } else if x > 0. {
+        return 1.;
+    } else if x == 0. {
+        return 0.;
+    }
+    return x;
+}
+
+#[doc(hidden)]
+pub fn sign(lv: &Value) -> Result<Value, String> {
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(uncertain::apply(*a, *s, sgn, |_| 0.)),
+        Value::Scalar(a) => return Ok(Value::Scalar(sgn(*a))),
+        Value::Vector(a) => return Ok(Value::Vector(a.iter().map(|x| sgn(*x)).collect())),
+        Value::Matrix(a) => return Ok(Value::Matrix(a.iter().map(|c| c.iter().map(|x| sgn(*x)).collect()).collect()))
+    }
+}
+
+#[doc(hidden)]
+pub fn dirac(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let width = match rv {
+        Value::Scalar(w) if *w > 0. => *w,
+        _ => return Err("The width of the dirac approximation has to be a positive scalar!".to_string())
+    };
+    let gauss = |x: f64| (-x.powi(2)/(2.*width.powi(2))).exp()/(width*std::f64::consts::TAU.sqrt());
+    match lv {
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(a, s) => return Ok(Value::Uncertain(gauss(*a), (gauss(*a)*a/width.powi(2)*s).abs())),
+        Value::Scalar(a) => return Ok(Value::Scalar(gauss(*a))),
+        Value::Vector(a) => return Ok(Value::Vector(a.iter().map(|x| gauss(*x)).collect())),
+        Value::Matrix(a) => return Ok(Value::Matrix(a.iter().map(|c| c.iter().map(|x| gauss(*x)).collect()).collect()))
+    }
+}
@@ -1,17 +1,106 @@
-use crate::{basetypes::{Operation, SimpleOpType, Context, AST}, errors::EvalError, eval, Value, Variable, PREC};
+use std::ops::ControlFlow;
 
-use super::{add, mult};
+use crate::{basetypes::{Operation, SimpleOpType, Context, AST}, errors::EvalError, eval, helpers::report_progress, parser::eval_with_vars, Value, Variable, PREC};
 
-/// calculates the integral of an expression in terms of a variable with a lower and a upper bound.
+use super::{add, div, mult, sub};
+
+/// describes the numerical method used to calculate an integral.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrationMethod {
+    /// Midpoint Riemann sum. Only first-order accurate and therefore slow, but robust for
+    /// integrands that are not smooth.
+    Midpoint,
+    /// Simpson's rule. Converges much faster than the midpoint sum for smooth integrands.
+    Simpson
+}
+
+/// configures the numerical integration done by [calculate_integral_with_options].
+///
+/// The default options use Simpson's rule with 10^((PREC-2)/2) steps, which are 1000 steps
+/// (100000 steps with the high-prec feature). Exploiting the symmetry of the integrand has to be
+/// enabled explicitly.
+///
+/// # Example
+///
+/// ```
+/// let options = IntegrationOptions { exploit_symmetry: true, ..IntegrationOptions::default() };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntegrationOptions {
+    /// the number of steps the interval gets divided into. Simpson's rule needs an even number of
+    /// steps, which is why an odd number of steps gets rounded up.
+    pub steps: usize,
+    pub method: IntegrationMethod,
+    /// if the interval is symmetric around 0, the parity of the integrand is detected (see
+    /// [expression_parity]). The integral of an odd integrand is then exactly 0 and the integral of
+    /// an even integrand is calculated as twice the integral over the positive half (using half the
+    /// steps). As the parity is only detected at a few sample points, this is disabled by default.
+    pub exploit_symmetry: bool
+}
+
+impl IntegrationOptions {
+    /// creates the default options for the given method: 10^((PREC-2)/2) steps for Simpson's rule
+    /// and 10^(PREC-2) steps for the midpoint sum.
+    pub fn from_method(method: IntegrationMethod) -> Self {
+        let steps = match method {
+            IntegrationMethod::Midpoint => 10usize.pow(PREC as u32-2),
+            IntegrationMethod::Simpson => 10usize.pow((PREC as u32-2)/2)
+        };
+        IntegrationOptions { steps, method, exploit_symmetry: false }
+    }
+}
+
+impl Default for IntegrationOptions {
+    fn default() -> Self {
+        IntegrationOptions::from_method(IntegrationMethod::Simpson)
+    }
+}
+
+fn add_weighted(sums: &mut Vec<Value>, evals: Vec<Value>, weight: f64) -> Result<(), EvalError> {
+    for (i, e) in evals.iter().enumerate() {
+        let weighted = mult(e, &Value::Scalar(weight))?;
+        if sums.len() <= i {
+            sums.push(weighted);
+        } else {
+            sums[i] = add(&sums[i], &weighted)?;
+        }
+    }
+    Ok(())
+}
+
+/// calculates the integral of an expression in terms of a variable with a lower and a upper bound
+/// using Simpson's rule.
 ///
 /// Only scalars are supported as lower and upper bounds.
 pub fn calculate_integral(expr: &AST, in_terms_of: String, lower_bound: Value, upper_bound: Value, context: &Context) -> Result<Vec<Value>, EvalError> {
-    let mut mut_vars = context.vars.to_owned();
-    for i in 0..mut_vars.len() {
-        if mut_vars[i].name == in_terms_of {
-            mut_vars.remove(i);
-            break;
-        }
+    calculate_integral_with_options(expr, in_terms_of, lower_bound, upper_bound, IntegrationOptions::default(), context)
+}
+
+/// calculates the integral of an expression in terms of a variable with a lower and a upper bound
+/// using the given [IntegrationMethod].
+///
+/// Only scalars are supported as lower and upper bounds.
+pub fn calculate_integral_with_method(expr: &AST, in_terms_of: String, lower_bound: Value, upper_bound: Value, method: IntegrationMethod, context: &Context) -> Result<Vec<Value>, EvalError> {
+    calculate_integral_with_options(expr, in_terms_of, lower_bound, upper_bound, IntegrationOptions::from_method(method), context)
+}
+
+/// calculates the integral of an expression in terms of a variable with a lower and a upper bound
+/// using the given [IntegrationOptions]. This allows to trade accuracy for speed.
+///
+/// Only scalars are supported as lower and upper bounds.
+pub fn calculate_integral_with_options(expr: &AST, in_terms_of: String, lower_bound: Value, upper_bound: Value, options: IntegrationOptions, context: &Context) -> Result<Vec<Value>, EvalError> {
+    calculate_integral_with_progress(expr, in_terms_of, lower_bound, upper_bound, options, None, context)
+}
+
+/// calculates the integral of an expression in terms of a variable with a lower and a upper bound
+/// using the given [IntegrationOptions], while periodically reporting the fraction of completed
+/// steps to the progress callback.
+///
+/// If the callback returns [ControlFlow::Break], the integration is aborted with
+/// [EvalError::Cancelled].
+pub fn calculate_integral_with_progress(expr: &AST, in_terms_of: String, lower_bound: Value, upper_bound: Value, options: IntegrationOptions, progress: Option<&dyn Fn(f64) -> ControlFlow<()>>, context: &Context) -> Result<Vec<Value>, EvalError> {
+    if options.steps == 0 {
+        return Err(EvalError::MathError("Integration needs at least one step!".to_string()));
     }
     match (lower_bound, upper_bound) {
         (Value::Scalar(mut lb), Value::Scalar(mut ub)) => {
@@ -23,24 +112,52 @@ pub fn calculate_integral(expr: &AST, in_terms_of: String, lower_bound: Value, u
                 ub = lb;
                 lb = temp;
             }
-            let dx = (ub-lb)/10f64.powi(PREC as i32-2);
+            if options.exploit_symmetry && lb == -ub {
+                // sample only inside of the interval, as the integrand might not be defined outside of it
+                let points = (1..=8).map(|k| ub*(k as f64*INV_PLASTIC_NUMBER).fract());
+                match parity_at(expr, &in_terms_of, context, points).unwrap_or(Parity::Neither) {
+                    Parity::Odd => {
+                        let evals = eval_with_vars(expr, context, &[Variable::new(&in_terms_of, vec![Value::Scalar(ub)])])?;
+                        return Ok(evals.to_vec().iter().map(zero_like).collect());
+                    },
+                    Parity::Even => {
+                        let half_options = IntegrationOptions { steps: (options.steps/2).max(1), method: options.method, exploit_symmetry: false };
+                        let half = calculate_integral_with_progress(expr, in_terms_of, Value::Scalar(0.), Value::Scalar(ub), half_options, progress, context)?;
+                        return half.iter().map(|v| mult(v, &Value::Scalar(2.)).map_err(EvalError::from)).collect();
+                    },
+                    Parity::Neither => {}
+                }
+            }
+            let steps = match options.method {
+                IntegrationMethod::Midpoint => options.steps,
+                IntegrationMethod::Simpson => options.steps + options.steps % 2
+            };
+            let dx = (ub-lb)/steps as f64;
             let mut sums = vec![];
-            let mut b = lb;
-            while b < ub {
-                mut_vars.push(Variable::new(&in_terms_of, vec![Value::Scalar(b)]));
-                let evals = eval(expr, &Context::new(&mut_vars, &context.funs))?;
-                for (i, e) in evals.to_vec().iter().enumerate() {
-                    if sums.len() <= i {
-                        sums.push(e.clone());
-                    } else {
-                        sums[i] = add(&sums[i], &e)?;
+            match options.method {
+                IntegrationMethod::Midpoint => {
+                    for k in 0..steps {
+                        let x = Variable::new(&in_terms_of, vec![Value::Scalar(lb + (k as f64 + 0.5)*dx)]);
+                        let evals = eval_with_vars(expr, context, &[x])?;
+                        add_weighted(&mut sums, evals.to_vec(), dx)?;
+                        report_progress(progress, k+1, steps)?;
+                    }
+                },
+                IntegrationMethod::Simpson => {
+                    for k in 0..=steps {
+                        let weight = if k == 0 || k == steps {
+                            1.
+                        } else if k % 2 == 1 {
+                            4.
+                        } else {
+                            2.
+                        };
+                        let x = Variable::new(&in_terms_of, vec![Value::Scalar(lb + k as f64*dx)]);
+                        let evals = eval_with_vars(expr, context, &[x])?;
+                        add_weighted(&mut sums, evals.to_vec(), weight*dx/3.)?;
+                        report_progress(progress, k+1, steps+1)?;
                     }
                 }
-                mut_vars.remove(mut_vars.len()-1);
-                b += dx;
-            }
-            for i in 0..sums.len() {
-                sums[i] = mult(&sums[i], &Value::Scalar(dx))?;
             }
 
             return Ok(sums)
@@ -50,66 +167,71 @@ pub fn calculate_integral(expr: &AST, in_terms_of: String, lower_bound: Value, u
 }
 /// calculates the derivative of an expression in terms of a variable at a certain value.
 ///
-/// Only scalars and vectors are supported as values.
+/// Only scalars are supported as values.
 ///
-/// The function also takes an optional fx value, which is the value f(x). This can be used in
-/// order to increase performance by not having to calculate f(x) twice for an application such as
-/// newtons method.
-pub fn calculate_derivative(expr: &AST, in_terms_of: &str, at: &Value, context: &mut Context) -> Result<Vec<Value>, EvalError> {
-    for i in &context.vars {
-        if i.name == in_terms_of {
-            context.remove_var(i.name.clone());
-            break;
-        }
-    }
+/// The derivative is approximated using the central difference (f(x+h)-f(x-h))/2h, where the step
+/// size h is scaled to the magnitude of x in order to avoid catastrophic cancellation.
+pub fn calculate_derivative(expr: &AST, in_terms_of: &str, at: &Value, context: &Context) -> Result<Vec<Value>, EvalError> {
     match at {
         Value::Scalar(s) => {
-            context.add_var(&Variable::new(in_terms_of, vec![at.clone()]));
-            let fxs = eval(expr, context)?.to_vec();
-            context.remove_var(in_terms_of);
-            context.add_var(&Variable::new(in_terms_of, vec![Value::Scalar(s+10f64.powi(-(PREC as i32)))]));
-            let fxhs = &eval(expr, context)?.to_vec();
-            if fxs.len() != fxhs.len() {
-                return Err(EvalError::MathError("Amount of solutions for f(x) and f(x+h) are different!".to_string()));
+            let h = f64::EPSILON.cbrt() * s.abs().max(1.);
+            let fxhs = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(s+h)])])?.to_vec();
+            let fxmhs = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(s-h)])])?.to_vec();
+            if fxhs.len() != fxmhs.len() {
+                return Err(EvalError::MathError("Amount of solutions for f(x+h) and f(x-h) are different!".to_string()));
             }
             let mut res = vec![];
-            for i in 0..fxs.len() {
-                let h = AST::from_operation(Operation::SimpleOperation {
-                    op_type: SimpleOpType::Div,
-                    left: AST::from_operation(Operation::SimpleOperation {
-                        op_type: SimpleOpType::Sub,
-                        left: AST::from_value(fxhs[i].clone()),
-                        right: AST::from_value(fxs[i].clone())
-                    }),
-                    right: AST::from_value(Value::Scalar(10f64.powi(-(PREC as i32))))
-                });
-                res.push(eval(&h, &context)?.to_vec());
-            }
-
-            context.remove_var(in_terms_of);
-
-            return Ok(res.into_iter().flatten().collect()); 
+            for i in 0..fxhs.len() {
+                res.push(div(&sub(&fxhs[i], &fxmhs[i])?, &Value::Scalar(2.*h))?);
+            }
+
+            return Ok(res); 
         } 
         _ => {return Err(EvalError::MathError("Only scalar values are allowed!".to_string()))}
     }
 }
 
-pub fn calculate_derivative_newton(expr: &AST, in_terms_of: &str, at: &Value, mut fx: Option<Value>, context: &mut Context) -> Result<Value, EvalError> {
-    for i in &context.vars {
-        if i.name == in_terms_of {
-            context.remove_var(in_terms_of);
-            break;
+/// calculates the nth derivative of an expression in terms of a variable at a certain value.
+///
+/// Only scalars are supported as values.
+///
+/// The derivative is approximated using the central finite difference stencil
+/// Σ (-1)^k C(n, k) f(x+(n/2-k)h) / h^n. An order of 0 evaluates the expression at the value and
+/// an order of 1 is equivalent to [calculate_derivative].
+pub fn calculate_nth_derivative(expr: &AST, in_terms_of: &str, at: &Value, order: usize, context: &Context) -> Result<Vec<Value>, EvalError> {
+    if order == 1 {
+        return calculate_derivative(expr, in_terms_of, at, context);
+    }
+    match at {
+        Value::Scalar(s) => {
+            // balance the truncation error against the rounding error, which grows with 1/h^n
+            let h = f64::EPSILON.powf(1./(order as f64+2.)) * s.abs().max(1.);
+            let mut sums: Vec<Value> = vec![];
+            let mut binom = 1.;
+            for k in 0..=order {
+                let x = s + (order as f64/2.-k as f64)*h;
+                let evals = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(x)])])?.to_vec();
+                if k != 0 && evals.len() != sums.len() {
+                    return Err(EvalError::MathError("Amount of solutions for the finite difference stencil are different!".to_string()));
+                }
+                let sign = if k % 2 == 0 {1.} else {-1.};
+                add_weighted(&mut sums, evals, sign*binom/h.powi(order as i32))?;
+                binom = binom*(order-k) as f64/(k+1) as f64;
+            }
+
+            return Ok(sums);
         }
+        _ => {return Err(EvalError::MathError("Only scalar values are allowed!".to_string()))}
     }
+}
+
+pub fn calculate_derivative_newton(expr: &AST, in_terms_of: &str, at: &Value, mut fx: Option<Value>, context: &Context) -> Result<Value, EvalError> {
     match at {
         Value::Scalar(s) => {
             if fx.is_none() {
-                context.add_var(&Variable::new(in_terms_of, vec![at.clone()]));
-                fx = Some(eval(expr, context)?.get(0).unwrap().clone());
-                context.remove_var(in_terms_of);
+                fx = Some(eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![at.clone()])])?.get(0).unwrap().clone());
             }
-            context.add_var(&Variable::new(in_terms_of, vec![Value::Scalar(s+10f64.powi(-(PREC as i32)))]));
-            let fxh = &eval(expr, context)?.get(0).unwrap().clone();
+            let fxh = &eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(s+10f64.powi(-(PREC as i32)))])])?.get(0).unwrap().clone();
             let h = AST::from_operation(Operation::SimpleOperation {
                 op_type: SimpleOpType::Div,
                 left: AST::from_operation(Operation::SimpleOperation {
@@ -120,9 +242,386 @@ pub fn calculate_derivative_newton(expr: &AST, in_terms_of: &str, at: &Value, mu
                 right: AST::from_value(Value::Scalar(10f64.powi(-(PREC as i32))))
             });
             let res = eval(&h, context)?.get(0).unwrap().clone();
-            context.remove_var(in_terms_of);
             return Ok(res);
         } 
         _ => {return Err(EvalError::MathError("Only scalar values are allowed!".to_string()))}
     }
 }
+
+/// calculates the gradient of a scalar expression in terms of the given variables at a certain
+/// point.
+///
+/// The point has to be a vector with one component per variable (or a scalar if there is only one
+/// variable). Each partial derivative is calculated using [calculate_derivative], while all
+/// other variables are held fixed at their component of the point.
+pub fn calculate_gradient(expr: &AST, in_terms_of: &[String], at: &Value, context: &Context) -> Result<Value, EvalError> {
+    let point = match at {
+        Value::Scalar(s) => vec![*s],
+        Value::Vector(v) => v.clone(),
+        Value::Matrix(_) => return Err(EvalError::MathError("Can't calculate the gradient at a matrix!".to_string())),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(..) => return Err(EvalError::MathError("Can't calculate the gradient at an uncertain point!".to_string()))
+    };
+    if point.len() != in_terms_of.len() {
+        return Err(EvalError::MathError("The point must have one component per variable!".to_string()));
+    }
+
+    let mut base_context = context.clone();
+    for v in in_terms_of {
+        base_context.remove_var(v);
+    }
+
+    let mut gradient = vec![];
+    for j in 0..in_terms_of.len() {
+        let mut partial_context = base_context.clone();
+        for k in 0..in_terms_of.len() {
+            if j != k {
+                partial_context.add_var(&Variable::new(&in_terms_of[k], vec![Value::Scalar(point[k])]));
+            }
+        }
+        match calculate_derivative(expr, &in_terms_of[j], &Value::Scalar(point[j]), &partial_context)?.as_slice() {
+            [Value::Scalar(s)] => gradient.push(*s),
+            _ => return Err(EvalError::MathError("Can only calculate the gradient of a scalar function!".to_string()))
+        }
+    }
+
+    return Ok(Value::Vector(gradient));
+}
+
+/// describes the symmetry of an expression in terms of a variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parity {
+    /// f(-x) = f(x)
+    Even,
+    /// f(-x) = -f(x)
+    Odd,
+    Neither
+}
+
+fn components(v: &Value) -> Vec<f64> {
+    match v {
+        Value::Scalar(s) => return vec![*s],
+        Value::Vector(v) => return v.clone(),
+        Value::Matrix(m) => return m.iter().flatten().copied().collect(),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(s, _) => return vec![*s]
+    }
+}
+
+/// numerically determines whether an expression is even, odd or neither in terms of a variable by
+/// comparing f(-x) with f(x) at the given number of sample points.
+///
+/// Because only a finite number of points is tested, this is a heuristic: an expression that is
+/// detected as even or odd is only guaranteed to be symmetric at the sample points. An expression
+/// that is 0 at every sample point is reported as [Parity::Odd].
+pub fn expression_parity(expr: &AST, in_terms_of: &str, context: &Context, samples: usize) -> Result<Parity, EvalError> {
+    if samples == 0 {
+        return Err(EvalError::MathError("Parity detection needs at least one sample point!".to_string()));
+    }
+    // irregularly spaced points, so that periodic expressions don't line up with the samples
+    return parity_at(expr, in_terms_of, context, (1..=samples).map(|k| k as f64*INV_PLASTIC_NUMBER));
+}
+
+const INV_PLASTIC_NUMBER: f64 = 0.7548776662466927;
+
+fn parity_at<I: Iterator<Item = f64>>(expr: &AST, in_terms_of: &str, context: &Context, points: I) -> Result<Parity, EvalError> {
+    let tolerance = 10f64.powi(-(PREC as i32-2));
+    let mut even = true;
+    let mut odd = true;
+    for x in points {
+        let fx = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(x)])])?.to_vec();
+        let fmx = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(-x)])])?.to_vec();
+        if fx.len() != fmx.len() {
+            return Ok(Parity::Neither);
+        }
+        for (a, b) in fx.iter().zip(fmx.iter()) {
+            let (a, b) = (components(a), components(b));
+            if a.len() != b.len() {
+                return Ok(Parity::Neither);
+            }
+            for (a, b) in a.iter().zip(b.iter()) {
+                if a.is_nan() || b.is_nan() {
+                    return Ok(Parity::Neither);
+                }
+                let scale = a.abs().max(b.abs()).max(1.);
+                if (b-a).abs() > tolerance*scale {
+                    even = false;
+                }
+                if (b+a).abs() > tolerance*scale {
+                    odd = false;
+                }
+            }
+        }
+        if !even && !odd {
+            return Ok(Parity::Neither);
+        }
+    }
+
+    if odd {
+        return Ok(Parity::Odd);
+    }
+    return Ok(Parity::Even);
+}
+
+fn zero_like(v: &Value) -> Value {
+    match v {
+        Value::Scalar(_) => return Value::Scalar(0.),
+        Value::Vector(v) => return Value::Vector(vec![0.; v.len()]),
+        Value::Matrix(m) => return Value::Matrix(vec![vec![0.; m[0].len()]; m.len()]),
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(..) => return Value::Scalar(0.)
+    }
+}
+
+fn contains_var(expr: &AST, name: &str) -> bool {
+    match expr {
+        AST::Scalar(_) => return false,
+        AST::Vector(v) => return v.iter().any(|a| contains_var(a, name)),
+        AST::Matrix(m) => return m.iter().flatten().any(|a| contains_var(a, name)),
+        AST::List(l) => return l.iter().any(|a| contains_var(a, name)),
+        AST::Variable(v) => return v == name,
+        // the body of a function can read the variables of the caller, so it might depend on the
+        // variable even if its inputs don't.
+        AST::Function { .. } => return true,
+        AST::Operation(o) => {
+            match o.as_ref() {
+                Operation::SimpleOperation { left, right, .. } => return contains_var(left, name) || contains_var(right, name),
+                // the variable might be bound by the advanced operation itself, so don't make any
+                // assumptions about it.
+                Operation::AdvancedOperation(_) => return true
+            }
+        }
+    }
+}
+
+fn eval_constant(expr: &AST, context: &Context) -> Result<Option<f64>, EvalError> {
+    let res = eval(expr, context)?.to_vec();
+    if res.len() == 1 {
+        if let Value::Scalar(s) = res[0] {
+            return Ok(Some(s));
+        }
+    }
+    return Ok(None);
+}
+
+/// returns (a, b) if the expression is of the form a*k+b, where k is the given variable.
+fn linear_coefficients(expr: &AST, in_terms_of: &str, context: &Context) -> Result<Option<(f64, f64)>, EvalError> {
+    if !contains_var(expr, in_terms_of) {
+        return Ok(eval_constant(expr, context)?.map(|c| (0., c)));
+    }
+    match expr {
+        AST::Variable(_) => return Ok(Some((1., 0.))),
+        AST::Operation(o) => {
+            let (op_type, left, right) = match o.as_ref() {
+                Operation::SimpleOperation { op_type, left, right } => (op_type, left, right),
+                Operation::AdvancedOperation(_) => return Ok(None)
+            };
+            if *op_type == SimpleOpType::Neg {
+                return Ok(linear_coefficients(left, in_terms_of, context)?.map(|(a, b)| (-a, -b)));
+            }
+            let (l, r) = match (linear_coefficients(left, in_terms_of, context)?, linear_coefficients(right, in_terms_of, context)?) {
+                (Some(l), Some(r)) => (l, r),
+                _ => return Ok(None)
+            };
+            match op_type {
+                SimpleOpType::Add => return Ok(Some((l.0+r.0, l.1+r.1))),
+                SimpleOpType::Sub => return Ok(Some((l.0-r.0, l.1-r.1))),
+                SimpleOpType::Mult | SimpleOpType::HiddenMult if l.0 == 0. => return Ok(Some((l.1*r.0, l.1*r.1))),
+                SimpleOpType::Mult | SimpleOpType::HiddenMult if r.0 == 0. => return Ok(Some((l.0*r.1, l.1*r.1))),
+                SimpleOpType::Div if r.0 == 0. && r.1 != 0. => return Ok(Some((l.0/r.1, l.1/r.1))),
+                _ => return Ok(None)
+            }
+        },
+        _ => return Ok(None)
+    }
+}
+
+/// returns (c, r) if the expression is of the form c*r^k, where k is the given variable.
+fn geometric_coefficients(expr: &AST, in_terms_of: &str, context: &Context) -> Result<Option<(f64, f64)>, EvalError> {
+    if !contains_var(expr, in_terms_of) {
+        return Ok(None);
+    }
+    let (op_type, left, right) = match expr {
+        AST::Operation(o) => {
+            match o.as_ref() {
+                Operation::SimpleOperation { op_type, left, right } => (op_type, left, right),
+                Operation::AdvancedOperation(_) => return Ok(None)
+            }
+        },
+        _ => return Ok(None)
+    };
+    match op_type {
+        SimpleOpType::Pow => {
+            if contains_var(left, in_terms_of) {
+                return Ok(None);
+            }
+            // r^(a*k+b) = r^b*(r^a)^k
+            match (eval_constant(left, context)?, linear_coefficients(right, in_terms_of, context)?) {
+                (Some(r), Some((a, b))) => return Ok(Some((r.powf(b), r.powf(a)))),
+                _ => return Ok(None)
+            }
+        },
+        SimpleOpType::Neg => return Ok(geometric_coefficients(left, in_terms_of, context)?.map(|(c, r)| (-c, r))),
+        SimpleOpType::Mult | SimpleOpType::HiddenMult | SimpleOpType::Div => {
+            let (factor, geometric, factor_left) = if contains_var(left, in_terms_of) {
+                (right, left, false)
+            } else {
+                (left, right, true)
+            };
+            if contains_var(factor, in_terms_of) {
+                return Ok(None);
+            }
+            let (f, (c, r)) = match (eval_constant(factor, context)?, geometric_coefficients(geometric, in_terms_of, context)?) {
+                (Some(f), Some(g)) => (f, g),
+                _ => return Ok(None)
+            };
+            match (op_type, factor_left) {
+                (SimpleOpType::Div, true) => return Ok(Some((f/c, 1./r))),
+                (SimpleOpType::Div, false) => return Ok(Some((c/f, r))),
+                _ => return Ok(Some((f*c, r)))
+            }
+        },
+        _ => return Ok(None)
+    }
+}
+
+/// returns the bounds of a sum or product, which have to be integers. Only the upper bound may be
+/// infinite.
+fn integer_bounds(lower_bound: &Value, upper_bound: &Value, op: &str) -> Result<(f64, f64), EvalError> {
+    let (lb, ub) = match (lower_bound, upper_bound) {
+        (Value::Scalar(lb), Value::Scalar(ub)) => (*lb, *ub),
+        _ => return Err(EvalError::MathError("Only scalar values are allowed as bounds!".to_string()))
+    };
+    if !lb.is_finite() || lb.fract() != 0. || (ub.is_finite() && ub.fract() != 0.) || ub.is_nan() || ub == f64::NEG_INFINITY {
+        return Err(EvalError::MathError(format!("The bounds of a {} must be integers!", op)));
+    }
+    return Ok((lb, ub));
+}
+
+/// the maximum number of terms (or factors) that are evaluated one by one, so that huge bounds are
+/// rejected instead of hanging the evaluation.
+const MAX_TERMS: f64 = 1e6;
+
+fn check_term_count(lb: f64, ub: f64, op: &str) -> Result<(), EvalError> {
+    if ub-lb >= MAX_TERMS {
+        return Err(EvalError::MathError(format!("The {} has too many terms to be calculated term by term!", op)));
+    }
+    return Ok(());
+}
+
+/// calculates the sum of an expression over the integer index in_terms_of from a lower to an
+/// upper bound in closed form.
+///
+/// Arithmetic (a*k+b) and geometric (c*r^k) series are recognized. The upper bound may be
+/// infinite, in which case only convergent geometric series (|r| < 1) have a value. If the
+/// upper bound is smaller than the lower bound, the sum is empty and evaluates to 0.
+///
+/// Returns None if the expression is not recognized as one of the supported series.
+pub fn calculate_sum_closed_form(expr: &AST, in_terms_of: &str, lower_bound: &Value, upper_bound: &Value, context: &Context) -> Result<Option<Value>, EvalError> {
+    let (lb, ub) = integer_bounds(lower_bound, upper_bound, "sum")?;
+    if ub < lb {
+        return Ok(Some(Value::Scalar(0.)));
+    }
+    let n = ub-lb+1.;
+
+    if let Some((a, b)) = linear_coefficients(expr, in_terms_of, context)? {
+        if ub.is_infinite() {
+            if a == 0. && b == 0. {
+                return Ok(Some(Value::Scalar(0.)));
+            }
+            return Err(EvalError::MathError("The series does not converge!".to_string()));
+        }
+        return Ok(Some(Value::Scalar(a*(lb+ub)*n/2.+b*n)));
+    }
+
+    if let Some((c, r)) = geometric_coefficients(expr, in_terms_of, context)? {
+        if ub.is_infinite() {
+            if c == 0. {
+                return Ok(Some(Value::Scalar(0.)));
+            }
+            if r.abs() >= 1. {
+                return Err(EvalError::MathError("The series does not converge!".to_string()));
+            }
+            return Ok(Some(Value::Scalar(c*r.powf(lb)/(1.-r))));
+        }
+        if r == 1. {
+            return Ok(Some(Value::Scalar(c*n)));
+        }
+        return Ok(Some(Value::Scalar(c*(r.powf(lb)-r.powf(ub+1.))/(1.-r))));
+    }
+
+    return Ok(None);
+}
+
+/// calculates the sum of an expression over the integer index in_terms_of from a lower to an
+/// upper bound.
+///
+/// Arithmetic and geometric series are evaluated in closed form (see
+/// [calculate_sum_closed_form]), which also allows convergent geometric series with an infinite
+/// upper bound. All other expressions are summed term by term, which is limited to a million terms.
+///
+/// The index is lexically scoped: it shadows a variable of the context with the same name only
+/// within the expression, while the bounds are evaluated with the outer variable. The context itself
+/// is never modified.
+pub fn calculate_sum(expr: &AST, in_terms_of: &str, lower_bound: &Value, upper_bound: &Value, context: &Context) -> Result<Vec<Value>, EvalError> {
+    if let Some(v) = calculate_sum_closed_form(expr, in_terms_of, lower_bound, upper_bound, context)? {
+        return Ok(vec![v]);
+    }
+
+    // the bounds have already been checked by calculate_sum_closed_form
+    let lb = lower_bound.get_scalar().unwrap();
+    let ub = upper_bound.get_scalar().unwrap();
+    if ub.is_infinite() {
+        return Err(EvalError::MathError("Only arithmetic and geometric series can be summed up to infinity!".to_string()));
+    }
+    check_term_count(lb, ub, "sum")?;
+
+    let mut sums: Vec<Value> = vec![];
+    let mut k = lb;
+    while k <= ub {
+        let evals = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(k)])])?.to_vec();
+        if k != lb && evals.len() != sums.len() {
+            return Err(EvalError::MathError("Amount of solutions for the terms of the sum are different!".to_string()));
+        }
+        add_weighted(&mut sums, evals, 1.)?;
+        k += 1.;
+    }
+
+    return Ok(sums);
+}
+
+/// calculates the product of an expression over the integer index in_terms_of from a lower to an
+/// upper bound. Like in [calculate_sum], the index only shadows a variable of the context with the
+/// same name within the expression.
+///
+/// If the upper bound is smaller than the lower bound, the product is empty and evaluates to 1. At
+/// most a million factors are multiplied.
+pub fn calculate_product(expr: &AST, in_terms_of: &str, lower_bound: &Value, upper_bound: &Value, context: &Context) -> Result<Vec<Value>, EvalError> {
+    let (lb, ub) = integer_bounds(lower_bound, upper_bound, "product")?;
+    if ub < lb {
+        return Ok(vec![Value::Scalar(1.)]);
+    }
+    if ub.is_infinite() {
+        return Err(EvalError::MathError("Can't calculate an infinite product!".to_string()));
+    }
+    check_term_count(lb, ub, "product")?;
+
+    let mut products: Vec<Value> = vec![];
+    let mut k = lb;
+    while k <= ub {
+        let evals = eval_with_vars(expr, context, &[Variable::new(in_terms_of, vec![Value::Scalar(k)])])?.to_vec();
+        if k == lb {
+            products = evals;
+        } else {
+            if evals.len() != products.len() {
+                return Err(EvalError::MathError("Amount of solutions for the factors of the product are different!".to_string()));
+            }
+            for (i, e) in evals.iter().enumerate() {
+                products[i] = mult(&products[i], e)?;
+            }
+        }
+        k += 1.;
+    }
+
+    return Ok(products);
+}
@@ -0,0 +1,394 @@
+use std::{cell::RefCell, collections::HashMap};
+
+/// the maximum number of determinants and inverses each that are cached during a single evaluation.
+const CACHE_LIMIT: usize = 256;
+
+#[derive(Default)]
+struct Cache {
+    determinants: HashMap<Vec<u64>, f64>,
+    inverses: HashMap<Vec<u64>, Vec<Vec<f64>>>,
+    computed_determinants: usize,
+    computed_inverses: usize
+}
+
+thread_local! {
+    // caches the determinants and inverses by the contents of their matrix while with_cache is running
+    static CACHE: RefCell<Option<Cache>> = const { RefCell::new(None) };
+}
+
+fn cache_key(a: &[Vec<f64>]) -> Vec<u64> {
+    let mut key = vec![a.len() as u64];
+    key.extend(a.iter().flatten().map(|x| x.to_bits()));
+    return key;
+}
+
+/// runs f while caching the determinants and inverses calculated in it, returning the number of
+/// determinants and inverses that actually had to be calculated. If a cache is already active (e.g.
+/// for an evaluation inside of an evaluation), it is used instead and (0, 0) is returned.
+pub(crate) fn with_cache<T>(f: impl FnOnce() -> T) -> (T, (usize, usize)) {
+    let is_outermost = CACHE.with(|c| {
+        let mut c = c.borrow_mut();
+        if c.is_some() {
+            return false;
+        }
+        *c = Some(Cache::default());
+        return true;
+    });
+    if !is_outermost {
+        return (f(), (0, 0));
+    }
+    // removes the cache again, even if f panics
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            CACHE.with(|c| c.borrow_mut().take());
+        }
+    }
+    let _guard = Guard;
+    let res = f();
+    let cache = CACHE.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    return (res, (cache.computed_determinants, cache.computed_inverses));
+}
+
+#[doc(hidden)]
+pub fn inv_m(a: &Vec<Vec<f64>>) -> Result<Vec<Vec<f64>>, String> {
+    let key = cache_key(a);
+    if let Some(inv) = CACHE.with(|c| c.borrow().as_ref().and_then(|c| c.inverses.get(&key).cloned())) {
+        return Ok(inv);
+    }
+    let inv = calc_inv(a)?;
+    CACHE.with(|c| {
+        if let Some(c) = c.borrow_mut().as_mut() {
+            c.computed_inverses += 1;
+            if c.inverses.len() < CACHE_LIMIT {
+                c.inverses.insert(key, inv.clone());
+            }
+        }
+    });
+    return Ok(inv);
+}
+
+#[doc(hidden)]
+pub fn det_m(a: &Vec<Vec<f64>>) -> Result<f64, String> {
+    let key = cache_key(a);
+    if let Some(det) = CACHE.with(|c| c.borrow().as_ref().and_then(|c| c.determinants.get(&key).copied())) {
+        return Ok(det);
+    }
+    let det = calc_det(a)?;
+    CACHE.with(|c| {
+        if let Some(c) = c.borrow_mut().as_mut() {
+            c.computed_determinants += 1;
+            if c.determinants.len() < CACHE_LIMIT {
+                c.determinants.insert(key, det);
+            }
+        }
+    });
+    return Ok(det);
+}
+
+fn calc_det(a: &[Vec<f64>]) -> Result<f64, String> {
+    if a.is_empty() || a.iter().any(|r| r.len() != a.len()) {
+        return Err("Can only calculate the determinant of a square matrix!".to_string());
+    }
+    let n = a.len();
+    let mut m = a.to_vec();
+    let mut det = 1.;
+
+    // Gaussian elimination with partial pivoting
+    for i in 0..n {
+        let mut pivot = i;
+        for j in (i+1)..n {
+            if m[j][i].abs() > m[pivot][i].abs() {
+                pivot = j;
+            }
+        }
+        if m[pivot][i] == 0. {
+            return Ok(0.);
+        }
+        if pivot != i {
+            m.swap(i, pivot);
+            det = -det;
+        }
+        det *= m[i][i];
+        for j in (i+1)..n {
+            let factor = m[j][i]/m[i][i];
+            for k in i..n {
+                m[j][k] -= factor*m[i][k];
+            }
+        }
+    }
+
+    return Ok(det);
+}
+
+fn calc_inv(a: &Vec<Vec<f64>>) -> Result<Vec<Vec<f64>>, String> {
+    if a.is_empty() || a.iter().any(|r| r.len() != a.len()) {
+        return Err("Can only invert a square matrix!".to_string());
+    }
+    let n = a.len();
+    let scale = a.iter().flatten().fold(0f64, |m, x| m.max(x.abs()));
+    let mut m = a.clone();
+    let mut inv: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j {1.} else {0.}).collect()).collect();
+
+    // Gauss-Jordan elimination with partial pivoting
+    for i in 0..n {
+        let mut pivot = i;
+        for j in (i+1)..n {
+            if m[j][i].abs() > m[pivot][i].abs() {
+                pivot = j;
+            }
+        }
+        if m[pivot][i].abs() <= f64::EPSILON*scale*n as f64 {
+            return Err("Can't invert a singular matrix!".to_string());
+        }
+        m.swap(i, pivot);
+        inv.swap(i, pivot);
+
+        let divisor = m[i][i];
+        for k in 0..n {
+            m[i][k] /= divisor;
+            inv[i][k] /= divisor;
+        }
+        for j in 0..n {
+            if j == i || m[j][i] == 0. {
+                continue;
+            }
+            let factor = m[j][i];
+            for k in 0..n {
+                m[j][k] -= factor*m[i][k];
+                inv[j][k] -= factor*inv[i][k];
+            }
+        }
+    }
+
+    return Ok(inv);
+}
+
+/// reduces a square matrix to upper Hessenberg form (zero below the first subdiagonal) using
+/// Gaussian elimination with partial pivoting, which keeps its eigenvalues.
+fn hessenberg(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = a.len();
+    let mut m = a.to_vec();
+
+    for k in 1..n.saturating_sub(1) {
+        let mut pivot = k;
+        for i in (k+1)..n {
+            if m[i][k-1].abs() > m[pivot][k-1].abs() {
+                pivot = i;
+            }
+        }
+        if pivot != k {
+            m.swap(pivot, k);
+            for r in m.iter_mut() {
+                r.swap(pivot, k);
+            }
+        }
+        let x = m[k][k-1];
+        if x == 0. {
+            continue;
+        }
+        for i in (k+1)..n {
+            let factor = m[i][k-1]/x;
+            if factor == 0. {
+                continue;
+            }
+            m[i][k-1] = 0.;
+            for j in k..n {
+                m[i][j] -= factor*m[k][j];
+            }
+            for r in m.iter_mut() {
+                r[k] += factor*r[i];
+            }
+        }
+    }
+
+    return m;
+}
+
+#[doc(hidden)]
+pub fn spectral_radius(a: &Vec<Vec<f64>>) -> Result<f64, String> {
+    if a.is_empty() || a.iter().any(|r| r.len() != a.len()) {
+        return Err("Can only calculate the spectral radius of a square matrix!".to_string());
+    }
+    let mut m = hessenberg(a);
+    let norm: f64 = m.iter().enumerate().map(|(i, r)| r[i.saturating_sub(1)..].iter().map(|x| x.abs()).sum::<f64>()).sum();
+    if norm == 0. {
+        return Ok(0.);
+    }
+
+    // Francis double shift QR algorithm on the Hessenberg matrix, deflating 1x1 and 2x2 blocks off
+    // the bottom. The eigenvalues are shifted by the accumulated exceptional shifts t.
+    let mut radius = 0f64;
+    let mut t = 0.;
+    let mut nn = m.len();
+    while nn > 0 {
+        let last = nn-1;
+        let mut its = 0;
+        loop {
+            // look for a negligible subdiagonal element to split the matrix at
+            let mut l = last;
+            while l > 0 {
+                let mut s = m[l-1][l-1].abs()+m[l][l].abs();
+                if s == 0. {
+                    s = norm;
+                }
+                if m[l][l-1].abs() <= f64::EPSILON*s {
+                    m[l][l-1] = 0.;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = m[last][last];
+            if l == last {
+                // one real eigenvalue
+                radius = radius.max((x+t).abs());
+                nn -= 1;
+                break;
+            }
+            let mut y = m[last-1][last-1];
+            let mut w = m[last][last-1]*m[last-1][last];
+            if l+1 == last {
+                // two eigenvalues of a 2x2 block, either real or a complex conjugate pair
+                let p = (y-x)/2.;
+                let q = p*p+w;
+                let z = q.abs().sqrt();
+                if q >= 0. {
+                    let z = p+z.copysign(p);
+                    radius = radius.max((x+t+z).abs());
+                    if z != 0. {
+                        radius = radius.max((x+t-w/z).abs());
+                    }
+                } else {
+                    radius = radius.max((x+t+p).hypot(z));
+                }
+                nn -= 2;
+                break;
+            }
+
+            if its == 100 {
+                return Err("Couldn't calculate the spectral radius, the QR algorithm didn't converge!".to_string());
+            }
+            if its > 0 && its % 10 == 0 {
+                // exceptional shift, breaks up cycles (e.g. of permutation matrices) that the
+                // Wilkinson shifts can't
+                t += x;
+                for i in 0..=last {
+                    m[i][i] -= x;
+                }
+                let s = m[last][last-1].abs()+m[last-1][last-2].abs();
+                x = 0.75*s;
+                y = x;
+                w = -0.4375*s*s;
+            }
+            its += 1;
+
+            // look for two consecutive small subdiagonal elements to start the double shift step at
+            let mut start = last-2;
+            let (mut p, mut q, mut r);
+            loop {
+                let z = m[start][start];
+                let rr = x-z;
+                let ss = y-z;
+                p = (rr*ss-w)/m[start+1][start]+m[start][start+1];
+                q = m[start+1][start+1]-z-rr-ss;
+                r = m[start+2][start+1];
+                let s = p.abs()+q.abs()+r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if start == l {
+                    break;
+                }
+                let u = m[start][start-1].abs()*(q.abs()+r.abs());
+                let v = p.abs()*(m[start-1][start-1].abs()+z.abs()+m[start+1][start+1].abs());
+                if u <= f64::EPSILON*v {
+                    break;
+                }
+                start -= 1;
+            }
+            for i in start..(last-1) {
+                m[i+2][i] = 0.;
+                if i != start {
+                    m[i+2][i-1] = 0.;
+                }
+            }
+
+            // chase the bulge down the matrix using Householder reflections
+            for k in start..last {
+                if k != start {
+                    p = m[k][k-1];
+                    q = m[k+1][k-1];
+                    r = if k+1 != last {m[k+2][k-1]} else {0.};
+                    x = p.abs()+q.abs()+r.abs();
+                    if x != 0. {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = (p*p+q*q+r*r).sqrt().copysign(p);
+                if s == 0. {
+                    continue;
+                }
+                if k == start {
+                    if l != start {
+                        m[k][k-1] = -m[k][k-1];
+                    }
+                } else {
+                    m[k][k-1] = -s*x;
+                }
+                p += s;
+                x = p/s;
+                y = q/s;
+                let z = r/s;
+                q /= p;
+                r /= p;
+                for j in k..=last {
+                    let mut p = m[k][j]+q*m[k+1][j];
+                    if k+1 != last {
+                        p += r*m[k+2][j];
+                        m[k+2][j] -= p*z;
+                    }
+                    m[k+1][j] -= p*y;
+                    m[k][j] -= p*x;
+                }
+                for i in l..=last.min(k+3) {
+                    let mut p = x*m[i][k]+y*m[i][k+1];
+                    if k+1 != last {
+                        p += z*m[i][k+2];
+                        m[i][k+2] -= p*r;
+                    }
+                    m[i][k+1] -= p*q;
+                    m[i][k] -= p;
+                }
+            }
+        }
+    }
+
+    return Ok(radius);
+}
+
+/// calculates the stationary distribution v of a column stochastic matrix a, i.e. the solution of
+/// (a-I)v = 0 whose entries sum up to 1.
+#[doc(hidden)]
+pub fn steady_state(a: &Vec<Vec<f64>>) -> Result<Vec<f64>, String> {
+    if a.is_empty() || a.iter().any(|r| r.len() != a.len()) {
+        return Err("Can only calculate the steady state of a square matrix!".to_string());
+    }
+    let n = a.len();
+    for j in 0..n {
+        let column_sum: f64 = (0..n).map(|i| a[i][j]).sum();
+        if (0..n).any(|i| a[i][j] < 0.) || (column_sum - 1.).abs() > 1e-9*n as f64 {
+            return Err("Can only calculate the steady state of a column stochastic matrix!".to_string());
+        }
+    }
+
+    // the rows of a-I are linearly dependent, so the last one is replaced by the normalization
+    let mut m: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j {a[i][j]-1.} else {a[i][j]}).collect()).collect();
+    m[n-1] = vec![1.; n];
+
+    let inv = calc_inv(&m).map_err(|_| "The Markov chain doesn't have a unique steady state!".to_string())?;
+
+    return Ok(inv.iter().map(|r| r[n-1]).collect());
+}
@@ -39,8 +39,25 @@ pub fn vvmult(a: &Vec<f64>, b: &Vec<f64>) -> Result<Value, String> {
     return Ok(Value::Scalar(sum));
 }
 
+#[doc(hidden)]
+pub fn vvouter(a: &Vec<f64>, b: &Vec<f64>) -> Result<Value, String> {
+    super::matrix_entries(a.len(), b.len())?;
+    let mut output_m = vec![];
+    for i in 0..a.len() {
+        let mut row = vec![];
+        for j in 0..b.len() {
+            row.push(a[i]*b[j]);
+        }
+        output_m.push(row);
+    }
+    return Ok(Value::Matrix(output_m));
+}
+
 #[doc(hidden)]
 pub fn mvmult(a: &Vec<Vec<f64>>, b: &Vec<f64>) -> Result<Value, String> {
+    if a.is_empty() {
+        return Err("Can't multiply an empty matrix!".to_string());
+    }
     if a[0].len() != b.len() {
         return Err("Vector and matrix have incompatible dimensions!".to_string());
     }
@@ -57,6 +74,9 @@ pub fn mvmult(a: &Vec<Vec<f64>>, b: &Vec<f64>) -> Result<Value, String> {
 
 #[doc(hidden)]
 pub fn mmmult(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>) -> Result<Value, String> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Can't multiply an empty matrix!".to_string());
+    }
     if a[0].len() != b.len() {
         return Err("Matrices have incompatible dimensions!".to_string());
     }
@@ -75,6 +95,31 @@ pub fn mmmult(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>) -> Result<Value, String> {
     return Ok(Value::Matrix(output_m))
 }
 
+#[doc(hidden)]
+pub fn mmkron(a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>) -> Result<Value, String> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Can't calculate the Kronecker product of an empty matrix!".to_string());
+    }
+    match (a.len().checked_mul(b.len()), a[0].len().checked_mul(b[0].len())) {
+        (Some(m), Some(n)) => {super::matrix_entries(m, n)?;},
+        _ => return Err("The Kronecker product is too large!".to_string())
+    }
+    // the blocks a[i][j]*b, one row of blocks at a time
+    let mut output_m = vec![];
+    for i in 0..a.len() {
+        let blocks = a[i].iter().map(|x| {
+            match smmult(x, b)? {
+                Value::Matrix(m) => return Ok(m),
+                _ => return Err("Scalar times matrix has to be a matrix!".to_string())
+            }
+        }).collect::<Result<Vec<Vec<Vec<f64>>>, String>>()?;
+        for k in 0..b.len() {
+            output_m.push(blocks.iter().flat_map(|block| block[k].clone()).collect());
+        }
+    }
+    return Ok(Value::Matrix(output_m));
+}
+
 #[doc(hidden)]
 pub fn ssdiv(a: &f64, b: &f64) -> Result<Value, String> {
     return Ok(Value::Scalar(a/b));
@@ -0,0 +1,69 @@
+use crate::PREC;
+
+/// brings a copy of a matrix into reduced row echelon form using Gauss-Jordan elimination with
+/// partial pivoting and returns it together with its number of pivots. Entries below 10^-PREC
+/// relative to the largest entry of the matrix count as zero and are set to exactly 0.
+fn row_reduce(a: &[Vec<f64>]) -> (Vec<Vec<f64>>, usize) {
+    let rows = a.len();
+    let cols = a.first().map(|r| r.len()).unwrap_or(0);
+    let scale = a.iter().flatten().fold(0f64, |m, x| m.max(x.abs()));
+    let tolerance = scale*10f64.powi(-(PREC as i32));
+    let mut m = a.to_vec();
+    let mut pivots = 0;
+
+    for j in 0..cols {
+        if pivots == rows {
+            break;
+        }
+        let mut pivot = pivots;
+        for i in (pivots+1)..rows {
+            if m[i][j].abs() > m[pivot][j].abs() {
+                pivot = i;
+            }
+        }
+        if m[pivot][j].abs() <= tolerance {
+            // no pivot in this column
+            for i in pivots..rows {
+                m[i][j] = 0.;
+            }
+            continue;
+        }
+        m.swap(pivots, pivot);
+
+        let divisor = m[pivots][j];
+        for k in j..cols {
+            m[pivots][k] /= divisor;
+        }
+        m[pivots][j] = 1.;
+        for i in 0..rows {
+            if i == pivots || m[i][j] == 0. {
+                continue;
+            }
+            let factor = m[i][j];
+            for k in j..cols {
+                m[i][k] -= factor*m[pivots][k];
+            }
+            m[i][j] = 0.;
+        }
+        pivots += 1;
+    }
+    for x in m.iter_mut().flatten() {
+        if x.abs() <= tolerance {
+            *x = 0.;
+        }
+    }
+
+    return (m, pivots);
+}
+
+/// calculates the reduced row echelon form of a matrix.
+#[doc(hidden)]
+pub fn rref(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    return row_reduce(a).0;
+}
+
+/// calculates the rank of a matrix as the number of pivots of its reduced row echelon form.
+#[doc(hidden)]
+pub fn rank(a: &[Vec<f64>]) -> usize {
+    return row_reduce(a).1;
+}
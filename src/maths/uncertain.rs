@@ -0,0 +1,66 @@
+use crate::basetypes::Value;
+
+// Values with an uncertainty are propagated using linear (first order) error propagation,
+// assuming that all uncertainties are independent of each other. Scalars are treated as
+// uncertain values with an uncertainty of zero.
+
+fn to_pair(v: &Value) -> Option<(f64, f64)> {
+    match v {
+        Value::Scalar(a) => return Some((*a, 0.)),
+        Value::Uncertain(a, s) => return Some((*a, *s)),
+        _ => return None
+    }
+}
+
+fn combine(lv: &Value, rv: &Value, op: &str, f: fn((f64, f64), (f64, f64)) -> (f64, f64)) -> Result<Value, String> {
+    match (to_pair(lv), to_pair(rv)) {
+        (Some(a), Some(b)) => {
+            let (v, s) = f(a, b);
+            return Ok(Value::Uncertain(v, s.abs()));
+        },
+        _ => return Err(format!("Can't {} uncertain value and vector or matrix!", op))
+    }
+}
+
+/// applies a function f with derivative df to an uncertain value.
+pub fn apply(a: f64, s: f64, f: fn(f64) -> f64, df: fn(f64) -> f64) -> Value {
+    return Value::Uncertain(f(a), (df(a)*s).abs());
+}
+
+pub fn add(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return combine(lv, rv, "add", |(a, sa), (b, sb)| (a+b, sa.hypot(sb)));
+}
+
+pub fn sub(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return combine(lv, rv, "subtract", |(a, sa), (b, sb)| (a-b, sa.hypot(sb)));
+}
+
+pub fn mult(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return combine(lv, rv, "multiply", |(a, sa), (b, sb)| (a*b, (b*sa).hypot(a*sb)));
+}
+
+pub fn div(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return combine(lv, rv, "divide", |(a, sa), (b, sb)| (a/b, (sa/b).hypot(a*sb/b.powi(2))));
+}
+
+pub fn pow(lv: &Value, rv: &Value) -> Result<Value, String> {
+    return combine(lv, rv, "exponentiate", |(a, sa), (b, sb)| {
+        let v = a.powf(b);
+        // the uncertainty of the exponent only contributes if it is non zero, which avoids
+        // NaNs for negative bases
+        let exp_part = if sb == 0. {0.} else {v*a.ln()*sb};
+        return (v, (b*a.powf(b-1.)*sa).hypot(exp_part));
+    });
+}
+
+pub fn root(lv: &Value, rv: &Value) -> Result<Value, String> {
+    let exponent = div(&Value::Scalar(1.), rv)?;
+    return pow(lv, &exponent);
+}
+
+pub fn with_uncertainty(lv: &Value, rv: &Value) -> Result<Value, String> {
+    match (lv, rv) {
+        (Value::Scalar(a), Value::Scalar(s)) => return Ok(Value::Uncertain(*a, s.abs())),
+        _ => return Err("Can only give a scalar an uncertainty of a scalar!".to_string())
+    }
+}
@@ -1,4 +1,103 @@
-use crate::{basetypes::{AdvancedOpType, AdvancedOperation, Operation, SimpleOpType, Value, Variable, AST}, errors::{EvalError, ParserError}, helpers::{cart_prod, get_args}, maths, roots::RootFinder, Context, Values};
+use std::{borrow::Cow, cell::RefCell};
+
+use crate::{basetypes::{AdvancedOpType, AdvancedOperation, Operation, SimpleOpType, Value, Variable, AST, DEFAULT_CONSTANTS, VAR_SYMBOLS}, errors::{EvalError, ParserError, ParserWarning}, helpers::{cart_prod, get_args, split_solution_index}, maths, roots::RootFinder, Context, Values};
+
+/// the functions built into the parser together with the operation they are parsed to and their
+/// number of arguments, e.g. ("sin", SimpleOpType::Sin, 1) for sin(x). The second argument of var
+/// and std is optional.
+pub const FUNCTIONS: [(&str, SimpleOpType, usize); 58] = [
+    ("sin", SimpleOpType::Sin, 1), ("cos", SimpleOpType::Cos, 1), ("tan", SimpleOpType::Tan, 1),
+    ("abs", SimpleOpType::Abs, 1), ("sqrt", SimpleOpType::Sqrt, 1), ("root", SimpleOpType::Root, 2),
+    ("ln", SimpleOpType::Ln, 1), ("log1p", SimpleOpType::Log1p, 1), ("expm1", SimpleOpType::Expm1, 1),
+    ("arcsin", SimpleOpType::Arcsin, 1), ("arccos", SimpleOpType::Arccos, 1),
+    ("arctan", SimpleOpType::Arctan, 1), ("spectral_radius", SimpleOpType::SpectralRadius, 1),
+    ("norm2", SimpleOpType::Norm2, 1),
+    ("heaviside", SimpleOpType::Heaviside, 1), ("dirac", SimpleOpType::Dirac, 2),
+    ("det", SimpleOpType::Det, 1), ("mean", SimpleOpType::Mean, 1), ("var", SimpleOpType::Var, 2),
+    ("std", SimpleOpType::Std, 2), ("vsum", SimpleOpType::VSum, 1), ("vprod", SimpleOpType::VProd, 1),
+    ("vmin", SimpleOpType::VMin, 1), ("vmax", SimpleOpType::VMax, 1),
+    ("steady_state", SimpleOpType::SteadyState, 1), ("identity", SimpleOpType::Identity, 1),
+    ("zeros", SimpleOpType::Zeros, 2), ("ones", SimpleOpType::Ones, 2),
+    ("diag", SimpleOpType::Diag, 1), ("range", SimpleOpType::Range, 2),
+    ("continued_fraction", SimpleOpType::ContinuedFraction, 2), ("atan2", SimpleOpType::Atan2, 2),
+    ("min", SimpleOpType::Min, 2), ("max", SimpleOpType::Max, 2), ("gcd", SimpleOpType::Gcd, 2),
+    ("lcm", SimpleOpType::Lcm, 2), ("choose", SimpleOpType::Choose, 2),
+    ("perm", SimpleOpType::Perm, 2), ("rank", SimpleOpType::Rank, 1), ("rref", SimpleOpType::Rref, 1),
+    ("row", SimpleOpType::Row, 2), ("col", SimpleOpType::Col, 2), ("concat", SimpleOpType::Concat, 2),
+    ("vstack", SimpleOpType::VStack, 2), ("hstack", SimpleOpType::HStack, 2),
+    ("outer", SimpleOpType::Outer, 2), ("kron", SimpleOpType::Kron, 2),
+    ("sign", SimpleOpType::Sign, 1), ("cot", SimpleOpType::Cot, 1), ("sec", SimpleOpType::Sec, 1),
+    ("csc", SimpleOpType::Csc, 1), ("arsinh", SimpleOpType::Arsinh, 1),
+    ("arcosh", SimpleOpType::Arcosh, 1), ("artanh", SimpleOpType::Artanh, 1),
+    ("linspace", SimpleOpType::Linspace, 3), ("clamp", SimpleOpType::Clamp, 3),
+    ("slice", SimpleOpType::Slice, 3), ("reshape", SimpleOpType::Reshape, 3)
+];
+
+/// the names of the functions built into the parser, e.g. sin(x).
+pub const FUNCTION_NAMES: [&str; FUNCTIONS.len()] = {
+    let mut names = [""; FUNCTIONS.len()];
+    let mut i = 0;
+    while i < FUNCTIONS.len() {
+        names[i] = FUNCTIONS[i].0;
+        i += 1;
+    }
+    names
+};
+
+/// the names of the advanced operations built into the parser, e.g. I(x^2, x, 0, 1).
+pub const ADVANCED_OPERATION_NAMES: [&str; 6] = ["I", "D", "grad", "sum", "prod", "eq"];
+
+/// specifies the class of a single token of an expression, see [classify_token].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenClass {
+    Number,
+    /// a function built into the parser (see [FUNCTION_NAMES])
+    Function,
+    /// an advanced operation like an integral or an equation (see [ADVANCED_OPERATION_NAMES])
+    AdvancedOperation,
+    /// anything with a valid variable name, including the names of user defined functions
+    Variable,
+    Operator,
+    /// parentheses, brackets, curly brackets and commas
+    Delimiter,
+    Unknown
+}
+
+/// classifies a single token of an expression (e.g. "sin", "3.5", "+" or "x"), which can be used
+/// for syntax highlighting.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(classify_token("sin"), TokenClass::Function);
+/// assert_eq!(classify_token("foo"), TokenClass::Variable);
+/// ```
+pub fn classify_token(token: &str) -> TokenClass {
+    let token = token.trim();
+    if token.is_empty() {
+        return TokenClass::Unknown;
+    }
+    if token.parse::<f64>().is_ok() && token.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+        return TokenClass::Number;
+    }
+    if FUNCTION_NAMES.contains(&token) {
+        return TokenClass::Function;
+    }
+    if ADVANCED_OPERATION_NAMES.contains(&token) {
+        return TokenClass::AdvancedOperation;
+    }
+    // or is written as ||, a single | is not an operator
+    if token != "|" && token.chars().all(|c| get_op_symbol(c).is_some() || c == '!' || c == '=') {
+        return TokenClass::Operator;
+    }
+    if token.len() == 1 && "()[]{},".contains(token) {
+        return TokenClass::Delimiter;
+    }
+    if is_valid_var_name(token.to_string()) {
+        return TokenClass::Variable;
+    }
+    return TokenClass::Unknown;
+}
 
 fn get_op_symbol(c: char) -> Option<SimpleOpType> {
     match c {
@@ -8,12 +107,95 @@ fn get_op_symbol(c: char) -> Option<SimpleOpType> {
         '&' => Some(SimpleOpType::AddSub),
         '*' => Some(SimpleOpType::Mult),
         '/' => Some(SimpleOpType::Div),
+        '\\' => Some(SimpleOpType::LeftDiv),
         '^' => Some(SimpleOpType::Pow),
         '#' => Some(SimpleOpType::Cross),
+        '|' => Some(SimpleOpType::Or),
+        '<' => Some(SimpleOpType::Less),
+        '>' => Some(SimpleOpType::Greater),
+        #[cfg(feature = "uncertainty")]
+        '±' => Some(SimpleOpType::Uncertainty),
         _ => None
     }
 }
 
+// checks if the backslash at i starts the name of a greek letter like \alpha, instead of being a
+// left division
+fn starts_symbol_name(expr_chars: &[char], i: usize) -> bool {
+    for (name, _) in VAR_SYMBOLS {
+        if !name.starts_with('\\') {
+            continue;
+        }
+        let name_chars: Vec<char> = name.chars().collect();
+        let end = i + name_chars.len();
+        if end <= expr_chars.len() && expr_chars[i..end] == name_chars[..] && (end == expr_chars.len() || !expr_chars[end].is_alphabetic()) {
+            return true;
+        }
+    }
+    return false;
+}
+
+// returns the length of the exponent marker (e or E, including a sign) if the character at i
+// starts the exponent of a number in scientific notation (3e5, 2.5e-3) and 0 otherwise
+fn exponent_len(expr_chars: &[char], i: usize) -> usize {
+    if i == 0 || (expr_chars[i] != 'e' && expr_chars[i] != 'E') || !(expr_chars[i-1].is_ascii_digit() || expr_chars[i-1] == '.') {
+        return 0;
+    }
+    // digits that are part of a name (e.g. x_1e5) don't form a number
+    let mut j = i;
+    while j > 0 && (expr_chars[j-1].is_ascii_digit() || expr_chars[j-1] == '.') {
+        j -= 1;
+    }
+    if j > 0 && (expr_chars[j-1].is_alphabetic() || expr_chars[j-1] == '_') {
+        return 0;
+    }
+    let mut len = 1;
+    if i+1 < expr_chars.len() && (expr_chars[i+1] == '+' || expr_chars[i+1] == '-') {
+        len = 2;
+    }
+    if i+len < expr_chars.len() && expr_chars[i+len].is_ascii_digit() {
+        return len;
+    }
+    return 0;
+}
+
+fn check_overflow(v: Value, op: &str) -> Result<Value, EvalError> {
+    if v.is_inf_or_nan() {
+        return Err(EvalError::Overflow(op.to_string()));
+    }
+    return Ok(v);
+}
+
+/// returns the binary operations ordered from the loosest to the tightest binding one. The
+/// expression is split at the loosest binding operation first.
+pub(crate) fn binary_op_types() -> Vec<SimpleOpType> {
+    let op_types = vec![SimpleOpType::Or, SimpleOpType::And, SimpleOpType::Less, SimpleOpType::Greater, SimpleOpType::LessEq, SimpleOpType::GreaterEq, SimpleOpType::Add, SimpleOpType::Sub, SimpleOpType::AddSub, SimpleOpType::Mult, SimpleOpType::Div, SimpleOpType::LeftDiv, SimpleOpType::Cross, SimpleOpType::HiddenMult, SimpleOpType::Pow, SimpleOpType::Get];
+    // a+-b binds tighter than addition and subtraction, but looser than multiplication
+    #[cfg(feature = "uncertainty")]
+    let op_types = {
+        let mut op_types = op_types;
+        op_types.insert(op_types.iter().position(|o| *o == SimpleOpType::Mult).unwrap(), SimpleOpType::Uncertainty);
+        op_types
+    };
+    return op_types;
+}
+
+pub(crate) fn is_comparison(op: &SimpleOpType) -> bool {
+    match op {
+        SimpleOpType::Less | SimpleOpType::Greater | SimpleOpType::LessEq | SimpleOpType::GreaterEq => return true,
+        _ => return false
+    }
+}
+
+/// whether the result of the operation depends on the [AngleMode](crate::AngleMode) of the context.
+pub(crate) fn depends_on_angle_mode(op: &SimpleOpType) -> bool {
+    match op {
+        SimpleOpType::Sin | SimpleOpType::Cos | SimpleOpType::Tan | SimpleOpType::Cot | SimpleOpType::Sec | SimpleOpType::Csc |
+        SimpleOpType::Arcsin | SimpleOpType::Arccos | SimpleOpType::Arctan | SimpleOpType::Atan2 => return true,
+        _ => return false
+    }
+}
+
 /// checks if the given variable name is a valid variable name.
 pub fn is_valid_var_name(var: String) -> bool {
     let var_chars: Vec<char> = var.chars().collect();
@@ -37,6 +219,10 @@ pub fn is_valid_var_name(var: String) -> bool {
             || i == '/'
             || i == '^'
             || i == '#' 
+            || i == '<'
+            || i == '>'
+            || i == '|'
+            || i == '!'
             || i == '=')
             && parenths_open == 0{
             return false
@@ -113,9 +299,131 @@ fn parse_value(s: String) -> Result<AST, ParserError> {
 }
 
 /// used to construct an AST from a string.
+///
+/// Whitespace (including tabs and newlines) is ignored, except between two numbers: an input like
+/// "1 1/2" is rejected with [ParserError::AmbiguousNumber] instead of being read as 11/2. Mixed
+/// numbers have to be written as an explicit sum (1+1/2).
+///
+/// This allows expressions to be split across multiple lines, e.g. a matrix written with one row
+/// per line or a long expression continued on the next line after a trailing operator ("1+\n2").
+///
+/// A number that is directly followed by e or E and an integer exponent is read as a number in
+/// scientific notation (3e5 = 300000, 2.5e-3 = 0.0025). Euler's number is only used where no
+/// exponent follows (3e = 3*e, 3e^2 = 3*e^2), so multiplying with it has to be written
+/// explicitly in these cases (3*e*5).
+///
+/// With the feature "uncertainty", a+-b (written without whitespace between + and -) and a±b give
+/// a value an uncertainty, while a + -b is still a sum.
 pub fn parse<S: Into<String>>(expr: S) -> Result<AST, ParserError> {
-    let whitespaced_string: String = expr.into().trim().split(" ").filter(|s| !s.is_empty()).collect();
-    parse_inner(&whitespaced_string)
+    let parts: Vec<String> = expr.into().split(char::is_whitespace).filter(|s| !s.is_empty()).map(|s| s.to_string()).collect();
+    // numbers only separated by whitespace (e.g. the mixed number 1 1/2) would otherwise get joined
+    // into a single number (11/2)
+    for i in 1..parts.len() {
+        let is_number_char = |c: &char| c.is_ascii_digit() || *c == '.';
+        if parts[i-1].chars().last().is_some_and(|c| is_number_char(&c)) && parts[i].chars().next().is_some_and(|c| is_number_char(&c)) {
+            let left: String = parts[i-1].chars().rev().take_while(is_number_char).collect::<Vec<char>>().iter().rev().collect();
+            let right: String = parts[i].chars().take_while(is_number_char).collect();
+            return Err(ParserError::AmbiguousNumber(format!("{} {}", left, right)));
+        }
+    }
+    // +- only gives a value an uncertainty if it is written without whitespace in between, so that
+    // e.g. 3 + -2 stays a sum
+    #[cfg(feature = "uncertainty")]
+    let parts: Vec<String> = parts.iter().map(|p| p.replace("+-", "±")).collect();
+    parse_inner(&parts.concat())
+}
+
+thread_local! {
+    // collects the warnings of the parser while parse_with_warnings is running
+    static WARNINGS: RefCell<Option<Vec<ParserWarning>>> = const { RefCell::new(None) };
+}
+
+fn warn(warning: ParserWarning) {
+    WARNINGS.with(|w| {
+        if let Some(warnings) = w.borrow_mut().as_mut() {
+            warnings.push(warning);
+        }
+    });
+}
+
+/// used to construct an AST from a string like [parse], but additionally returns warnings
+/// describing the interpretations chosen for ambiguous inputs, e.g. that 2e has been interpreted as
+/// 2*e or that xy has been interpreted as a single variable.
+///
+/// # Example
+///
+/// ```
+/// let (ast, warnings) = parse_with_warnings("xy")?;
+///
+/// assert_eq!(warnings, vec![ParserWarning::MultiLetterVariable("xy".to_string())]);
+/// ```
+pub fn parse_with_warnings<S: Into<String>>(expr: S) -> Result<(AST, Vec<ParserWarning>), ParserError> {
+    WARNINGS.with(|w| *w.borrow_mut() = Some(vec![]));
+    let res = parse(expr);
+    let warnings = WARNINGS.with(|w| w.borrow_mut().take()).unwrap_or_default();
+    return Ok((res?, warnings));
+}
+
+thread_local! {
+    // the aliases used while parse_with_aliases is running
+    static ALIASES: RefCell<Vec<(String, String)>> = const { RefCell::new(vec![]) };
+}
+
+/// used to construct an AST from a string like [parse], but with the given aliases for function
+/// names. Each alias is given as a pair of the alias and the name of the function it stands for
+/// (see [Context::add_alias](crate::Context::add_alias)). Aliases are not resolved recursively.
+///
+/// # Example
+///
+/// ```
+/// let ast = parse_with_aliases("asin(0)", &[("asin".to_string(), "arcsin".to_string())])?;
+///
+/// assert_eq!(ast, parse("arcsin(0)")?);
+/// ```
+pub fn parse_with_aliases<S: Into<String>>(expr: S, aliases: &[(String, String)]) -> Result<AST, ParserError> {
+    let previous = ALIASES.with(|a| a.replace(aliases.to_vec()));
+    let res = parse(expr);
+    ALIASES.with(|a| *a.borrow_mut() = previous);
+    return res;
+}
+
+/// replaces commas used as decimal separators (e.g. "3,14") with points, so the expression can be
+/// parsed by [parse] or evaluated by [quick_eval](crate::quick_eval). As commas also separate the
+/// arguments of functions and the entries of vectors, matrices and lists, only commas directly
+/// between two digits outside of these are replaced.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(normalize_decimal_separator("3,14*sin(1,5)+[1,5, 2]"), "3.14*sin(1,5)+[1,5, 2]");
+/// assert_eq!(normalize_decimal_separator("(1,5+2,5)*2"), "(1.5+2.5)*2");
+/// ```
+pub fn normalize_decimal_separator<S: Into<String>>(expr: S) -> String {
+    let chars: Vec<char> = expr.into().chars().collect();
+    let mut output = String::new();
+    // whether the commas of each currently open delimiter separate arguments
+    let mut arg_lists: Vec<bool> = vec![];
+    for i in 0..chars.len() {
+        match chars[i] {
+            '(' => {
+                // the parentheses of a function call directly follow its name, which starts with a letter
+                let mut j = i;
+                while j > 0 && (chars[j-1].is_alphanumeric() || chars[j-1] == '_') {
+                    j -= 1;
+                }
+                arg_lists.push(j < i && chars[j].is_alphabetic());
+            },
+            '[' | '{' => arg_lists.push(true),
+            ')' | ']' | '}' => {arg_lists.pop();},
+            ',' if !arg_lists.last().unwrap_or(&false) && i > 0 && chars[i-1].is_ascii_digit() && chars.get(i+1).is_some_and(|c| c.is_ascii_digit()) => {
+                output.push('.');
+                continue;
+            },
+            _ => {}
+        }
+        output.push(chars[i]);
+    }
+    return output;
 }
 
 fn parse_inner(expr: &str) -> Result<AST, ParserError> {
@@ -158,17 +466,34 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
 
     //is it an operation?
     
-    let op_types = vec![SimpleOpType::Add, SimpleOpType::Sub, SimpleOpType::AddSub, SimpleOpType::Mult, SimpleOpType::Div, SimpleOpType::Cross, SimpleOpType::HiddenMult, SimpleOpType::Pow, SimpleOpType::Get];
+    let op_types = binary_op_types();
     let mut ops_in_expr: Vec<(SimpleOpType, usize, usize, usize)> = vec![];
-    let mut highest_op = 7;
+    let mut highest_op = op_types.len();
     let mut last_char = '\\';
     let mut brackets_open = 0;
     let mut curly_brackets_open = 0;
+    let mut skip_next = false;
     for i in 0..expr_chars.len() {
+        if skip_next {
+            skip_next = false;
+            last_char = expr_chars[i];
+            continue;
+        }
+        let exp_len = exponent_len(&expr_chars, i);
+        if exp_len > 0 {
+            skip_next = exp_len == 2;
+            last_char = expr_chars[i];
+            continue;
+        }
         let mut is_hidden_mult = false;
-        if (last_char.is_digit(10) && (expr_chars[i].is_alphabetic() || expr_chars[i] == '\\' || expr_chars[i] == '(' || expr_chars[i] == '['))||(last_char == ')' && expr_chars[i] == '(') {
+        if (last_char.is_ascii_digit() && (expr_chars[i].is_alphabetic() || (expr_chars[i] == '\\' && starts_symbol_name(&expr_chars, i)) || expr_chars[i] == '(' || expr_chars[i] == '['))||(last_char == ')' && expr_chars[i] == '(') {
             is_hidden_mult = true;
-            if i as i32-2 > 0 && expr_chars[i-2] == '_' {
+            // digits that are part of a name (e.g. x_1 or log1p) don't start a number
+            let mut j = i;
+            while j > 0 && (expr_chars[j-1].is_ascii_digit() || expr_chars[j-1] == '.') {
+                j -= 1;
+            }
+            if last_char != ')' && j > 0 && (expr_chars[j-1].is_alphabetic() || expr_chars[j-1] == '_') && exponent_len(&expr_chars, j-1) == 0 {
                 is_hidden_mult = false;
             }
         }
@@ -200,9 +525,31 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
             curly_brackets_open -= 1;
             continue;
         }
-        let symbol = get_op_symbol(expr_chars[i]);
-        if parenths_open == 0 && brackets_open == 0 && curly_brackets_open == 0 && i != 0 && i != expr_chars.len()-1 && symbol.is_some() {
-            ops_in_expr.push((symbol.unwrap(), i, 0, 1));
+        let mut symbol = get_op_symbol(expr_chars[i]);
+        let mut op_len = 1;
+        // a backslash followed by the name of a greek letter starts a name like \alpha
+        if symbol == Some(SimpleOpType::LeftDiv) && starts_symbol_name(&expr_chars, i) {
+            symbol = None;
+        }
+        if i+1 < expr_chars.len() && expr_chars[i+1] == '=' {
+            match symbol {
+                Some(SimpleOpType::Less) => {symbol = Some(SimpleOpType::LessEq); op_len = 2},
+                Some(SimpleOpType::Greater) => {symbol = Some(SimpleOpType::GreaterEq); op_len = 2},
+                _ => {}
+            }
+        }
+        if i+1 < expr_chars.len() && expr_chars[i+1] == expr_chars[i] {
+            match symbol {
+                Some(SimpleOpType::AddSub) => {symbol = Some(SimpleOpType::And); op_len = 2; skip_next = true},
+                Some(SimpleOpType::Or) => {op_len = 2; skip_next = true},
+                _ => {}
+            }
+        } else if symbol == Some(SimpleOpType::Or) {
+            // or has to be written as ||, a single | is not an operator
+            symbol = None;
+        }
+        if parenths_open == 0 && brackets_open == 0 && curly_brackets_open == 0 && i != 0 && i + op_len < expr_chars.len() && symbol.is_some() {
+            ops_in_expr.push((symbol.unwrap(), i, 0, op_len));
         } 
     }
 
@@ -215,13 +562,51 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
         }
     }
 
-    if highest_op == 1 || highest_op == 3 {
+    // chained comparisons (a<b<c) are evaluated like a<b and b<c
+
+    let comparisons = ops_in_expr.iter().filter(|o| is_comparison(&o.0)).collect::<Vec<_>>();
+
+    if highest_op < op_types.len() && is_comparison(&op_types[highest_op]) && comparisons.len() > 1 {
+        let mut operands = vec![];
+        let mut last_end = 0;
+        for c in &comparisons {
+            operands.push(parse_inner(&expr_chars[last_end..c.1].iter().collect::<String>())?);
+            last_end = c.1 + c.3;
+        }
+        operands.push(parse_inner(&expr_chars[last_end..].iter().collect::<String>())?);
+
+        let mut chain = AST::from_operation(Operation::SimpleOperation {
+            op_type: comparisons[0].0.clone(),
+            left: operands[0].clone(),
+            right: operands[1].clone()
+        });
+        for (j, c) in comparisons.iter().enumerate().skip(1) {
+            chain = AST::from_operation(Operation::SimpleOperation {
+                op_type: SimpleOpType::And,
+                left: chain,
+                right: AST::from_operation(Operation::SimpleOperation {
+                    op_type: c.0.clone(),
+                    left: operands[j].clone(),
+                    right: operands[j+1].clone()
+                })
+            });
+        }
+
+        return Ok(chain);
+    }
+
+    if highest_op < op_types.len() && (op_types[highest_op] == SimpleOpType::Sub || op_types[highest_op] == SimpleOpType::Mult) {
         ops_in_expr.reverse();
     }
 
     for o in op_types {
         for i in &ops_in_expr {
             if i.0 == o {
+                if i.0 == SimpleOpType::HiddenMult {
+                    let left: String = expr_chars[0..(i.1-i.2)].iter().collect();
+                    let right: String = expr_chars[(i.1+i.3)..].iter().collect();
+                    warn(ParserWarning::HiddenMult(expr.to_string(), format!("{}*{}", left, right)));
+                }
                 let left_b = parse_inner(&expr_chars[0..(i.1-i.2)].to_vec().iter().collect::<String>())?;
                 let right_b = parse_inner(&expr_chars[(i.1+i.3)..].to_vec().iter().collect::<String>())?; 
                 return Ok(AST::from_operation(Operation::SimpleOperation {
@@ -243,6 +628,26 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
         }));
     }
 
+    // is it a logical not?
+
+    if expr_chars[0] == '!' {
+        return Ok(AST::from_operation(Operation::SimpleOperation {
+            op_type: SimpleOpType::Not,
+            left: parse_inner(&expr_chars[1..].to_vec().iter().collect::<String>())?,
+            right: AST::from_value(Value::Scalar(0.))
+        }));
+    }
+
+    // is it a factorial?
+
+    if expr_chars[expr_chars.len()-1] == '!' {
+        return Ok(AST::from_operation(Operation::SimpleOperation {
+            op_type: SimpleOpType::Factorial,
+            left: parse_inner(&expr_chars[..expr_chars.len()-1].to_vec().iter().collect::<String>())?,
+            right: AST::from_value(Value::Scalar(0.))
+        }));
+    }
+
     // is it a plus minus?
     
     if expr_chars[0] == '&' {
@@ -253,31 +658,72 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
         }));
     }
 
+    // is it an alias of a function?
+
+    let alias = ALIASES.with(|a| a.borrow().iter().find(|a| expr.starts_with(&format!("{}(", a.0))).cloned());
+    let aliased_expr: String;
+    let expr = match alias {
+        Some((alias, name)) => {
+            aliased_expr = format!("{}{}", name, &expr[alias.len()..]);
+            expr_chars = aliased_expr.chars().collect();
+            aliased_expr.as_str()
+        },
+        None => expr
+    };
+
     // is it a function?
 
-    let function_look_up = vec![(SimpleOpType::Sin, "sin("), (SimpleOpType::Cos, "cos("), (SimpleOpType::Tan, "tan("), (SimpleOpType::Abs, "abs("), (SimpleOpType::Sqrt, "sqrt("), (SimpleOpType::Root, "root("), (SimpleOpType::Ln, "ln("), (SimpleOpType::Arcsin, "arcsin("), (SimpleOpType::Arccos, "arccos("), (SimpleOpType::Arctan, "arctan(")];
-    
-    for i in function_look_up {
-        if expr_chars.iter().collect::<String>().starts_with(i.1) {
-            if i.0 == SimpleOpType::Root {
-                let args = get_args(&expr_chars[i.1.len()..expr_chars.len()-1]);
+    for (name, op_type, arity) in FUNCTIONS {
+        let prefix = format!("{}(", name);
+        if expr_chars.iter().collect::<String>().starts_with(&prefix) {
+            if op_type == SimpleOpType::Var || op_type == SimpleOpType::Std {
+                // the delta degrees of freedom are optional and default to 0 (population)
+                let args = get_args(&expr_chars[prefix.len()..expr_chars.len()-1]);
+
+                if args.len() != 1 && args.len() != 2 {
+                    return Err(ParserError::WrongNumberOfArgs(name.to_string()));
+                }
+                let right_b = match args.get(1) {
+                    Some(a) => parse_inner(a)?,
+                    None => AST::from_value(Value::Scalar(0.))
+                };
+
+                return Ok(AST::from_operation(Operation::SimpleOperation {
+                    op_type,
+                    left: parse_inner(&args[0])?,
+                    right: right_b
+                }));
+            } else if arity == 2 {
+                let args = get_args(&expr_chars[prefix.len()..expr_chars.len()-1]);
 
                 if args.len() != 2 {
-                    return Err(ParserError::WrongNumberOfArgs("root".to_string()));
+                    return Err(ParserError::WrongNumberOfArgs(name.to_string()));
                 } else {
                     let left_b = parse_inner(&args[0].clone())?;
                     let right_b = parse_inner(&args[1].clone())?;
 
                     return Ok(AST::from_operation(Operation::SimpleOperation { 
-                        op_type: i.0,
+                        op_type,
                         left: left_b,
                         right: right_b
                     }));
                 }
+            } else if arity == 3 {
+                let args = get_args(&expr_chars[prefix.len()..expr_chars.len()-1]);
+
+                if args.len() != 3 {
+                    return Err(ParserError::WrongNumberOfArgs(name.to_string()));
+                }
+                // the second and third argument are stored as a vector on the right side
+                return Ok(AST::from_operation(Operation::SimpleOperation {
+                    op_type,
+                    left: parse_inner(&args[0])?,
+                    right: AST::Vector(Box::new(vec![parse_inner(&args[1])?, parse_inner(&args[2])?]))
+                }));
             } else {
-                let left_b = parse_inner(&expr_chars[i.1.len()..expr_chars.len()-1].to_vec().iter().collect::<String>())?;
+                let left_b = parse_inner(&expr_chars[prefix.len()..expr_chars.len()-1].to_vec().iter().collect::<String>())?;
                 return Ok(AST::from_operation(Operation::SimpleOperation {
-                    op_type: i.0,
+                    op_type,
                     left: left_b,
                     right: AST::from_value(Value::Scalar(0.))
                 }));
@@ -287,22 +733,76 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
 
     // is it an advanced operation?
 
-    let advanced_op_look_up = vec![(AdvancedOpType::Integral, "I("), (AdvancedOpType::Derivative, "D("), (AdvancedOpType::Equation, "eq(")];
+    // in the same order as ADVANCED_OPERATION_NAMES
+    let advanced_op_look_up = [AdvancedOpType::Integral, AdvancedOpType::Derivative, AdvancedOpType::Gradient, AdvancedOpType::Sum, AdvancedOpType::Product, AdvancedOpType::Equation].into_iter().zip(ADVANCED_OPERATION_NAMES.map(|n| format!("{}(", n)));
 
     for i in advanced_op_look_up {
-        if expr_chars.iter().collect::<String>().starts_with(i.1) {
+        if expr_chars.iter().collect::<String>().starts_with(&i.1) {
             match i.0 {
                 AdvancedOpType::Derivative => {
                     let args = get_args(&expr_chars[i.1.len()..expr_chars.len()-1]);
                     
-                    if args.len() != 3 {
+                    if args.len() != 3 && args.len() != 4 {
                         return Err(ParserError::WrongNumberOfArgs("derivative".to_string()));
                     }
                     let parsed_function = parse_inner(&args[0])?;
                     let parsed_value_at = parse_inner(&args[2])?;
+                    let order = match args.get(3) {
+                        Some(o) => o.trim().parse::<usize>().map_err(|_| ParserError::ParseValue(o.to_string()))?,
+                        None => 1
+                    };
                     return Ok(AST::from_operation(Operation::AdvancedOperation(AdvancedOperation::Derivative {
                         expr: parsed_function,
                         in_terms_of: args[1].clone(),
+                        at: parsed_value_at,
+                        order
+                    })));
+                },
+                AdvancedOpType::Sum | AdvancedOpType::Product => {
+                    let args = get_args(&expr_chars[i.1.len()..expr_chars.len()-1]);
+
+                    if args.len() != 4 {
+                        return Err(ParserError::WrongNumberOfArgs(if i.0 == AdvancedOpType::Sum {"sum"} else {"product"}.to_string()));
+                    }
+                    if args[1].is_empty() || !is_valid_var_name(args[1].clone()) {
+                        return Err(ParserError::InvalidVariableName(args[1].clone()));
+                    }
+                    let parsed_function = parse_inner(&args[0])?;
+                    let parsed_lower_b = parse_inner(&args[2])?;
+                    let parsed_upper_b = parse_inner(&args[3])?;
+                    if i.0 == AdvancedOpType::Product {
+                        return Ok(AST::from_operation(Operation::AdvancedOperation(AdvancedOperation::Product {
+                            expr: parsed_function,
+                            in_terms_of: args[1].clone(),
+                            lower_bound: parsed_lower_b,
+                            upper_bound: parsed_upper_b
+                        })));
+                    }
+                    return Ok(AST::from_operation(Operation::AdvancedOperation(AdvancedOperation::Sum {
+                        expr: parsed_function,
+                        in_terms_of: args[1].clone(),
+                        lower_bound: parsed_lower_b,
+                        upper_bound: parsed_upper_b
+                    })));
+                },
+                AdvancedOpType::Gradient => {
+                    let args = get_args(&expr_chars[i.1.len()..expr_chars.len()-1]);
+
+                    if args.len() != 3 {
+                        return Err(ParserError::WrongNumberOfArgs("gradient".to_string()));
+                    }
+                    let parsed_function = parse_inner(&args[0])?;
+                    let vars_str = args[1].strip_prefix('[').and_then(|v| v.strip_suffix(']')).unwrap_or(&args[1]);
+                    let in_terms_of: Vec<String> = vars_str.split(',').map(|v| v.trim().to_string()).collect();
+                    for v in &in_terms_of {
+                        if v.is_empty() || !is_valid_var_name(v.clone()) {
+                            return Err(ParserError::InvalidVariableName(v.clone()));
+                        }
+                    }
+                    let parsed_value_at = parse_inner(&args[2])?;
+                    return Ok(AST::from_operation(Operation::AdvancedOperation(AdvancedOperation::Gradient {
+                        expr: parsed_function,
+                        in_terms_of,
                         at: parsed_value_at
                     })));
                 },
@@ -380,6 +880,11 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
             return Err(ParserError::InvalidVariableName(expr.to_string()));
         }
 
+        // inf isn't a constant, but the upper bound of infinite sums and products
+        if expr_chars.len() > 1 && expr_chars.iter().all(|c| c.is_alphabetic()) && expr != "inf" && !DEFAULT_CONSTANTS.iter().any(|(name, _)| *name == expr) {
+            warn(ParserWarning::MultiLetterVariable(expr.to_string()));
+        }
+
         return Ok(AST::from_variable_name(expr));
     }
 
@@ -397,17 +902,173 @@ fn parse_inner(expr: &str) -> Result<AST, ParserError> {
 /// used to evaluate a AST in the provided context.
 ///
 /// If you are searching for a quick and easy way to evaluate an expression, have a look at [quick_eval()](fn@crate::quick_eval).
+///
+/// Determinants and inverses of matrices with the same contents are only calculated once per
+/// evaluation, e.g. det(A) in det(A)+det(A).
+///
+/// # Order of multiple results
+///
+/// If parts of the expression have multiple values (e.g. variables holding multiple values or
+/// ±), every combination of them is evaluated. The results are always in lexicographic order of
+/// the combinations, where the leftmost part varies slowest and the rightmost part varies fastest:
+///
+/// - Operations: for every value of the left side, all values of the right side ({1, 2}+{10, 20}
+/// results in 11, 21, 12, 22). ± returns the sum before the difference.
+/// - Vectors: for every value of the first entry, all values of the second entry and so on.
+/// - Matrices: like vectors, with the entries taken row by row from left to right.
+/// - Functions and advanced operations: like vectors, in the order of their arguments.
+/// - Lists: the values of all entries one after another.
 pub fn eval(b: &AST, context: &Context) -> Result<Values, EvalError> {
-   Ok(Values::from_vec(eval_rec(b, context, "")?))
+   return Ok(eval_with_stats(b, context)?.0);
+}
+
+/// describes the work done during an evaluation (see [eval_with_stats]).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EvalStats {
+    /// the number of determinants that had to be calculated.
+    pub determinants: usize,
+    /// the number of matrix inverses that had to be calculated (e.g. for divisions by a matrix).
+    pub inversions: usize
 }
 
-fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, EvalError> {
+/// evaluates an AST like [eval], but additionally returns [EvalStats] about the evaluation.
+///
+/// # Example
+///
+/// ```
+/// let a = Variable::new("A", vec![Value::Matrix(vec![vec![1., 2.], vec![3., 4.]])]);
+/// let (res, stats) = eval_with_stats(&parse("det(A)+det(A)")?, &Context::from_vars(vec![a]))?;
+///
+/// assert_eq!(stats.determinants, 1);
+/// ```
+pub fn eval_with_stats(b: &AST, context: &Context) -> Result<(Values, EvalStats), EvalError> {
+    let (res, (determinants, inversions)) = maths::linalg::with_cache(|| eval_rec(b, &Scope::new(context, &[]), ""));
+    return Ok((Values::from_vec(res?), EvalStats { determinants, inversions }));
+}
+
+/// evaluates the same AST in each of the given contexts, returning the results in the same order
+/// as the contexts. The expression only has to be parsed once, which makes this useful for
+/// parameter sweeps or Monte Carlo simulations. Determinants and inverses are shared between the
+/// evaluations, so a matrix that is the same in every context is only e.g. inverted once.
+///
+/// The first error stops the evaluation and is returned.
+///
+/// # Example
+///
+/// ```
+/// let contexts: Vec<Context> = (1..=3).map(|i| Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(i as f64)])])).collect();
+/// let res = eval_many(&parse("x^2")?, &contexts)?;
+///
+/// assert_eq!(res[2].to_vec(), vec![Value::Scalar(9.)]);
+/// ```
+pub fn eval_many(b: &AST, contexts: &[Context]) -> Result<Vec<Values>, EvalError> {
+    return maths::linalg::with_cache(|| contexts.iter().map(|c| Ok(Values::from_vec(eval_rec(b, &Scope::new(c, &[]), "")?))).collect()).0;
+}
+
+/// evaluates an AST like [eval], but with the given variables shadowing the variables of the context
+/// with the same name. This avoids cloning the context just to (re)bind a few variables.
+pub(crate) fn eval_with_vars(b: &AST, context: &Context, vars: &[Variable]) -> Result<Values, EvalError> {
+   Ok(Values::from_vec(maths::linalg::with_cache(|| eval_rec(b, &Scope::new(context, vars), "")).0?))
+}
+
+/// a layer of variables shadowing the variables of the context (and of the outer layers), e.g. the
+/// inputs of a function while its body is evaluated.
+struct Scope<'a> {
+    context: &'a Context,
+    vars: &'a [Variable],
+    parent: Option<&'a Scope<'a>>
+}
+
+impl<'a> Scope<'a> {
+    fn new(context: &'a Context, vars: &'a [Variable]) -> Scope<'a> {
+        return Scope { context, vars, parent: None };
+    }
+    fn get_var(&self, name: &str) -> Option<&'a Variable> {
+        if let Some(v) = self.vars.iter().find(|v| v.name == name) {
+            return Some(v);
+        }
+        match self.parent {
+            Some(p) => return p.get_var(name),
+            None => return self.context.get_var(name)
+        }
+    }
+    /// returns a context containing the variables of all layers. The context only gets cloned if
+    /// there actually are shadowing variables.
+    fn to_context(&self) -> Cow<'a, Context> {
+        let mut layers = vec![];
+        let mut current = Some(self);
+        while let Some(scope) = current {
+            if !scope.vars.is_empty() {
+                layers.push(scope.vars);
+            }
+            current = scope.parent;
+        }
+        if layers.is_empty() {
+            return Cow::Borrowed(self.context);
+        }
+
+        let mut context = self.context.clone();
+        // add the outermost layer first, so that inner layers replace its variables. Within a layer
+        // the first variable with a name wins, just like in a lookup.
+        for layer in layers.iter().rev() {
+            for v in layer.iter().rev() {
+                context.add_var(v);
+            }
+        }
+        return Cow::Owned(context);
+    }
+}
+
+/// compiles an AST into a closure of a single variable, which evaluates the AST in the provided
+/// context with the variable bound to the given scalar.
+///
+/// The context is captured by the closure, so it doesn't have to be rebuilt on every call. If the
+/// AST evaluates to more than one value, the closure returns an error.
+///
+/// # Example
+///
+/// ```
+/// let f = compile(parse("x^2")?, "x", &Context::empty());
+///
+/// assert_eq!(f(3.)?, Value::Scalar(9.));
+/// ```
+pub fn compile<S: Into<String>>(ast: AST, var: S, context: &Context) -> impl Fn(f64) -> Result<Value, EvalError> {
+    let var = var.into();
+    let mut compiled_context = context.clone();
+    compiled_context.add_var(&Variable::new(&var, vec![Value::Scalar(0.)]));
+    // the variable is updated in place, so its position doesn't change between calls
+    let var_pos = compiled_context.vars.len()-1;
+    let compiled_context = RefCell::new(compiled_context);
+
+    return move |x: f64| {
+        let mut context = compiled_context.borrow_mut();
+        context.vars[var_pos].values = Values::from_vec(vec![Value::Scalar(x)]);
+        let mut res = eval_rec(&ast, &Scope::new(&context, &[]), "")?;
+        if res.len() != 1 {
+            return Err(EvalError::MathError("The compiled expression has to evaluate to a single value!".to_string()));
+        }
+        return Ok(res.remove(0));
+    };
+}
+
+/// evaluates the upper bound of a sum or product. The variable inf stands for an infinite bound,
+/// unless it is defined in the scope.
+fn eval_upper_bound(b: &AST, scope: &Scope, last_fn: &str) -> Result<Vec<Value>, EvalError> {
+    if let AST::Variable(name) = b {
+        if name == "inf" && scope.get_var(name).is_none() {
+            return Ok(vec![Value::Scalar(f64::INFINITY)]);
+        }
+    }
+    return eval_rec(b, scope, last_fn);
+}
+
+fn eval_rec(b: &AST, scope: &Scope, last_fn: &str) -> Result<Vec<Value>, EvalError> {
     match b {
         AST::Scalar(s) => return Ok(vec![Value::Scalar(*s)]),
         AST::Vector(v) => {
             let mut evaled_fields: Vec<Vec<f64>> = vec![];
             for i in &**v {
-                let values = eval_rec(i, context, last_fn)?;
+                let values = eval_rec(i, scope, last_fn)?;
                 for i in &values {
                     if i.get_scalar().is_none() {
                         return Err(EvalError::NonScalarInVector);
@@ -425,7 +1086,7 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
             for i in &**m {
                 let mut row = vec![];
                 for j in i {
-                    let values = eval_rec(j, context, last_fn)?;
+                    let values = eval_rec(j, scope, last_fn)?;
                     for i in &values {
                         if i.get_scalar().is_none() {
                             return Err(EvalError::NonScalarInMatrix);
@@ -445,33 +1106,31 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
             Ok(permuts.iter().map(|m| Value::Matrix(m.to_vec())).collect())
         },
         AST::List(l) => {
-            return Ok(l.iter().map(|e| eval_rec(e, context, last_fn)).collect::<Result<Vec<Vec<Value>>, EvalError>>()?.into_iter().flatten().collect());
+            return Ok(l.iter().map(|e| eval_rec(e, scope, last_fn)).collect::<Result<Vec<Vec<Value>>, EvalError>>()?.into_iter().flatten().collect());
         }
         AST::Variable(v) => {
-            for i in context.vars.iter() {
-                if &i.name == v {
-                    return Ok(i.values.clone().to_vec());
+            match scope.get_var(v) {
+                Some(var) => return Ok(var.values.clone().to_vec()),
+                None => {
+                    // x_0, x_1, ... refer to the individual values of a variable x holding multiple
+                    // values (e.g. the solutions of an equation)
+                    if let Some((base, index)) = split_solution_index(v) {
+                        if let Some(value) = scope.get_var(base).and_then(|var| var.values.get(index)) {
+                            return Ok(vec![value.clone()]);
+                        }
+                    }
+                    return Err(EvalError::NoVariable(v.to_string()));
                 }
             }
-
-            return Err(EvalError::NoVariable(v.to_string()));
         },
         AST::Function { name, inputs } => {
             if last_fn == name {
                 return Err(EvalError::RecursiveFunction);
             }
-            let mut function = None;
-            for i in context.funs.iter() {
-                if i.name == name.to_string() {
-                    function = Some(i);
-                    break;
-                } 
-            }
-            if function.is_none() {
-                return Err(EvalError::NoFunction(name.to_string()));
-            }
-
-            let function = function.unwrap();
+            let function = match scope.context.get_fun(name) {
+                Some(f) => f,
+                None => return Err(EvalError::NoFunction(name.to_string()))
+            };
             
             if inputs.len() != function.inputs.len() {
                 return Err(EvalError::WrongNumberOfArgs((function.inputs.len(), inputs.len())));
@@ -479,7 +1138,7 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
 
             let mut eval_inputs = vec![];
             for i in inputs.iter() {
-                eval_inputs.push(eval_rec(i, context, last_fn)?);
+                eval_inputs.push(eval_rec(i, scope, last_fn)?);
             }
 
             let permuts = cart_prod(&eval_inputs);
@@ -492,12 +1151,8 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
                     f_vars.push(Variable::new(&function.inputs[i], vec![p[i].clone()]));
                 }
 
-                for i in context.vars.iter() {
-                    if !f_vars.iter().map(|v| v.name.to_string()).collect::<Vec<String>>().contains(&i.name) {
-                        f_vars.push(i.clone());
-                    }
-                }
-                res.push(eval_rec(&function.ast, &Context::new(&f_vars, &context.funs), name)?);
+                let f_scope = Scope { context: scope.context, vars: &f_vars, parent: Some(scope) };
+                res.push(eval_rec(&function.ast, &f_scope, name)?);
             }
 
             return Ok(res.into_iter().flatten().collect());
@@ -505,34 +1160,113 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
         AST::Operation(o) => {
             match &**o {
                 Operation::SimpleOperation {op_type, left, right} => {
-                    let lv = eval_rec(&left, context, last_fn)?;
-                    let rv = eval_rec(&right, context, last_fn)?;
+                    let lv = eval_rec(&left, scope, last_fn)?;
+
+                    // short circuit logical operations if the left side already decides the result
+
+                    if *op_type == SimpleOpType::And && lv.iter().all(|v| *v == Value::Scalar(0.)) {
+                        return Ok(lv);
+                    }
+                    if *op_type == SimpleOpType::Or && lv.iter().all(|v| v.is_scalar() && *v != Value::Scalar(0.)) {
+                        return Ok(lv.iter().map(|_| Value::Scalar(1.)).collect());
+                    }
+
+                    // every combination of the values of the operands on the right side
+                    let rv: Vec<Vec<Value>> = if op_type.arity() == 3 {
+                        let args = match right {
+                            AST::Vector(v) if v.len() == 2 => v,
+                            _ => return Err(EvalError::MathError(format!("Expected two operands on the right side of {:?}!", op_type)))
+                        };
+                        cart_prod(&vec![eval_rec(&args[0], scope, last_fn)?, eval_rec(&args[1], scope, last_fn)?])
+                    } else {
+                        eval_rec(&right, scope, last_fn)?.into_iter().map(|v| vec![v]).collect()
+                    };
 
                     let mut res = vec![];
 
                     for i in lv {
-                        for j in &rv {
+                        for args in &rv {
+                            let j = &args[0];
                             match op_type {
+                                SimpleOpType::Or => res.push(maths::or(&i, &j)?),
+                                SimpleOpType::And => res.push(maths::and(&i, &j)?),
+                                SimpleOpType::Not => res.push(maths::not(&i)?),
+                                SimpleOpType::Less => res.push(maths::less(&i, &j)?),
+                                SimpleOpType::Greater => res.push(maths::greater(&i, &j)?),
+                                SimpleOpType::LessEq => res.push(maths::less_eq(&i, &j)?),
+                                SimpleOpType::GreaterEq => res.push(maths::greater_eq(&i, &j)?),
                                 SimpleOpType::Get => res.push(maths::get(&i, &j)?),
+                                SimpleOpType::Factorial => res.push(check_overflow(maths::factorial(&i)?, "factorial")?),
                                 SimpleOpType::Add => res.push(maths::add(&i, &j)?),
                                 SimpleOpType::Sub => res.push(maths::sub(&i, &j)?),
                                 SimpleOpType::AddSub => res.append(&mut vec![maths::add(&i, &j)?, maths::sub(&i, &j)?]),
+                                #[cfg(feature = "uncertainty")]
+                                SimpleOpType::Uncertainty => res.push(maths::uncertain::with_uncertainty(&i, &j)?),
                                 SimpleOpType::Mult => res.push(maths::mult(&i, &j)?),
                                 SimpleOpType::Neg => res.push(maths::neg(&i)?),
                                 SimpleOpType::Div => res.push(maths::div(&i, &j)?),
+                                SimpleOpType::LeftDiv => res.push(maths::left_div(&i, &j)?),
                                 SimpleOpType::Cross => res.push(maths::cross(&i, &j)?),
                                 SimpleOpType::HiddenMult => res.push(maths::mult(&i, &j)?),
                                 SimpleOpType::Pow => res.push(maths::pow(&i, &j)?),
-                                SimpleOpType::Sin => res.push(maths::sin(&i)?),
-                                SimpleOpType::Cos => res.push(maths::cos(&i)?),
-                                SimpleOpType::Tan => res.push(maths::tan(&i)?),
+                                SimpleOpType::Sin => res.push(maths::sin(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Cos => res.push(maths::cos(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Tan => res.push(maths::tan(&i, scope.context.angle_mode)?),
                                 SimpleOpType::Abs => res.push(maths::abs(&i)?),
                                 SimpleOpType::Sqrt => res.push(maths::sqrt(&i)?),
                                 SimpleOpType::Root => res.push(maths::root(&i, &j)?),
                                 SimpleOpType::Ln => res.push(maths::ln(&i)?),
-                                SimpleOpType::Arcsin => res.push(maths::arcsin(&i)?),
-                                SimpleOpType::Arccos => res.push(maths::arccos(&i)?),
-                                SimpleOpType::Arctan => res.push(maths::arctan(&i)?),
+                                SimpleOpType::Log1p => res.push(maths::log1p(&i)?),
+                                SimpleOpType::Expm1 => res.push(maths::expm1(&i)?),
+                                SimpleOpType::Arcsin => res.push(maths::arcsin(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Arccos => res.push(maths::arccos(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Arctan => res.push(maths::arctan(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Cot => res.push(maths::cot(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Sec => res.push(maths::sec(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Csc => res.push(maths::csc(&i, scope.context.angle_mode)?),
+                                SimpleOpType::Arsinh => res.push(maths::arsinh(&i)?),
+                                SimpleOpType::Arcosh => res.push(maths::arcosh(&i)?),
+                                SimpleOpType::Artanh => res.push(maths::artanh(&i)?),
+                                SimpleOpType::SpectralRadius => res.push(maths::spectral_radius(&i)?),
+                                SimpleOpType::Norm2 => res.push(maths::norm2(&i)?),
+                                SimpleOpType::Heaviside => res.push(maths::heaviside(&i)?),
+                                SimpleOpType::Sign => res.push(maths::sign(&i)?),
+                                SimpleOpType::Dirac => res.push(maths::dirac(&i, &j)?),
+                                SimpleOpType::Det => res.push(maths::det(&i)?),
+                                SimpleOpType::Mean => res.push(maths::mean(&i)?),
+                                SimpleOpType::Var => res.push(maths::var(&i, &j)?),
+                                SimpleOpType::Std => res.push(maths::std(&i, &j)?),
+                                SimpleOpType::VSum => res.push(maths::vsum(&i)?),
+                                SimpleOpType::VProd => res.push(maths::vprod(&i)?),
+                                SimpleOpType::VMin => res.push(maths::vmin(&i)?),
+                                SimpleOpType::VMax => res.push(maths::vmax(&i)?),
+                                SimpleOpType::SteadyState => res.push(maths::steady_state(&i)?),
+                                SimpleOpType::Rank => res.push(maths::rank(&i)?),
+                                SimpleOpType::Rref => res.push(maths::rref(&i)?),
+                                SimpleOpType::Row => res.push(maths::row(&i, &j)?),
+                                SimpleOpType::Col => res.push(maths::col(&i, &j)?),
+                                SimpleOpType::Concat => res.push(maths::concat(&i, &j)?),
+                                SimpleOpType::VStack => res.push(maths::vstack(&i, &j)?),
+                                SimpleOpType::HStack => res.push(maths::hstack(&i, &j)?),
+                                SimpleOpType::Outer => res.push(maths::outer(&i, &j)?),
+                                SimpleOpType::Kron => res.push(maths::kron(&i, &j)?),
+                                SimpleOpType::Slice => res.push(maths::slice(&i, j, &args[1])?),
+                                SimpleOpType::Reshape => res.push(maths::reshape(&i, j, &args[1])?),
+                                SimpleOpType::Identity => res.push(maths::identity(&i)?),
+                                SimpleOpType::Zeros => res.push(maths::zeros(&i, &j)?),
+                                SimpleOpType::Ones => res.push(maths::ones(&i, &j)?),
+                                SimpleOpType::Diag => res.push(maths::diag(&i)?),
+                                SimpleOpType::Range => res.push(maths::range(&i, &j)?),
+                                SimpleOpType::Linspace => res.push(maths::linspace(&i, j, &args[1])?),
+                                SimpleOpType::ContinuedFraction => res.push(maths::continued_fraction(&i, &j)?),
+                                SimpleOpType::Atan2 => res.push(maths::atan2(&i, &j, scope.context.angle_mode)?),
+                                SimpleOpType::Min => res.push(maths::min(&i, &j)?),
+                                SimpleOpType::Max => res.push(maths::max(&i, &j)?),
+                                SimpleOpType::Clamp => res.push(maths::clamp(&i, j, &args[1])?),
+                                SimpleOpType::Gcd => res.push(maths::gcd(&i, &j)?),
+                                SimpleOpType::Lcm => res.push(maths::lcm(&i, &j)?),
+                                SimpleOpType::Choose => res.push(check_overflow(maths::choose(&i, &j)?, "choose")?),
+                                SimpleOpType::Perm => res.push(check_overflow(maths::perm(&i, &j)?, "perm")?),
                                 SimpleOpType::Parenths => res.push(i.clone()),
                             }
                         }
@@ -543,31 +1277,74 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
                 Operation::AdvancedOperation(a) => {
                     match a {
                         AdvancedOperation::Integral {expr, in_terms_of, lower_bound, upper_bound} => {
-                            let lb = eval_rec(&lower_bound, context, last_fn)?;
-                            let ub = eval_rec(&upper_bound, context, last_fn)?;
+                            let lb = eval_rec(&lower_bound, scope, last_fn)?;
+                            let ub = eval_rec(&upper_bound, scope, last_fn)?;
 
+                            let context = scope.to_context();
                             let mut res = vec![];
 
                             for i in lb {
                                 for j in &ub {
-                                    res.push(maths::calculus::calculate_integral(&expr, in_terms_of.clone(), i.clone(), j.clone(), context)?);
+                                    res.push(maths::calculus::calculate_integral(&expr, in_terms_of.clone(), i.clone(), j.clone(), &context)?);
                                 }
                             }
 
                             return Ok(res.into_iter().flatten().collect());
                         },
-                        AdvancedOperation::Derivative {expr, in_terms_of, at} => {
-                            let eat = eval_rec(&at, context, last_fn)?;
+                        AdvancedOperation::Derivative {expr, in_terms_of, at, order} => {
+                            let eat = eval_rec(&at, scope, last_fn)?;
 
                             let mut res = vec![];
 
+                            let context = scope.to_context();
                             for i in eat {
-                                let mut new_context = context.to_owned();
-                                res.push(maths::calculus::calculate_derivative(&expr, &in_terms_of, &i, &mut new_context)?);
+                                res.push(maths::calculus::calculate_nth_derivative(&expr, &in_terms_of, &i, *order, &context)?);
                             }
 
                             return Ok(res.into_iter().flatten().collect());
                         },
+                        AdvancedOperation::Sum {expr, in_terms_of, lower_bound, upper_bound} => {
+                            let lb = eval_rec(&lower_bound, scope, last_fn)?;
+                            let ub = eval_upper_bound(&upper_bound, scope, last_fn)?;
+
+                            let context = scope.to_context();
+                            let mut res = vec![];
+
+                            for i in lb {
+                                for j in &ub {
+                                    res.push(maths::calculus::calculate_sum(&expr, &in_terms_of, &i, j, &context)?);
+                                }
+                            }
+
+                            return Ok(res.into_iter().flatten().collect());
+                        },
+                        AdvancedOperation::Product {expr, in_terms_of, lower_bound, upper_bound} => {
+                            let lb = eval_rec(&lower_bound, scope, last_fn)?;
+                            let ub = eval_upper_bound(&upper_bound, scope, last_fn)?;
+
+                            let context = scope.to_context();
+                            let mut res = vec![];
+
+                            for i in lb {
+                                for j in &ub {
+                                    res.push(maths::calculus::calculate_product(&expr, &in_terms_of, &i, j, &context)?);
+                                }
+                            }
+
+                            return Ok(res.into_iter().flatten().collect());
+                        },
+                        AdvancedOperation::Gradient {expr, in_terms_of, at} => {
+                            let eat = eval_rec(&at, scope, last_fn)?;
+
+                            let context = scope.to_context();
+                            let mut res = vec![];
+
+                            for i in eat {
+                                res.push(maths::calculus::calculate_gradient(&expr, &in_terms_of, &i, &context)?);
+                            }
+
+                            return Ok(res);
+                        },
                         AdvancedOperation::Equation { equations, search_vars } => {
                             let mut final_expressions = vec![];
 
@@ -580,7 +1357,7 @@ fn eval_rec(b: &AST, context: &Context, last_fn: &str) -> Result<Vec<Value>, Eva
 
                                 final_expressions.push(root_b);
                             }
-                            let root_finder = RootFinder::new(final_expressions, context.to_owned(), search_vars.to_vec())?;
+                            let root_finder = RootFinder::new(final_expressions, scope.to_context().into_owned(), search_vars.to_vec())?;
                             return root_finder.find_roots();
                         }
                     }
@@ -1,14 +1,18 @@
-use crate::{basetypes::{Value, Variable, AST}, errors::EvalError, maths::calculus::calculate_derivative_newton, parser::eval, Context, PREC};
+use std::{cmp::Ordering, collections::HashMap, ops::ControlFlow};
 
-fn clean_results(res: &[Value]) -> Vec<Value> {
+use crate::{basetypes::{Operation, SimpleOpType, Value, Variable, AST}, errors::EvalError, helpers::report_progress, maths::calculus::{calculate_derivative, calculate_derivative_newton}, parser::{eval, eval_with_vars}, Context, PREC};
+
+/// removes duplicate results and truncates them to the max_solutions smallest results. The returned
+/// usize is the number of distinct results before truncation.
+fn clean_results(res: &[Value], max_solutions: usize, precision: usize) -> (Vec<Value>, usize) {
     if res.len() == 0 {
-        return vec![];
+        return (vec![], 0);
     }
     let mut new_res: Vec<Value> = vec![];
     for i in res {
         let mut found = false;
         for j in &new_res {
-            if i.round(PREC-2) == j.round(PREC-2) {
+            if i.round(precision-2) == j.round(precision-2) {
                 found = true;
                 break;
             }
@@ -17,24 +21,28 @@ fn clean_results(res: &[Value]) -> Vec<Value> {
             new_res.push(i.clone());
         }
     }
+    let distinct = new_res.len();
+    let truncated = distinct > max_solutions;
     match res[0] {
         Value::Scalar(_) => {
-            if new_res.len() > 10 {
+            if truncated {
                 new_res.sort_by(|a, b| a.get_scalar().unwrap().abs().partial_cmp(&b.get_scalar().unwrap().abs()).unwrap());
-                new_res = new_res[0..10].to_vec();
+                new_res = new_res[0..max_solutions].to_vec();
                 new_res.sort_by(|a, b| a.get_scalar().unwrap().partial_cmp(&b.get_scalar().unwrap()).unwrap());
             }
         },
         Value::Vector(_) => {
-            if new_res.len() > 10 {
+            if truncated {
                 new_res.sort_by(|a, b| a.get_vector().unwrap()[0].abs().partial_cmp(&b.get_vector().unwrap()[0].abs()).unwrap());
-                new_res = new_res[0..10].to_vec();
+                new_res = new_res[0..max_solutions].to_vec();
                 new_res.sort_by(|a, b| a.get_vector().unwrap()[0].partial_cmp(&b.get_vector().unwrap()[0]).unwrap());
             }
         },
-        Value::Matrix(_) => {}
+        Value::Matrix(_) => {},
+        #[cfg(feature = "uncertainty")]
+        Value::Uncertain(..) => {}
     } 
-    return new_res;
+    return (new_res, distinct);
 }
 
 fn gauss_algorithm(v: &mut Vec<Vec<f64>>) -> Result<Value, EvalError> {
@@ -96,25 +104,358 @@ fn gauss_algorithm(v: &mut Vec<Vec<f64>>) -> Result<Value, EvalError> {
     return Ok(Value::Vector(result_vec));
 }
 
+fn eval_system(expressions: &[AST], context: &Context, search_vars: &[String], x: &[f64]) -> Result<Vec<f64>, EvalError> {
+    let vars: Vec<Variable> = search_vars.iter().zip(x).map(|(n, v)| Variable::new(n, vec![Value::Scalar(*v)])).collect();
+    let mut res = vec![];
+    for e in expressions {
+        match eval_with_vars(e, context, &vars)?.get(0) {
+            Some(Value::Scalar(s)) => res.push(*s),
+            Some(Value::Vector(_)) => return Err(EvalError::VectorInEq),
+            Some(Value::Matrix(_)) => return Err(EvalError::MatrixInEq),
+            #[cfg(feature = "uncertainty")]
+            Some(Value::Uncertain(..)) => return Err(EvalError::UncertainInEq),
+            None => return Err(EvalError::NothingToDoEq)
+        }
+    }
+    return Ok(res);
+}
+
+/// extracts the coefficient matrix A and the right hand side b of a system of linear expressions,
+/// so that the expressions are equal to A*x - b. The linearity is checked at some sample points.
+pub(crate) fn linear_coefficients(expressions: &[AST], context: &Context, search_vars: &[String]) -> Result<(Vec<Vec<f64>>, Vec<f64>), EvalError> {
+    let n = search_vars.len();
+    let f0 = eval_system(expressions, context, search_vars, &vec![0.; n])?;
+    let mut a = vec![vec![0.; n]; expressions.len()];
+    for j in 0..n {
+        let mut unit = vec![0.; n];
+        unit[j] = 1.;
+        let fj = eval_system(expressions, context, search_vars, &unit)?;
+        for i in 0..expressions.len() {
+            a[i][j] = fj[i]-f0[i];
+        }
+    }
+    let b: Vec<f64> = f0.iter().map(|f| -f).collect();
+
+    // a linear system has to satisfy f(x) = A*x - b everywhere
+    for k in 0..4 {
+        let sign = |i: usize| if i % 2 == 0 {1.} else {-1.};
+        let point: Vec<f64> = (0..n).map(|j| sign(j+k)*(1.3 + 0.7*k as f64 + 0.45*j as f64)).collect();
+        let fp = eval_system(expressions, context, search_vars, &point)?;
+        for i in 0..expressions.len() {
+            let expected = a[i].iter().zip(&point).map(|(c, x)| c*x).sum::<f64>() - b[i];
+            if !fp[i].is_finite() || (fp[i]-expected).abs() > 10f64.powi(-(PREC as i32-2))*expected.abs().max(1.) {
+                return Err(EvalError::MathError("The system of equations is not linear!".to_string()));
+            }
+        }
+    }
+
+    return Ok((a, b));
+}
+
+fn depends_on(expr: &AST, vars: &[String]) -> bool {
+    match expr {
+        AST::Scalar(_) => return false,
+        AST::Vector(v) => return v.iter().any(|a| depends_on(a, vars)),
+        AST::Matrix(m) => return m.iter().flatten().any(|a| depends_on(a, vars)),
+        AST::List(l) => return l.iter().any(|a| depends_on(a, vars)),
+        AST::Variable(v) => return vars.contains(v),
+        // the body of a function or an advanced operation might refer to the variables
+        AST::Function { .. } => return true,
+        AST::Operation(o) => {
+            match o.as_ref() {
+                Operation::SimpleOperation { left, right, .. } => return depends_on(left, vars) || depends_on(right, vars),
+                Operation::AdvancedOperation(_) => return true
+            }
+        }
+    }
+}
+
+/// checks whether an expression is linear in the given variables by walking its AST. Function calls
+/// and advanced operations are never considered to be linear.
+fn is_linear(expr: &AST, vars: &[String]) -> bool {
+    if !depends_on(expr, vars) {
+        return true;
+    }
+    match expr {
+        AST::Variable(_) => return true,
+        AST::Operation(o) => {
+            match o.as_ref() {
+                Operation::SimpleOperation { op_type, left, right } => {
+                    match op_type {
+                        SimpleOpType::Add | SimpleOpType::Sub => return is_linear(left, vars) && is_linear(right, vars),
+                        SimpleOpType::Neg | SimpleOpType::Parenths => return is_linear(left, vars) && !depends_on(right, vars),
+                        SimpleOpType::Mult | SimpleOpType::HiddenMult => return (!depends_on(left, vars) && is_linear(right, vars)) || (is_linear(left, vars) && !depends_on(right, vars)),
+                        SimpleOpType::Div => return is_linear(left, vars) && !depends_on(right, vars),
+                        _ => return false
+                    }
+                },
+                Operation::AdvancedOperation(_) => return false
+            }
+        },
+        _ => return false
+    }
+}
+
+/// describes the result of solving a system directly (without Newton's method).
+enum DirectResult {
+    /// the system can't be solved directly
+    NotApplicable,
+    NoSolution,
+    Roots(Vec<Vec<f64>>)
+}
+
+/// solves a square system of linear expressions directly with the gauss algorithm, checking the
+/// solution against the check expressions.
+fn solve_linear(search_expres: &[AST], check_expres: &[AST], context: &Context, search_vars: &[String], precision: usize) -> DirectResult {
+    if search_expres.len() != search_vars.len() || !search_expres.iter().all(|e| is_linear(e, search_vars)) {
+        return DirectResult::NotApplicable;
+    }
+    let (a, b) = match linear_coefficients(search_expres, context, search_vars) {
+        Ok(c) => c,
+        Err(_) => return DirectResult::NotApplicable
+    };
+    if let Some(x) = solve_integer_system(&a, &b) {
+        return check_solutions(vec![x], check_expres, context, search_vars, precision);
+    }
+    let mut augmented: Vec<Vec<f64>> = a.iter().zip(&b).map(|(row, b)| {let mut row = row.clone(); row.push(*b); row}).collect();
+    let mut x = match gauss_algorithm(&mut augmented) {
+        Ok(x) => x.get_vector().unwrap(),
+        Err(EvalError::InfiniteSolutions) => return DirectResult::NoSolution,
+        Err(_) => return DirectResult::NotApplicable
+    };
+    if x.iter().any(|v| !v.is_finite()) {
+        return DirectResult::NotApplicable;
+    }
+    // one step of iterative refinement removes most of the rounding error of the elimination
+    let mut residual_system: Vec<Vec<f64>> = a.iter().zip(&b).map(|(row, b)| {
+        let mut row = row.clone();
+        row.push(b - row.iter().zip(&x).map(|(c, x)| c*x).sum::<f64>());
+        row
+    }).collect();
+    if let Ok(Value::Vector(d)) = gauss_algorithm(&mut residual_system) {
+        if d.iter().all(|v| v.is_finite()) {
+            x.iter_mut().zip(d).for_each(|(x, d)| *x += d);
+        }
+    }
+    return check_solutions(vec![x], check_expres, context, search_vars, precision);
+}
+
+/// removes all solutions that don't satisfy the check expressions up to the given precision.
+fn check_solutions(xs: Vec<Vec<f64>>, check_expres: &[AST], context: &Context, search_vars: &[String], precision: usize) -> DirectResult {
+    let mut roots = vec![];
+    for x in xs {
+        let check_results = match eval_system(check_expres, context, search_vars, &x) {
+            Ok(r) => r,
+            Err(_) => return DirectResult::NotApplicable
+        };
+        if check_results.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() < 10f64.powi(-(precision as i32)) {
+            roots.push(x);
+        }
+    }
+    if roots.is_empty() {
+        return DirectResult::NoSolution;
+    }
+    return DirectResult::Roots(roots);
+}
+
+/// returns the degree of an expression if it is a polynomial in the given variable. Function calls
+/// and advanced operations are never considered to be polynomials.
+fn polynomial_degree(expr: &AST, var: &[String], context: &Context) -> Option<usize> {
+    if !depends_on(expr, var) {
+        return Some(0);
+    }
+    match expr {
+        AST::Variable(_) => return Some(1),
+        AST::Operation(o) => {
+            let (op_type, left, right) = match o.as_ref() {
+                Operation::SimpleOperation { op_type, left, right } => (op_type, left, right),
+                Operation::AdvancedOperation(_) => return None
+            };
+            match op_type {
+                SimpleOpType::Add | SimpleOpType::Sub => return Some(polynomial_degree(left, var, context)?.max(polynomial_degree(right, var, context)?)),
+                SimpleOpType::Mult | SimpleOpType::HiddenMult => return Some(polynomial_degree(left, var, context)? + polynomial_degree(right, var, context)?),
+                SimpleOpType::Neg | SimpleOpType::Parenths if !depends_on(right, var) => return polynomial_degree(left, var, context),
+                SimpleOpType::Div if !depends_on(right, var) => return polynomial_degree(left, var, context),
+                SimpleOpType::Pow if !depends_on(right, var) => {
+                    let exponent = match eval(right, context).ok()?.to_vec().as_slice() {
+                        [Value::Scalar(e)] if e.fract() == 0. && (0. ..=3.).contains(e) => *e as usize,
+                        _ => return None
+                    };
+                    return Some(polynomial_degree(left, var, context)?*exponent);
+                },
+                _ => return None
+            }
+        },
+        _ => return None
+    }
+}
+
+/// calculates the real roots of c3*x^3 + c2*x^2 + c1*x + c0 (with c3 or c2 not 0) in ascending order.
+fn polynomial_roots(c: [f64; 4]) -> Vec<f64> {
+    let [c0, c1, c2, c3] = c;
+    let mut roots = vec![];
+    if c3 == 0. {
+        let disc = c1*c1 - 4.*c2*c0;
+        if disc < 0. {
+            return vec![];
+        }
+        // numerically stable form of the quadratic formula
+        let q = -0.5*(c1 + if c1 < 0. {-1.} else {1.}*disc.sqrt());
+        roots.push(q/c2);
+        if q != 0. && disc != 0. {
+            roots.push(c0/q);
+        }
+    } else {
+        // depressed cubic t^3 + p*t + q with x = t - b/3
+        let (a, b, c) = (c2/c3, c1/c3, c0/c3);
+        let p = b - a*a/3.;
+        let q = 2.*a*a*a/27. - a*b/3. + c;
+        let disc = q*q/4. + p*p*p/27.;
+        if p == 0. && q == 0. {
+            roots.push(-a/3.);
+        } else if disc > 0. {
+            let sqrt_disc = disc.sqrt();
+            roots.push((-q/2. + sqrt_disc).cbrt() + (-q/2. - sqrt_disc).cbrt() - a/3.);
+        } else {
+            // three real roots (some of them might coincide)
+            let r = 2.*(-p/3.).sqrt();
+            let phi = (3.*q/(p*r)).clamp(-1., 1.).acos()/3.;
+            for k in 0..3 {
+                roots.push(r*(phi - 2.*std::f64::consts::PI*k as f64/3.).cos() - a/3.);
+            }
+        }
+        // polish the roots with a few Newton steps on the polynomial
+        for x in roots.iter_mut() {
+            for _ in 0..3 {
+                let fx = ((c3*(*x) + c2)*(*x) + c1)*(*x) + c0;
+                let dfx = (3.*c3*(*x) + 2.*c2)*(*x) + c1;
+                if dfx == 0. || fx == 0. {
+                    break;
+                }
+                *x -= fx/dfx;
+            }
+        }
+    }
+    roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    roots.dedup_by(|a, b| (*a-*b).abs() <= 10f64.powi(-(PREC as i32))*a.abs().max(1.));
+    return roots;
+}
+
+/// solves a single polynomial expression of degree 2 or 3 in a single variable using the closed-form
+/// formulas, checking the roots against the check expressions.
+fn solve_polynomial(search_expres: &[AST], check_expres: &[AST], context: &Context, search_vars: &[String], precision: usize) -> DirectResult {
+    if search_expres.len() != 1 || search_vars.len() != 1 {
+        return DirectResult::NotApplicable;
+    }
+    let degree = match polynomial_degree(&search_expres[0], search_vars, context) {
+        Some(d) if d == 2 || d == 3 => d,
+        _ => return DirectResult::NotApplicable
+    };
+    let f = |x: f64| -> Option<f64> {
+        let fx = eval_system(search_expres, context, search_vars, &[x]).ok()?[0];
+        if fx.is_finite() {Some(fx)} else {None}
+    };
+    let (f0, f1, fm1, f2) = match (f(0.), f(1.), f(-1.), f(2.)) {
+        (Some(f0), Some(f1), Some(fm1), Some(f2)) => (f0, f1, fm1, f2),
+        _ => return DirectResult::NotApplicable
+    };
+    // coefficients of the interpolating polynomial through x = 0, 1, -1, 2
+    let c2 = (f1+fm1)/2. - f0;
+    let odd = (f1-fm1)/2.;
+    let c3 = if degree == 3 {(f2 - f0 - 4.*c2 - 2.*odd)/6.} else {0.};
+    let c = [f0, odd-c3, c2, c3];
+    if c[degree] == 0. {
+        return DirectResult::NotApplicable;
+    }
+    // make sure that the polynomial actually describes the expression
+    for x in [3.7, -2.3] {
+        let expected = ((c[3]*x + c[2])*x + c[1])*x + c[0];
+        match f(x) {
+            Some(fx) if (fx-expected).abs() <= 10f64.powi(-(PREC as i32-2))*expected.abs().max(1.) => {},
+            _ => return DirectResult::NotApplicable
+        }
+    }
+
+    let roots = polynomial_roots(c);
+    if roots.is_empty() {
+        return DirectResult::NoSolution;
+    }
+    return check_solutions(roots.into_iter().map(|r| vec![r]).collect(), check_expres, context, search_vars, precision);
+}
+
+/// calculates the determinant of an integer matrix exactly using the fraction-free Bareiss
+/// algorithm. Returns None if an intermediate result overflows.
+fn integer_det(mut m: Vec<Vec<i128>>) -> Option<i128> {
+    let n = m.len();
+    let mut sign = 1;
+    let mut prev = 1;
+    for k in 0..n {
+        if m[k][k] == 0 {
+            match (k+1..n).find(|r| m[*r][k] != 0) {
+                Some(r) => {
+                    m.swap(k, r);
+                    sign = -sign;
+                },
+                None => return Some(0)
+            }
+        }
+        for i in k+1..n {
+            for j in k+1..n {
+                m[i][j] = m[i][j].checked_mul(m[k][k])?.checked_sub(m[i][k].checked_mul(m[k][j])?)?/prev;
+            }
+        }
+        prev = m[k][k];
+    }
+    return Some(sign*m[n-1][n-1]);
+}
+
+/// solves a linear system with integer coefficients exactly using Cramer's rule, so that every
+/// component of the solution is the correctly rounded quotient of two integers. Returns None if the
+/// coefficients aren't (small) integers or the system is singular.
+fn solve_integer_system(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    let to_int = |v: f64| if v.fract() == 0. && v.abs() < 2f64.powi(31) {Some(v as i128)} else {None};
+    let a_int = a.iter().map(|row| row.iter().map(|v| to_int(*v)).collect::<Option<Vec<i128>>>()).collect::<Option<Vec<Vec<i128>>>>()?;
+    let b_int = b.iter().map(|v| to_int(*v)).collect::<Option<Vec<i128>>>()?;
+    if a_int.len() > 10 {
+        return None;
+    }
+
+    let det = integer_det(a_int.clone())?;
+    if det == 0 {
+        return None;
+    }
+    let mut x = vec![];
+    for j in 0..a_int.len() {
+        let mut a_j = a_int.clone();
+        for i in 0..a_j.len() {
+            a_j[i][j] = b_int[i];
+        }
+        let det_j = integer_det(a_j)?;
+        if det_j.unsigned_abs() > 1 << 53 || det.unsigned_abs() > 1 << 53 {
+            return None;
+        }
+        x.push(det_j as f64/det as f64);
+    }
+    return Some(x);
+}
+
 fn jacobi_and_gauss(search_expres: &[AST], x: &[Variable], context: &mut Context, fx: &Vec<f64>) -> Result<Vec<Variable>, EvalError> {
     let mut jacobi: Vec<Vec<f64>> = vec![];
 
-    let mut vars: Vec<&Variable> = context.vars.iter().collect();
-
     for i in 0..search_expres.len() {
         let mut row = vec![];
         for j in 0..x.len() {
-            let mut added_vars = 0;
             for k in 0..x.len() {
                 if j != k {
-                    vars.push(&x[k]);
-                    added_vars += 1;
+                    context.add_var(&x[k]);
                 }
             }
-            let derivative = calculate_derivative_newton(&search_expres[i], &x[j].name, x[j].values.get(0).unwrap(), Some(Value::Scalar(fx[i])), &mut Context::new(&vars.iter().map(|v| v.to_owned().to_owned()).collect::<Vec<Variable>>(), &context.funs))?.get_scalar().unwrap();
+            let derivative = calculate_derivative_newton(&search_expres[i], &x[j].name, x[j].values.get(0).unwrap(), Some(Value::Scalar(fx[i])), context)?.get_scalar().unwrap();
             row.push(derivative);
-            for _ in 0..added_vars {
-                vars.remove(vars.len()-1);
+            for k in (0..x.len()).rev() {
+                if j != k {
+                    context.remove_var(&x[k].name);
+                }
             }
         }
         jacobi.push(row);
@@ -140,7 +481,7 @@ enum NewtonReturn {
     FinishedX(Vec<Variable>) 
 }
 
-fn newton(search_expres: &Vec<AST>, check_expres: &Vec<AST> , x: &Vec<Variable>, context: &mut Context) -> Result<NewtonReturn, EvalError> {
+fn newton(search_expres: &Vec<AST>, check_expres: &Vec<AST> , x: &Vec<Variable>, context: &mut Context, precision: usize) -> Result<NewtonReturn, EvalError> {
     let mut fx = vec![];
     for i in x {
         context.add_var(i);
@@ -152,7 +493,7 @@ fn newton(search_expres: &Vec<AST>, check_expres: &Vec<AST> , x: &Vec<Variable>,
         context.remove_var(&i.name);
     }
 
-    if -10f64.powi(-(PREC as i32)) < fx.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() && fx.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() < 10f64.powi(-(PREC as i32)) {
+    if -10f64.powi(-(precision as i32)) < fx.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() && fx.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() < 10f64.powi(-(precision as i32)) {
         let mut check_results = vec![]; 
         for i in x {
             context.add_var(i);
@@ -166,7 +507,7 @@ fn newton(search_expres: &Vec<AST>, check_expres: &Vec<AST> , x: &Vec<Variable>,
         if check_results.is_empty() {
             return Ok(NewtonReturn::FinishedX(x.to_vec()));
         }
-        if -10f64.powi(-(PREC as i32)) < check_results.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() && check_results.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() < 10f64.powi(-(PREC as i32)) {
+        if -10f64.powi(-(precision as i32)) < check_results.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() && check_results.iter().map(|f| f.powi(2)).sum::<f64>().sqrt() < 10f64.powi(-(precision as i32)) {
             return Ok(NewtonReturn::FinishedX(x.to_vec()));
         } else {
             return Err(EvalError::ExpressionCheckFailed);
@@ -197,13 +538,91 @@ fn generate_combinations(arr: Vec<usize>, len: usize, prev_arr: Vec<usize>) -> V
     return combs;
 }
 
+/// the maximum number of starting points of the guess range, so that a tiny step is rejected
+/// instead of running (almost) forever.
+const MAX_GUESSES: usize = 10_000_000;
+
+/// runs Newton's method on a single expression in terms of a single variable starting at the
+/// initial value and returns every successive approximation (excluding the initial value).
+///
+/// This exposes the iteration used by the [RootFinder](struct@crate::roots::RootFinder), e.g. to
+/// visualize the convergence. The derivative is approximated using central differences (see
+/// [calculate_derivative]). The iteration stops once the expression evaluates to (almost) 0 or
+/// after 1000 iterations.
+pub fn newton_trace(expr: &AST, var: &str, initial: f64, context: &Context) -> Result<Vec<f64>, EvalError> {
+    let mut local_context = context.clone();
+    local_context.remove_var(var);
+
+    let mut trace = vec![];
+    let mut x = initial;
+    for _ in 0..1000 {
+        local_context.add_var(&Variable::new(var, vec![Value::Scalar(x)]));
+        let fx = match eval(expr, &local_context)?.get(0) {
+            Some(Value::Scalar(s)) => *s,
+            Some(Value::Vector(_)) => return Err(EvalError::VectorInEq),
+            Some(Value::Matrix(_)) => return Err(EvalError::MatrixInEq),
+            #[cfg(feature = "uncertainty")]
+            Some(Value::Uncertain(..)) => return Err(EvalError::UncertainInEq),
+            None => return Err(EvalError::NothingToDoEq)
+        };
+        local_context.remove_var(var);
+
+        if fx.abs() < 10f64.powi(-(PREC as i32)) {
+            break;
+        }
+
+        // a step scaled to x keeps the derivative accurate independent of PREC
+        let derivative = match calculate_derivative(expr, var, &Value::Scalar(x), &local_context)?.first() {
+            Some(Value::Scalar(s)) => *s,
+            _ => return Err(EvalError::NothingToDoEq)
+        };
+        x -= fx/derivative;
+        if x.is_nan() || x.is_infinite() {
+            return Err(EvalError::NaNOrInf);
+        }
+        trace.push(x);
+    }
+
+    return Ok(trace);
+}
+
+/// describes how the roots returned by [RootFinder::find_roots_with_report] have been found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveReport {
+    /// the indices of the expressions that were used to find the roots. For an over-determined
+    /// system the remaining expressions only act as a check. None if no roots have been found.
+    pub combination: Option<Vec<usize>>,
+    /// the number of initial guesses Newton's method has been started from, summed over all tried
+    /// combinations. Linear systems and polynomials are solved directly and don't need any.
+    pub starting_points: usize,
+    /// the number of initial guesses that converged to a root, including duplicates.
+    pub converged: usize,
+    /// the number of distinct roots that remained after removing duplicates. If this is larger
+    /// than the number of returned roots, the roots have been truncated.
+    pub distinct_roots: usize,
+    /// the number of Newton iterations needed to converge to each of the roots (in the order of the
+    /// returned roots). If a root has been found from multiple initial guesses, the smallest number
+    /// of iterations is reported. Roots of linear systems and polynomials, which are solved directly,
+    /// take 0 iterations.
+    pub iterations: Vec<usize>,
+    /// whether more roots than the maximum number of solutions (see
+    /// [RootFinder::with_max_solutions]) have been found and the roots have therefore been truncated.
+    pub truncated: bool
+}
+
 /// defines a root finder to find the roots of an expression/multiple expressions (system of equations).
 #[derive(Debug)]
 pub struct RootFinder {
     expressions: Vec<AST>,
     combinations: Vec<Vec<usize>>,
     context: Context,
-    search_vars_names: Vec<String>
+    search_vars_names: Vec<String>,
+    guess_range: (f64, f64, f64),
+    max_iterations: usize,
+    max_solutions: usize,
+    precision: usize,
+    domain: Vec<AST>,
+    all_branches: bool
 }
 
 impl RootFinder {
@@ -254,7 +673,9 @@ impl RootFinder {
         match initial_res.get(0).unwrap() {
             Value::Scalar(_) => {},
             Value::Vector(_) => return Err(EvalError::VectorInEq),
-            Value::Matrix(_) => return Err(EvalError::MatrixInEq)
+            Value::Matrix(_) => return Err(EvalError::MatrixInEq),
+            #[cfg(feature = "uncertainty")]
+            Value::Uncertain(..) => return Err(EvalError::UncertainInEq)
         }
 
         let combs;
@@ -265,32 +686,232 @@ impl RootFinder {
             combs = vec![(0..expressions.len()).collect::<Vec<usize>>()];
         }
 
-        return Ok(RootFinder { expressions, combinations: combs, context, search_vars_names });
+        return Ok(RootFinder { expressions, combinations: combs, context, search_vars_names, guess_range: (-1000., 1000., 1.), max_iterations: 1000, max_solutions: 10, precision: PREC, domain: vec![], all_branches: false });
+    }
+    /// sets the range of initial guesses for Newton's method. Every search variable starts at
+    /// min, min+step, min+2*step, ... up to (excluding) max.
+    ///
+    /// The default range is -1000 to 1000 with a step of 1. Roots far away from 0 might need a
+    /// different range, while a smaller range makes solving faster if the roots are known to be
+    /// close to 0. A range with more than 10^7 starting points (e.g. because of a tiny step) is
+    /// rejected when solving.
+    pub fn with_guess_range(mut self, min: f64, max: f64, step: f64) -> RootFinder {
+        self.guess_range = (min, max, step);
+        return self;
+    }
+    /// sets the maximum number of Newton iterations per initial guess. The default is 1000.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> RootFinder {
+        self.max_iterations = max_iterations;
+        return self;
+    }
+    /// sets the maximum number of returned solutions. If more solutions are found (e.g. for periodic
+    /// expressions), only the max_solutions solutions closest to 0 (in their first component) are
+    /// returned. The default is 10.
+    pub fn with_max_solutions(mut self, max_solutions: usize) -> RootFinder {
+        self.max_solutions = max_solutions;
+        return self;
+    }
+    /// sets the precision of the solver: a root is found once the expressions are closer than
+    /// 10^(-precision) to 0 and roots that are equal up to precision-2 digits are treated as the same
+    /// root. The default is [PREC], which is also used for printing. A precision close to the limits
+    /// of f64 (about 15 digits) might not be reachable, so that no roots are found.
+    ///
+    /// Linear systems and polynomials are still solved directly, but their roots are checked and
+    /// deduplicated with this precision as well.
+    pub fn with_precision(mut self, precision: usize) -> RootFinder {
+        self.precision = precision.max(2);
+        return self;
+    }
+    /// restricts the search to the points where the given condition (e.g. the parsed expression
+    /// x >= 0) is true, i.e. evaluates to a non-zero scalar. Initial guesses outside of the domain
+    /// are skipped and roots outside of the domain are discarded, which can be used to get rid of
+    /// unwanted or extraneous roots. Calling this function multiple times requires all of the
+    /// conditions to be true.
+    pub fn with_domain(mut self, condition: AST) -> RootFinder {
+        self.domain.push(condition);
+        return self;
+    }
+    /// sets whether all branches of a&b (see [SimpleOpType::AddSub]) are solved. By default only
+    /// the principal branch (a+b) is solved.
+    ///
+    /// As every a&b doubles the number of branches, an expression containing n of them results in
+    /// 2^n systems to solve, which all get solved separately before their roots are combined. The
+    /// time needed grows accordingly, so solving all branches should only be turned on when the
+    /// additional roots are actually needed.
+    pub fn with_all_branches(mut self, all_branches: bool) -> RootFinder {
+        self.all_branches = all_branches;
+        return self;
+    }
+    fn in_domain(&self, x: &[f64]) -> Result<bool, EvalError> {
+        let vars: Vec<Variable> = self.search_vars_names.iter().zip(x).map(|(n, v)| Variable::new(n, vec![Value::Scalar(*v)])).collect();
+        for c in &self.domain {
+            match eval_with_vars(c, &self.context, &vars)?.get(0) {
+                Some(Value::Scalar(s)) => if *s == 0. {return Ok(false)},
+                _ => return Err(EvalError::MathError("The domain of the search variables has to be a scalar condition!".to_string()))
+            }
+        }
+        return Ok(true);
     }
     /// starts the root finding process.
     /// 
     /// In the case of a system of equations results will be represented as a vector with the
     /// result order being that in which the search_vars_names have been passed to the
     /// [RootFinder::new] function.
+    ///
+    /// If the (selected) expressions are linear in the search variables, the system is solved
+    /// directly instead of using Newton's method. Systems with integer coefficients are solved exactly.
+    /// The same applies to a single quadratic or cubic polynomial in a single variable, which is
+    /// solved using the closed-form formulas.
     pub fn find_roots(&self) -> Result<Vec<Value>, EvalError> {
-        for i in &self.combinations {
+        return self.find_roots_with_progress(None);
+    }
+    /// starts the root finding process and returns every solution as a map from the name of each
+    /// search variable to its value.
+    ///
+    /// This is an alternative to [RootFinder::find_roots], where the mapping between the components
+    /// of a solution vector and the search variables is implicit.
+    pub fn find_roots_named(&self) -> Result<Vec<HashMap<String, f64>>, EvalError> {
+        let roots = self.find_roots()?;
+        let mut named = vec![];
+        for r in roots {
+            let values = match r {
+                Value::Scalar(s) => vec![s],
+                Value::Vector(v) => v,
+                Value::Matrix(_) => return Err(EvalError::MatrixInEq),
+                #[cfg(feature = "uncertainty")]
+                Value::Uncertain(..) => return Err(EvalError::UncertainInEq)
+            };
+            named.push(self.search_vars_names.iter().cloned().zip(values).collect());
+        }
+        return Ok(named);
+    }
+    /// starts the root finding process, while periodically reporting the fraction of tried
+    /// initial guesses to the progress callback.
+    ///
+    /// If the callback returns [ControlFlow::Break], the root finding is aborted with
+    /// [EvalError::Cancelled].
+    pub fn find_roots_with_progress(&self, progress: Option<&dyn Fn(f64) -> ControlFlow<()>>) -> Result<Vec<Value>, EvalError> {
+        return Ok(self.solve(progress)?.0);
+    }
+    /// starts the root finding process and additionally returns a [SolveReport] with diagnostics
+    /// about the solving process.
+    pub fn find_roots_with_report(&self) -> Result<(Vec<Value>, SolveReport), EvalError> {
+        return self.solve(None);
+    }
+    fn solve(&self, progress: Option<&dyn Fn(f64) -> ControlFlow<()>>) -> Result<(Vec<Value>, SolveReport), EvalError> {
+        if !self.all_branches {
+            return self.solve_system(&self.expressions, progress);
+        }
+
+        let mut systems: Vec<Vec<AST>> = vec![vec![]];
+        for e in &self.expressions {
+            let branches = e.add_sub_branches();
+            systems = systems.iter().flat_map(|s| branches.iter().map(|b| [s.clone(), vec![b.clone()]].concat())).collect();
+        }
+
+        let mut roots = vec![];
+        let mut iterations = vec![];
+        let mut report = SolveReport { combination: None, starting_points: 0, converged: 0, distinct_roots: 0, iterations: vec![], truncated: false };
+        let system_count = systems.len() as f64;
+        for (k, system) in systems.iter().enumerate() {
+            // the progress of all systems combined
+            let system_progress = progress.map(|p| move |f: f64| p((k as f64 + f)/system_count));
+            let (system_roots, system_report) = match &system_progress {
+                Some(p) => self.solve_system(system, Some(p))?,
+                None => self.solve_system(system, None)?
+            };
+            report.starting_points += system_report.starting_points;
+            report.converged += system_report.converged;
+            if report.combination.is_none() {
+                report.combination = system_report.combination;
+            }
+            roots.extend(system_roots);
+            iterations.extend(system_report.iterations);
+        }
+
+        let (mut cleaned_results, distinct_roots) = clean_results(&roots, self.max_solutions, self.precision);
+        // the roots of the different branches are sorted like the roots of a single system
+        cleaned_results.sort_by(|a, b| {
+            match (a, b) {
+                (Value::Scalar(a), Value::Scalar(b)) => return a.total_cmp(b),
+                (Value::Vector(a), Value::Vector(b)) => return a.partial_cmp(b).unwrap_or(Ordering::Equal),
+                _ => return Ordering::Equal
+            }
+        });
+        report.iterations = cleaned_results.iter().map(|c| {
+            roots.iter().zip(&iterations).filter(|(r, _)| r.round(self.precision-2) == c.round(self.precision-2)).map(|(_, n)| *n).min().unwrap_or(0)
+        }).collect();
+        report.truncated = distinct_roots > cleaned_results.len();
+        report.distinct_roots = distinct_roots;
+
+        return Ok((cleaned_results, report));
+    }
+    fn solve_system(&self, expressions: &[AST], progress: Option<&dyn Fn(f64) -> ControlFlow<()>>) -> Result<(Vec<Value>, SolveReport), EvalError> {
+        let (min, max, step) = self.guess_range;
+        if !min.is_finite() || !max.is_finite() || min >= max || step.is_nan() || step <= 0. {
+            return Err(EvalError::MathError("The guess range has to be finite and have a positive step!".to_string()));
+        }
+        let guesses = ((max-min)/step).ceil();
+        if guesses > MAX_GUESSES as f64 {
+            return Err(EvalError::MathError("The guess range has too many starting points!".to_string()));
+        }
+        let guesses = guesses as usize;
+        let total_guesses = match self.combinations.len().checked_mul(guesses) {
+            Some(t) => t,
+            None => return Err(EvalError::MathError("The guess range has too many starting points!".to_string()))
+        };
+        let mut starting_points = 0;
+        let mut converged = 0;
+        for (c, i) in self.combinations.iter().enumerate() {
             let mut search_expres = vec![];
-            let mut check_expres = self.expressions.clone();
+            let mut check_expres = expressions.to_vec();
             let mut removed = 0;
             for j in i {
                 search_expres.push(check_expres.remove(*j-removed));
                 removed += 1;
             } 
+            let direct = match solve_linear(&search_expres, &check_expres, &self.context, &self.search_vars_names, self.precision) {
+                DirectResult::NotApplicable => solve_polynomial(&search_expres, &check_expres, &self.context, &self.search_vars_names, self.precision),
+                d => d
+            };
+            match direct {
+                DirectResult::Roots(roots) => {
+                    let mut in_domain = vec![];
+                    for r in roots {
+                        if self.in_domain(&r)? {
+                            in_domain.push(r);
+                        }
+                    }
+                    if in_domain.is_empty() {
+                        report_progress(progress, (c+1)*guesses, total_guesses)?;
+                        continue;
+                    }
+                    let roots: Vec<Value> = in_domain.into_iter().map(|x| if x.len() == 1 {Value::Scalar(x[0])} else {Value::Vector(x)}).collect();
+                    let (roots, distinct_roots) = clean_results(&roots, self.max_solutions, self.precision);
+                    let iterations = vec![0; roots.len()];
+                    let truncated = distinct_roots > roots.len();
+                    report_progress(progress, (c+1)*guesses, total_guesses)?;
+                    return Ok((roots, SolveReport { combination: Some(i.to_vec()), starting_points, converged, distinct_roots, iterations, truncated }));
+                },
+                DirectResult::NoSolution => continue,
+                DirectResult::NotApplicable => {}
+            }
             let mut local_context = self.context.clone();
             let mut results = vec![];
-            'solve_loop_0: for j in -1000..1000 {
+            let mut iterations = vec![];
+            'solve_loop_0: for j in 0..guesses {
+                if !self.in_domain(&vec![min + j as f64*step; self.search_vars_names.len()])? {
+                    report_progress(progress, c*guesses + j + 1, total_guesses)?;
+                    continue;
+                }
                 let mut x = vec![];
                 for k in &self.search_vars_names {
-                    x.push(Variable::new(k, vec![Value::Scalar(j as f64)]));
+                    x.push(Variable::new(k, vec![Value::Scalar(min + j as f64*step)]));
                 }
+                starting_points += 1;
 
-                'solve_loop_1: for _ in 0..1000 {
-                    let newton_result = newton(&search_expres, &check_expres, &x, &mut local_context);
+                'solve_loop_1: for n in 0..self.max_iterations {
+                    let newton_result = newton(&search_expres, &check_expres, &x, &mut local_context, self.precision);
 
                     match newton_result {
                         Ok(o) => {
@@ -301,11 +922,15 @@ impl RootFinder {
                                     for i in fin_x {
                                         result_vec.push(i.values.get(0).unwrap().get_scalar().unwrap());
                                     }
+                                    if !self.in_domain(&result_vec)? {
+                                        break 'solve_loop_1;
+                                    }
                                     if result_vec.len() == 1 {
                                         results.push(Value::Scalar(result_vec[0].clone()));
                                     } else {
                                         results.push(Value::Vector(result_vec));
                                     }
+                                    iterations.push(n);
                                     break 'solve_loop_1;
                                 },
                             }
@@ -320,15 +945,21 @@ impl RootFinder {
                         }
                     }
                 }
+                report_progress(progress, c*guesses + j + 1, total_guesses)?;
             }
 
-            let cleaned_results = clean_results(&results);
+            converged += results.len();
+            let (cleaned_results, distinct_roots) = clean_results(&results, self.max_solutions, self.precision);
+            let truncated = distinct_roots > cleaned_results.len();
 
             if !cleaned_results.is_empty() {
-                return Ok(cleaned_results);
+                let root_iterations = cleaned_results.iter().map(|c| {
+                    results.iter().zip(&iterations).filter(|(r, _)| r.round(self.precision-2) == c.round(self.precision-2)).map(|(_, n)| *n).min().unwrap_or(0)
+                }).collect();
+                return Ok((cleaned_results, SolveReport { combination: Some(i.to_vec()), starting_points, converged, distinct_roots, iterations: root_iterations, truncated }));
             }
         }
 
-        return Ok(vec![]);
+        return Ok((vec![], SolveReport { combination: None, starting_points, converged, distinct_roots: 0, iterations: vec![], truncated: false }));
     }
 }
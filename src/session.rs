@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use crate::{basetypes::AST, errors::QuickEvalError, eval, parse_with_aliases, Context, Value, Values, Variable};
+
+/// provides an evaluation session for a REPL, holding a [Context] and a cache of already parsed
+/// expressions.
+///
+/// Re-evaluating an unchanged input (e.g. after changing a variable) reuses the cached [AST] instead
+/// of parsing the input again.
+///
+/// # Example
+///
+/// ```
+/// let mut session = Session::new();
+/// session.set("a", Value::Scalar(2.));
+///
+/// assert_eq!(session.eval("3a")?.to_vec(), vec![Value::Scalar(6.)]);
+///
+/// session.set("a", Value::Scalar(3.));
+///
+/// assert_eq!(session.eval("3a")?.to_vec(), vec![Value::Scalar(9.)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Session {
+    context: Context,
+    cache: HashMap<String, AST>,
+    // the aliases the cached expressions were parsed with
+    cached_aliases: Vec<(String, String)>
+}
+
+impl Session {
+    /// creates a new session using the default context (see [Context::default]).
+    pub fn new() -> Session {
+        return Session::from_context(Context::default());
+    }
+    /// creates a new session using the given context.
+    pub fn from_context(context: Context) -> Session {
+        return Session { context, cache: HashMap::new(), cached_aliases: vec![] };
+    }
+    /// evaluates the given input in the context of the session. The input is only parsed if it
+    /// hasn't been evaluated in this session before with the same aliases.
+    pub fn eval<S: Into<String>>(&mut self, input: S) -> Result<Values, QuickEvalError> {
+        let input = input.into();
+        if self.cached_aliases != self.context.aliases {
+            self.cache.clear();
+            self.cached_aliases = self.context.aliases.clone();
+        }
+        if !self.cache.contains_key(&input) {
+            let ast = parse_with_aliases(input.clone(), &self.context.aliases)?;
+            self.cache.insert(input.clone(), ast);
+        }
+        return Ok(eval(&self.cache[&input], &self.context)?);
+    }
+    /// sets the variable with the given name to the given value, replacing an already existing
+    /// variable with the same name.
+    pub fn set<S: Into<String>>(&mut self, name: S, value: Value) {
+        self.context.add_var(&Variable::new(name, vec![value]));
+    }
+    /// checks if the given input has already been parsed and cached.
+    pub fn is_cached(&self, input: &str) -> bool {
+        return self.cache.contains_key(input);
+    }
+    /// clears the cache of parsed expressions.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+    /// returns the context of the session.
+    pub fn context(&self) -> &Context {
+        return &self.context;
+    }
+    /// returns the context of the session mutably, e.g. to add functions.
+    pub fn context_mut(&mut self) -> &mut Context {
+        return &mut self.context;
+    }
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::new()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{basetypes::{Operation, SimpleOpType, AST}, errors::EvalError, roots::RootFinder, Context, Value};
+use crate::{basetypes::{AdvancedOperation, Operation, SimpleOpType, AST}, errors::EvalError, helpers::round_and_format, roots::{linear_coefficients, RootFinder}, Context, Value};
 
 #[deprecated(since="0.4.0", note="This functionality has been directly implemented into the eval process using the eq AdvancedOperator")]
 /// used to solve an equation or a system of equations.
@@ -20,3 +20,61 @@ pub fn solve(equations: Vec<(AST, AST)>, context: &Context, search_vars: Vec<Str
     let root_finder = RootFinder::new(final_expressions, context.to_owned(), search_vars)?;
     return root_finder.find_roots();
 }
+
+/// extracts the coefficient matrix A and the right hand side b of a linear system of equations,
+/// so that the system is equivalent to A*x = b, where x are the search variables in the given
+/// order.
+///
+/// The coefficients are determined by evaluating the equations, which is why the system is
+/// additionally checked for linearity at some sample points. A non-linear system results in an error.
+pub fn linear_system(equations: &[(AST, AST)], context: &Context, search_vars: &[String]) -> Result<(Vec<Vec<f64>>, Vec<f64>), EvalError> {
+    if equations.is_empty() || search_vars.is_empty() {
+        return Err(EvalError::NothingToDoEq);
+    }
+    for i in search_vars {
+        if context.contains_var(i) {
+            return Err(EvalError::SearchVarsInVars);
+        }
+    }
+
+    let expressions: Vec<AST> = equations.iter().map(|(l, r)| AST::from_operation(Operation::SimpleOperation {
+        op_type: SimpleOpType::Sub,
+        left: l.clone(),
+        right: r.clone()
+    })).collect();
+
+    return linear_coefficients(&expressions, context, search_vars);
+}
+
+/// converts a linear system of equations given as an eq(...) expression to the LaTeX representation
+/// of its augmented coefficient matrix [A | b] (see [linear_system]).
+///
+/// # Example
+///
+/// ```
+/// let system = parse("eq(x+y=3, x-y=1, x, y)")?;
+/// let latex = augmented_matrix_latex(&system, &Context::empty())?;
+///
+/// assert_eq!(latex, "\\left[\\begin{array}{cc|c}1 & 1 & 3 \\\\1 & -1 & 1 \\\\\\end{array}\\right]");
+/// ```
+pub fn augmented_matrix_latex(system: &AST, context: &Context) -> Result<String, EvalError> {
+    let (equations, search_vars) = match system {
+        AST::Operation(o) => match &**o {
+            Operation::AdvancedOperation(AdvancedOperation::Equation { equations, search_vars }) => (equations, search_vars),
+            _ => return Err(EvalError::NothingToDoEq)
+        },
+        _ => return Err(EvalError::NothingToDoEq)
+    };
+    let (a, b) = linear_system(equations, context, search_vars)?;
+
+    let mut output_string = format!("\\left[\\begin{{array}}{{{}|c}}", "c".repeat(search_vars.len()));
+    for i in 0..a.len() {
+        for j in 0..a[i].len() {
+            output_string += &format!("{} & ", round_and_format(a[i][j], true));
+        }
+        output_string += &format!("{} \\\\", round_and_format(b[i], true));
+    }
+    output_string += "\\end{array}\\right]";
+
+    return Ok(output_string);
+}
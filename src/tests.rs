@@ -1,4 +1,4 @@
-use crate::{basetypes::Function, errors::{EvalError, MathLibError, ParserError, QuickEvalError}, parse, quick_eval, Context, Value, Variable};
+use crate::{basetypes::{Function, NumberFormat}, helpers::round_and_format_with, errors::{EvalError, MathLibError, ParserError, QuickEvalError}, parse, quick_eval, Context, Value, Values, Variable};
 
 #[test]
 fn easy_eval1() -> Result<(), MathLibError> {
@@ -337,20 +337,1854 @@ fn medium_eval27() -> Result<(), MathLibError> {
     Ok(())
 }
 
+#[test]
+fn medium_eval28() {
+    let v = Variable::new("v", vec![Value::Vector(vec![])]);
+    let m = Variable::new("M", vec![Value::Matrix(vec![])]);
+    let context = Context::from_vars(vec![v, m]);
+
+    let res = quick_eval("v?0", &context);
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can't index into an empty vector!".to_string())));
+
+    let res = quick_eval("M*[1, 2]", &context);
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can't multiply an empty matrix!".to_string())));
+
+    let res = quick_eval("M*[[1, 2], [3, 4]]", &context);
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can't multiply an empty matrix!".to_string())));
+
+    let res = quick_eval("[[1, 2], [3, 4]]*M", &context);
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can't multiply an empty matrix!".to_string())));
+}
+
+#[test]
+fn medium_eval29() -> Result<(), MathLibError> {
+    let res = quick_eval("0 < 5 < 10", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(1.)]);
+
+    let res = quick_eval("0 < 15 < 10", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(0.)]);
+
+    let x = Variable::new("x", vec![Value::Scalar(3.)]);
+    let res = quick_eval("1 <= x+2 >= 5 > 4", &Context::from_vars(vec![x]))?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(1.)]);
+
+    // the middle operand is evaluated for both comparisons, so its values are combined with each
+    // other like in 0 < 2&1 && 2&1 < 2
+    let res = quick_eval("0 < 2&1 < 2", &Context::empty())?.to_vec();
+    assert_eq!(res, quick_eval("0 < 2&1 && 2&1 < 2", &Context::empty())?.to_vec());
+    assert_eq!(res, vec![Value::Scalar(0.), Value::Scalar(1.), Value::Scalar(0.), Value::Scalar(1.)]);
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval30() -> Result<(), MathLibError> {
+    let truth_table = vec![(0., 0., 0., 0.), (0., 1., 0., 1.), (1., 0., 0., 1.), (1., 1., 1., 1.)];
+
+    for (a, b, and, or) in truth_table {
+        let vars = vec![Variable::new("a", vec![Value::Scalar(a)]), Variable::new("b", vec![Value::Scalar(b)])];
+        let context = Context::from_vars(vars);
+
+        assert_eq!(quick_eval("a && b", &context)?.to_vec(), vec![Value::Scalar(and)]);
+        assert_eq!(quick_eval("a || b", &context)?.to_vec(), vec![Value::Scalar(or)]);
+        assert_eq!(quick_eval("!a", &context)?.to_vec(), vec![Value::Scalar(1.-a)]);
+    }
+
+    let x = Variable::new("x", vec![Value::Scalar(5.)]);
+    let res = quick_eval("x > 0 && x < 10", &Context::from_vars(vec![x]))?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(1.)]);
+
+    let res = quick_eval("0 > 1 && [1, 2] < 3", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(0.)]);
+
+    // or has to be written as ||, just like and has to be written as &&
+    assert_eq!(parse("1|0"), Err(ParserError::ParseValue("1|0".to_string())));
+    assert!(parse("x | y").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval31() -> Result<(), MathLibError> {
+    let res = quick_eval("5!", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(120.)]);
+
+    let res = quick_eval("-3!+2", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(-4.)]);
+
+    let res = quick_eval("170!", &Context::empty())?.to_vec();
+    assert!(!res[0].is_inf_or_nan());
+
+    let res = quick_eval("200!", &Context::empty());
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::Overflow("factorial".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval32() -> Result<(), MathLibError> {
+    let x = 10f64.powf(-15.);
+
+    let res = quick_eval("log1p(10^(-15))", &Context::empty())?.to_vec()[0].get_scalar().unwrap();
+    let naive = quick_eval("ln(1+10^(-15))", &Context::empty())?.to_vec()[0].get_scalar().unwrap();
+    let exact = x - x*x/2.;
+    assert!((res-exact).abs() < (naive-exact).abs());
+    assert!(((res-exact)/exact).abs() < 1e-15);
+
+    let res = quick_eval("expm1(10^(-15))", &Context::empty())?.to_vec()[0].get_scalar().unwrap();
+    let naive = quick_eval("e^(10^(-15))-1", &Context::default())?.to_vec()[0].get_scalar().unwrap();
+    let exact = x + x*x/2.;
+    assert!((res-exact).abs() < (naive-exact).abs());
+
+    let res = quick_eval("log1p(expm1(2))", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(2.));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval33() -> Result<(), MathLibError> {
+    let res = quick_eval("[[4, 1], [1, 3]]\\[6, 7]", &Context::empty())?.round(6).to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1., 2.]));
+
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![4., 1.], vec![1., 3.]])]);
+    let b = Variable::new("b", vec![Value::Vector(vec![6., 7.])]);
+    let context = Context::from_vars(vec![a, b]);
+
+    let res = quick_eval("A\\(b)", &context)?.round(6).to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1., 2.]));
+
+    for expr in ["A\\b", "A \\ b"] {
+        let res = quick_eval(expr, &context)?.round(6).to_vec();
+        assert_eq!(res[0], Value::Vector(vec![1., 2.]));
+    }
+    assert_eq!(parse("A\\b")?.as_string(), "A \\ b");
+
+    let res = quick_eval("b/A", &context)?.round(6).to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1., 2.]));
+
+    let res = quick_eval("A/A", &context)?.round(6).to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 0.], vec![0., 1.]]));
+
+    let res = quick_eval("[[1, 2], [2, 4]]\\[1, 1]", &Context::empty());
+    assert!(res.is_err());
+
+    let res = quick_eval("2\\alpha", &Context::from_vars(vec![Variable::new("\\alpha", vec![Value::Scalar(3.)])]))?.to_vec();
+    assert_eq!(res[0], Value::Scalar(6.));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval34() -> Result<(), MathLibError> {
+    let res = quick_eval("1+1/2", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(1.5));
+
+    let res = quick_eval("3 / 4", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(0.75));
+
+    let res = quick_eval("1 1/2", &Context::empty());
+    assert_eq!(res, Err(QuickEvalError::ParserError(ParserError::AmbiguousNumber("1 1".to_string()))));
+
+    let res = parse("x+2 3.5");
+    assert_eq!(res, Err(ParserError::AmbiguousNumber("2 3.5".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval35() -> Result<(), MathLibError> {
+    let f = crate::compile(parse("x^2")?, "x", &Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(10.)])]));
+    for x in [-2., 0., 1.5, 3.] {
+        assert_eq!(f(x)?, Value::Scalar(x*x));
+    }
+
+    let mut context = Context::default();
+    context.add_fun(&Function::new("g", parse("a*t")?, vec!["t"]));
+    context.add_var(&Variable::new("a", vec![Value::Scalar(2.)]));
+    let f = crate::compile(parse("g(x)+pi")?, "x", &context);
+    assert_eq!(f(1.)?, Value::Scalar(2.+std::f64::consts::PI));
+
+    let f = crate::compile(parse("x&1")?, "x", &Context::empty());
+    assert!(f(1.).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn calculus_eval1() -> Result<(), MathLibError> {
     let res = quick_eval("D(x^2, x, 3)", &Context::empty())?.to_vec();
 
-    assert_eq!(res[0].round(6), Value::Scalar(6.));
+    assert_eq!(res[0].round(6), Value::Scalar(6.));
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval2() -> Result<(), MathLibError> {
+    let res = quick_eval("I(x^2, x, 0, 5)", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0].round(4), Value::Scalar(41.6667));
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval3() -> Result<(), MathLibError> {
+    let res = quick_eval("I(x^2, x, 0, 5)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 125./3.).abs() < 1e-10);
+
+    let res = quick_eval("I(sin(x), x, 0, pi)", &Context::default())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 2.).abs() < 1e-10);
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval4() -> Result<(), MathLibError> {
+    use crate::maths::calculus::{calculate_integral_with_options, IntegrationMethod, IntegrationOptions};
+
+    // the default number of midpoint steps is too large with the high-prec feature
+    let expr = parse("x^2")?;
+    let options = IntegrationOptions { steps: 10000, method: IntegrationMethod::Midpoint, exploit_symmetry: false };
+    let res = calculate_integral_with_options(&expr, "x".to_string(), Value::Scalar(0.), Value::Scalar(5.), options, &Context::empty())?;
+
+    assert!((res[0].get_scalar().unwrap() - 125./3.).abs() < 1e-6);
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval5() -> Result<(), MathLibError> {
+    use crate::maths::calculus::{calculate_integral_with_options, IntegrationMethod, IntegrationOptions};
+
+    let expr = parse("sin(x)")?;
+    let context = Context::default();
+
+    let precise_options = IntegrationOptions::default();
+    let precise = calculate_integral_with_options(&expr, "x".to_string(), Value::Scalar(0.), Value::Scalar(std::f64::consts::PI), precise_options, &context)?;
+
+    let fast_options = IntegrationOptions { steps: 10, method: IntegrationMethod::Simpson, exploit_symmetry: false };
+    let fast = calculate_integral_with_options(&expr, "x".to_string(), Value::Scalar(0.), Value::Scalar(std::f64::consts::PI), fast_options, &context)?;
+
+    // the low resolution evaluates the integrand far less often, but is still within tolerance
+    assert!(fast_options.steps*10 <= precise_options.steps);
+    assert!((precise[0].get_scalar().unwrap() - 2.).abs() < 1e-10);
+    assert!((fast[0].get_scalar().unwrap() - 2.).abs() < 1e-3);
+    assert!((precise[0].get_scalar().unwrap() - 2.).abs() < (fast[0].get_scalar().unwrap() - 2.).abs());
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval6() -> Result<(), MathLibError> {
+    let res = quick_eval("D(x^3, x, 2)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 12.).abs() < 1e-8);
+
+    let cases = vec![("x^3", 2., 12.), ("sin(x)", 1., 1f64.cos()), ("e^x", 1., std::f64::consts::E)];
+
+    for (f, x, exact) in cases {
+        let central = quick_eval(format!("D({}, x, {})", f, x), &Context::default())?.to_vec()[0].get_scalar().unwrap();
+
+        let h = 1e-8;
+        let mut context = Context::default();
+        context.add_var(&Variable::new("x", vec![Value::Scalar(x)]));
+        let fx = quick_eval(f, &context)?.to_vec()[0].get_scalar().unwrap();
+        context.add_var(&Variable::new("x", vec![Value::Scalar(x+h)]));
+        let fxh = quick_eval(f, &context)?.to_vec()[0].get_scalar().unwrap();
+        let forward = (fxh-fx)/h;
+
+        assert!((central-exact).abs() < (forward-exact).abs());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval7() -> Result<(), MathLibError> {
+    let res = quick_eval("D(x^4, x, 2, 2)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 48.).abs() < 1e-4);
+
+    let res = quick_eval("D(x^3, x, 5, 3)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 6.).abs() < 1e-3);
+
+    let res = quick_eval("D(x^3, x, 2, 1)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 12.).abs() < 1e-8);
+
+    let res = quick_eval("D(x^3, x, 2, 0)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(8.));
+
+    let res = quick_eval("D(x^3, x, 2, 1.5)", &Context::empty());
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval8() -> Result<(), MathLibError> {
+    use crate::maths::calculus::calculate_sum_closed_form;
+
+    let context = Context::default();
+
+    let geometric = parse("2^k")?;
+    let res = calculate_sum_closed_form(&geometric, "k", &Value::Scalar(0.), &Value::Scalar(10.), &context)?;
+    assert_eq!(res, Some(Value::Scalar(2047.)));
+
+    let arithmetic = parse("3*k+1")?;
+    let res = calculate_sum_closed_form(&arithmetic, "k", &Value::Scalar(1.), &Value::Scalar(100.), &context)?;
+    assert_eq!(res, Some(Value::Scalar(15250.)));
+
+    let convergent = parse("0.5^k")?;
+    let res = calculate_sum_closed_form(&convergent, "k", &Value::Scalar(0.), &Value::Scalar(f64::INFINITY), &context)?;
+    assert_eq!(res, Some(Value::Scalar(2.)));
+
+    let res = calculate_sum_closed_form(&parse("3*(1/3)^k")?, "k", &Value::Scalar(1.), &Value::Scalar(f64::INFINITY), &context)?;
+    assert!((res.unwrap().get_scalar().unwrap() - 1.5).abs() < 1e-12);
+
+    let res = calculate_sum_closed_form(&geometric, "k", &Value::Scalar(0.), &Value::Scalar(f64::INFINITY), &context);
+    assert!(res.is_err());
+
+    let res = calculate_sum_closed_form(&parse("k^2")?, "k", &Value::Scalar(0.), &Value::Scalar(10.), &context)?;
+    assert_eq!(res, None);
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval9() -> Result<(), MathLibError> {
+    let res = quick_eval("grad(x^2+y^2, [x, y], [1, 1])", &Context::empty())?.to_vec();
+    let grad = res[0].get_vector().unwrap();
+    assert_eq!(grad.len(), 2);
+    assert!((grad[0] - 2.).abs() < 1e-6);
+    assert!((grad[1] - 2.).abs() < 1e-6);
+
+    let x = Variable::new("x", vec![Value::Scalar(5.)]);
+    let res = quick_eval("grad(x*y^2+z, [x, y, z], [1, 2, 3])", &Context::from_vars(vec![x]))?.to_vec();
+    let grad = res[0].get_vector().unwrap();
+    assert!((grad[0] - 4.).abs() < 1e-6);
+    assert!((grad[1] - 4.).abs() < 1e-6);
+    assert!((grad[2] - 1.).abs() < 1e-6);
+
+    let res = quick_eval("grad(x^2+y^2, [x, y], [1, 1, 1])", &Context::empty());
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn error1() {
+    let err: Box<dyn std::error::Error> = Box::new(MathLibError::from(quick_eval("(3", &Context::empty()).unwrap_err()));
+    assert_eq!(err.to_string(), ParserError::UnmatchedOpenDelimiter.get_reason());
+
+    let quick_eval_err = err.source().unwrap();
+    assert_eq!(quick_eval_err.to_string(), ParserError::UnmatchedOpenDelimiter.get_reason());
+    let parser_err = quick_eval_err.source().unwrap();
+    assert_eq!(parser_err.downcast_ref::<ParserError>(), Some(&ParserError::UnmatchedOpenDelimiter));
+    assert!(parser_err.source().is_none());
+
+    let err: Box<dyn std::error::Error> = Box::new(MathLibError::Other("custom".to_string()));
+    assert_eq!(err.to_string(), "custom");
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn error2() -> Result<(), MathLibError> {
+    use crate::{eval, quick_eval_unified};
+
+    let parse_err: ParserError = parse("3*(").unwrap_err();
+    assert_eq!(parse_err, ParserError::UnmatchedOpenDelimiter);
+
+    let ast = parse("3y")?;
+    let eval_err: EvalError = eval(&ast, &Context::empty()).unwrap_err();
+    assert_eq!(eval_err, EvalError::NoVariable("y".to_string()));
+
+    assert_eq!(quick_eval_unified("3*(", &Context::empty()), Err(MathLibError::ParserError(ParserError::UnmatchedOpenDelimiter)));
+    assert_eq!(quick_eval_unified("3y", &Context::empty()), Err(MathLibError::EvalError(EvalError::NoVariable("y".to_string()))));
+
+    let x = Variable::new("x", vec![Value::Scalar(1.)]);
+    let res = quick_eval_unified("x", &Context::from_vars(vec![x.clone(), x]));
+    assert_eq!(res, Err(MathLibError::QuickEvalError(QuickEvalError::DuplicateVars)));
+
+    assert_eq!(quick_eval_unified("3*3", &Context::empty())?.to_vec(), vec![Value::Scalar(9.)]);
+
+    Ok(())
+}
+
+#[test]
+fn value_ops1() {
+    assert_eq!(Value::Scalar(3.) + Value::Scalar(4.), Value::Scalar(7.));
+    assert_eq!(&Value::Scalar(3.) * &Value::Scalar(4.), Value::Scalar(12.));
+    assert_eq!(-Value::Scalar(3.), Value::Scalar(-3.));
+
+    let a = Value::Vector(vec![1., 2.]);
+    let b = Value::Vector(vec![3., 5.]);
+    assert_eq!(&a + &b, Value::Vector(vec![4., 7.]));
+    assert_eq!(&b - &a, Value::Vector(vec![2., 3.]));
+    assert_eq!(&a * &b, Value::Scalar(13.));
+    assert_eq!(&b / &Value::Scalar(2.), Value::Vector(vec![1.5, 2.5]));
+
+    let m = Value::Matrix(vec![vec![1., 2.], vec![3., 4.]]);
+    assert_eq!(&m * &m, Value::Matrix(vec![vec![7., 10.], vec![15., 22.]]));
+    assert_eq!(&m * &a, Value::Vector(vec![5., 11.]));
+    assert_eq!(m.clone() - m.clone(), Value::Matrix(vec![vec![0., 0.], vec![0., 0.]]));
+
+    assert!(a.try_add(&m).is_err());
+    assert!(a.try_add(&Value::Vector(vec![1., 2., 3.])).is_err());
+    assert!(std::panic::catch_unwind(|| &a + &m).is_err());
+}
+
+#[test]
+fn from_str1() -> Result<(), MathLibError> {
+    assert_eq!("3.5".parse::<Value>()?, Value::Scalar(3.5));
+    assert_eq!(" -2e3 ".parse::<Value>()?, Value::Scalar(-2000.));
+    assert_eq!("[3, 4, -5]".parse::<Value>()?, Value::Vector(vec![3., 4., -5.]));
+    assert_eq!("[[3, 4, 5], [1, 2, 3]]".parse::<Value>()?, quick_eval("[[3, 4, 5], [1, 2, 3]]", &Context::empty())?.to_vec()[0]);
+
+    assert_eq!("3+4".parse::<Value>(), Err(ParserError::NotALiteral("3+4".to_string())));
+    assert_eq!("[x, 2]".parse::<Value>(), Err(ParserError::NotALiteral("[x, 2]".to_string())));
+    assert_eq!("-(3)".parse::<Value>(), Err(ParserError::NotALiteral("-(3)".to_string())));
+    assert_eq!("sqrt(4)".parse::<Value>(), Err(ParserError::NotALiteral("sqrt(4)".to_string())));
+    assert_eq!("(3".parse::<Value>(), Err(ParserError::UnmatchedOpenDelimiter));
+
+    Ok(())
+}
+
+#[test]
+fn shape1() -> Result<(), MathLibError> {
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![0.; 3]; 2])]);
+    let b = Variable::new("B", vec![Value::Matrix(vec![vec![0.; 4]; 3])]);
+    let v = Variable::new("v", vec![Value::Vector(vec![0.; 4])]);
+    let context = Context::from_vars(vec![a, b, v]);
+
+    assert_eq!(parse("A*B")?.result_shape(&context)?, Some((2, 4)));
+    assert_eq!(parse("2*A*B*v")?.result_shape(&context)?, Some((2, 1)));
+    assert_eq!(parse("v*v+1")?.result_shape(&context)?, Some((1, 1)));
+    assert_eq!(parse("A*B+f(A)")?.result_shape(&context)?, None);
+
+    assert!(parse("B*A")?.result_shape(&context).is_err());
+    assert!(parse("A+B")?.result_shape(&context).is_err());
+
+    let shape = parse("A*B")?.result_shape(&context)?.unwrap();
+    let res = quick_eval("A*B", &context)?.to_vec();
+    let m = res[0].get_matrix().unwrap();
+    assert_eq!(shape, (m.len(), m[0].len()));
+
+    Ok(())
+}
+
+#[test]
+fn warnings1() -> Result<(), MathLibError> {
+    use crate::{errors::ParserWarning, parse_with_warnings};
+
+    // the parser doesn't split names into products, xy is a single variable
+    let (ast, warnings) = parse_with_warnings("xy")?;
+    assert_eq!(ast, parse("xy")?);
+    assert_eq!(warnings, vec![ParserWarning::MultiLetterVariable("xy".to_string())]);
+    assert_eq!(warnings[0].get_reason(), "Interpreted xy as a single variable! If you meant a product, write it explicitly: x*y.");
+
+    let (_, warnings) = parse_with_warnings("2e+x")?;
+    assert_eq!(warnings, vec![ParserWarning::HiddenMult("2e".to_string(), "2*e".to_string())]);
+
+    let (_, warnings) = parse_with_warnings("sin(x)+pi+x_1+\\alpha")?;
+    assert!(warnings.is_empty());
+
+    let (_, warnings) = parse_with_warnings("sum(0.5^k, k, 0, inf)")?;
+    assert!(warnings.is_empty());
+
+    assert!(parse_with_warnings("(3").is_err());
+
+    // warnings are only collected by parse_with_warnings
+    parse("xy")?;
+    let (_, warnings) = parse_with_warnings("3")?;
+    assert!(warnings.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn tokens1() -> Result<(), MathLibError> {
+    use crate::parser::{classify_token, TokenClass, FUNCTIONS, FUNCTION_NAMES};
+
+    assert_eq!(classify_token("sin"), TokenClass::Function);
+    assert_eq!(classify_token("foo"), TokenClass::Variable);
+    assert_eq!(classify_token("eq"), TokenClass::AdvancedOperation);
+    assert_eq!(classify_token("clamp"), TokenClass::Function);
+    assert_eq!(classify_token("2.5e-3"), TokenClass::Number);
+    assert_eq!(classify_token("<="), TokenClass::Operator);
+    assert_eq!(classify_token("|"), TokenClass::Unknown);
+    assert_eq!(classify_token("["), TokenClass::Delimiter);
+    assert_eq!(classify_token("inf"), TokenClass::Variable);
+    assert_eq!(classify_token("3x"), TokenClass::Unknown);
+
+    // every recognized function can actually be parsed as a function with its number of arguments
+    assert_eq!(FUNCTION_NAMES.to_vec(), FUNCTIONS.iter().map(|f| f.0).collect::<Vec<&str>>());
+    for (name, op_type, arity) in FUNCTIONS {
+        assert_eq!(op_type.arity(), arity);
+        let args = vec!["x"; arity].join(", ");
+        assert!(matches!(parse(format!("{}({})", name, args))?, crate::basetypes::AST::Operation(_)));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn visit1() -> Result<(), MathLibError> {
+    use crate::basetypes::AST;
+    use std::collections::HashSet;
+
+    let ast = parse("3x+sin(y)")?;
+    assert_eq!(ast.free_variables(), HashSet::from(["x".to_string(), "y".to_string()]));
+
+    let mut operations = 0;
+    ast.visit(&mut |a| if let AST::Operation(_) = a {operations += 1});
+    // the addition, the hidden multiplication and sin
+    assert_eq!(operations, 3);
+
+    let ast = parse("I(x*a, x, 0, b)+eq(t^2=c, t)")?;
+    assert_eq!(ast.free_variables(), HashSet::from(["a".to_string(), "b".to_string(), "c".to_string()]));
+
+    let mut ast = parse("x+2*x")?;
+    ast.visit_mut(&mut |a| if *a == AST::Variable("x".to_string()) {*a = AST::Scalar(3.)});
+    assert_eq!(crate::eval(&ast, &Context::empty())?.to_vec(), vec![Value::Scalar(9.)]);
+
+    // the second and third argument of clamp are visited without the vector storing them
+    let mut nodes = 0;
+    parse("clamp(x, 0, 1)")?.visit(&mut |a| if let AST::Vector(_) = a {nodes += 10} else {nodes += 1});
+    assert_eq!(nodes, 4);
+
+    let mut ast = parse("clamp(x, 0, x)")?;
+    ast.visit_mut(&mut |a| if *a == AST::Variable("x".to_string()) {*a = AST::Scalar(3.)});
+    assert_eq!(crate::eval(&ast, &Context::empty())?.to_vec(), vec![Value::Scalar(3.)]);
+
+    Ok(())
+}
+
+#[test]
+fn simplify1() -> Result<(), MathLibError> {
+    use crate::basetypes::{Operation, SimpleOpType, AST};
+
+    let expected = AST::from_operation(Operation::SimpleOperation {
+        op_type: SimpleOpType::Add,
+        left: AST::Scalar(6.),
+        right: AST::Variable("x".to_string())
+    });
+    assert_eq!(parse("2*3+x")?.simplify(), expected);
+    assert_eq!(parse("-(-x)*1")?.simplify(), AST::Variable("x".to_string()));
+    // x could be a vector, for which x+0 fails
+    assert_eq!(parse("-(-x)*1+0")?.simplify(), parse("x+0")?);
+    assert_eq!(parse("(1+1)^x/1")?.simplify(), parse("2^x")?);
+
+    let mut context = Context::default();
+    context.add_var(&Variable::new("x", vec![Value::Scalar(2.)]));
+    context.add_var(&Variable::new("y", vec![Value::Scalar(-0.5)]));
+    let exprs = ["2*3+x", "-(-x)*1+0", "sin(pi/2)*x^(1+1)", "(3-1)(x+y)/1", "x&(2*2)", "I(t*(1+1), t, 0, x)", "[1+1, x*1]", "2^(10/5)>x"];
+    for e in exprs {
+        let parsed = parse(e)?;
+        assert_eq!(crate::eval(&parsed.simplify(), &context)?, crate::eval(&parsed, &context)?);
+    }
+
+    // trigonometric functions depend on the angle mode of the context and aren't folded
+    context.angle_mode = crate::AngleMode::Degrees;
+    for e in ["sin(90)+x", "arctan(1)*x", "atan2(1, 1)", "cos(2*30)"] {
+        let parsed = parse(e)?;
+        assert_eq!(crate::eval(&parsed.simplify(), &context)?, crate::eval(&parsed, &context)?);
+    }
+    assert_eq!(parse("cos(2*30)")?.simplify(), parse("cos(60)")?);
+
+    // invalid expressions stay invalid
+    let context = Context::from_vars(vec![Variable::new("v", vec![Value::Vector(vec![1., 2.])])]);
+    for e in ["[1, 2]+0", "0+[1, 2]", "[1, 2]-0", "[1, 2]^1", "v+0", "v^1"] {
+        let parsed = parse(e)?;
+        assert!(crate::eval(&parsed, &context).is_err());
+        assert!(crate::eval(&parsed.simplify(), &context).is_err(), "{}", e);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn substitute1() -> Result<(), MathLibError> {
+    let substituted = parse("x^2")?.substitute("x", &parse("t+1")?);
+    assert_eq!(substituted, parse("(t+1)^2")?);
+
+    let context = Context::from_vars(vec![Variable::new("t", vec![Value::Scalar(2.)])]);
+    assert_eq!(crate::eval(&substituted, &context)?.to_vec(), vec![Value::Scalar(9.)]);
+
+    // the x bound by the integral is not replaced, while the free x in the upper bound is
+    let substituted = parse("I(x, x, 0, x)")?.substitute("x", &parse("t")?);
+    assert_eq!(substituted, parse("I(x, x, 0, t)")?);
+    assert_eq!(crate::eval(&substituted, &context)?.round(6).to_vec(), vec![Value::Scalar(2.)]);
+
+    Ok(())
+}
+
+#[test]
+fn as_string1() -> Result<(), MathLibError> {
+    use crate::basetypes::{Operation, SimpleOpType, AST};
+
+    let op = |op_type: SimpleOpType, left: AST, right: AST| AST::from_operation(Operation::SimpleOperation { op_type, left, right });
+    let var = |name: &str| AST::Variable(name.to_string());
+
+    // (a+b)*c without a parentheses node
+    let ast = op(SimpleOpType::Mult, op(SimpleOpType::Add, var("a"), var("b")), var("c"));
+    assert_eq!(ast.as_string(), "(a + b) * c");
+    assert_eq!(parse(ast.as_string())?.simplify(), ast);
+
+    let ast = op(SimpleOpType::Sub, var("a"), op(SimpleOpType::Sub, var("b"), AST::Scalar(-2.)));
+    assert_eq!(ast.as_string(), "a - (b - (-2))");
+    assert_eq!(parse(ast.as_string())?.simplify(), ast);
+
+    let mut context = Context::default();
+    context.add_var(&Variable::new("x", vec![Value::Scalar(1.5)]));
+    context.add_var(&Variable::new("y", vec![Value::Scalar(-2.)]));
+    context.add_var(&Variable::new("v", vec![Value::Vector(vec![1., 2., 3.])]));
+    let exprs = ["2x^2-3x+1", "1/2/x", "x-y-(x-y)", "-x^2", "-(x^2)", "2^3^x", "(x+y)(x-y)", "3(y+4)!",
+        "x<y<3", "(x<y)<3", "!(x>y)&&y<0", "abs(y)*sin(pi*x)/(1+1)", "[x, 2y, 1]#v", "v?(1+1)", "x&y*2", "(-y)!",
+        "I(t^2, t, 0, x)", "D(sin(t)^2, t, x, 2)", "sum(k/2, k, 1, 3)", "1e-3x", "eq(z^2=x*4, z)", "root(x*y^2, 3-1)"];
+    for e in exprs {
+        let parsed = parse(e)?;
+        let reparsed = parse(parsed.as_string())?;
+        assert_eq!(crate::eval(&reparsed, &context)?, crate::eval(&parsed, &context)?, "{} -> {}", e, parsed.as_string());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn display1() -> Result<(), MathLibError> {
+    assert_eq!(format!("{}", Value::Scalar(3.5)), "3.5");
+    assert_eq!(format!("{}", Value::Vector(vec![1., 20., 3.])), "⎛1 ⎞\n⎜20⎟\n⎝3 ⎠");
+    assert_eq!(format!("{}", Value::Matrix(vec![vec![1., 2.], vec![3., 4.]])), "⎡1 2⎤\n⎣3 4⎦");
+
+    let res = quick_eval("eq(x^2=9, x)", &Context::empty())?.round(3);
+    assert_eq!(format!("{}", res), "{-3, 3}");
+
+    Ok(())
+}
+
+#[test]
+fn display2() -> Result<(), MathLibError> {
+    assert_eq!(round_and_format_with(1500., false, NumberFormat::SiPrefix), "1.5k");
+    assert_eq!(round_and_format_with(0.0012, false, NumberFormat::SiPrefix), "1.2m");
+    assert_eq!(round_and_format_with(-4.7e-6, false, NumberFormat::SiPrefix), "-4.7µ");
+    assert_eq!(round_and_format_with(3.3e-9, false, NumberFormat::SiPrefix), "3.3n");
+    assert_eq!(round_and_format_with(2.2e6, false, NumberFormat::SiPrefix), "2.2M");
+    assert_eq!(round_and_format_with(42., false, NumberFormat::SiPrefix), "42");
+    assert_eq!(round_and_format_with(0., false, NumberFormat::SiPrefix), "0");
+    assert_eq!(round_and_format_with(999.99999999999999, false, NumberFormat::SiPrefix), "1k");
+    assert_eq!(round_and_format_with(4.7e-6, true, NumberFormat::SiPrefix), "4.7\\,\\mathrm{\\mu}");
+
+    assert_eq!(Value::Vector(vec![1500., 0.0012]).display_with(NumberFormat::SiPrefix), "⎛1.5k⎞\n⎝1.2m⎠");
+    assert_eq!(Value::Scalar(1500.).display_with(NumberFormat::Default), "1500");
+
+    Ok(())
+}
+
+#[test]
+fn table1() -> Result<(), MathLibError> {
+    let res = quick_eval("eq(x+y+z=6, x-y=0, z=x^2, x, y, z)", &Context::empty())?.round(3);
+    let table = res.as_table(None);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for l in &lines {
+        assert_eq!(l.split(" | ").count(), 3);
+    }
+
+    let values = crate::Values::from_vec(vec![Value::Vector(vec![1., 20., 3.]), Value::Vector(vec![4., 5., 6.])]);
+    let table = values.as_table(Some(vec!["x".to_string(), "y".to_string(), "z".to_string()]));
+    assert_eq!(table, "x |  y | z\n--+----+--\n1 | 20 | 3\n4 |  5 | 6");
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval10() -> Result<(), MathLibError> {
+    let res = quick_eval("sum(k, k, 1, 10)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(55.));
+
+    let res = quick_eval("sum(k^2, k, 1, 100)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(338350.));
+
+    let res = quick_eval("sum([k, 1], k, 1, 3)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![6., 3.]));
+
+    let res = quick_eval("sum(0.5^k, k, 0, inf)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(2.));
+
+    let res = quick_eval("sum(k^2, k, 0, inf)", &Context::empty());
+    assert!(res.is_err());
+
+    let res = quick_eval("prod(0.5, k, 0, inf)", &Context::empty());
+    assert!(res.is_err());
+
+    // inf only is an infinite bound, it isn't a constant and doesn't shadow a variable named inf
+    assert!(quick_eval("inf", &Context::default()).is_err());
+    let context = Context::from_vars(vec![Variable::new("inf", vec![Value::Scalar(3.)])]);
+    let res = quick_eval("sum(k, k, 1, inf)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Scalar(6.));
+
+    // function bodies can read the summation index
+    let context = Context::from_funs(vec![Function::new("f", parse("x+k")?, vec!["x"])]);
+    let res = quick_eval("sum(f(1), k, 1, 3)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Scalar(9.));
+    let res = quick_eval("prod(f(1), k, 1, 3)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Scalar(24.));
+
+    let parsed = parse("sum(k^2, k, 1, n)")?;
+    assert_eq!(parsed.as_latex(), "\\sum_{k=1}^{n} k^{2}");
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval11() -> Result<(), MathLibError> {
+    let res = quick_eval("prod(k, k, 1, 5)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(120.));
+
+    let res = quick_eval("prod(1+1/k, k, 1, 9)", &Context::empty())?.round(3).to_vec();
+    assert_eq!(res[0], Value::Scalar(10.));
+
+    let res = quick_eval("prod(k, k, 5, 1)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(1.));
+
+    let res = quick_eval("prod(k, k, 1.5, 3)", &Context::empty());
+    assert!(res.is_err());
+
+    let parsed = parse("prod(k, k, 1, n)")?;
+    assert_eq!(parsed.as_latex(), "\\prod_{k=1}^{n} k");
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval12() -> Result<(), MathLibError> {
+    use crate::maths::calculus::{expression_parity, Parity};
+
+    let context = Context::default();
+    assert_eq!(expression_parity(&parse("x^2")?, "x", &context, 10)?, Parity::Even);
+    assert_eq!(expression_parity(&parse("x^3")?, "x", &context, 10)?, Parity::Odd);
+    assert_eq!(expression_parity(&parse("cos(x)+x^4")?, "x", &context, 10)?, Parity::Even);
+    assert_eq!(expression_parity(&parse("sin(x)*x^2")?, "x", &context, 10)?, Parity::Odd);
+    assert_eq!(expression_parity(&parse("x^2+x")?, "x", &context, 10)?, Parity::Neither);
+    assert_eq!(expression_parity(&parse("e^x")?, "x", &context, 10)?, Parity::Neither);
+    assert_eq!(expression_parity(&parse("[x, x^2]")?, "x", &context, 10)?, Parity::Neither);
+
+    assert!(expression_parity(&parse("x^2")?, "x", &context, 0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval13() -> Result<(), MathLibError> {
+    use crate::maths::calculus::{calculate_integral_with_options, IntegrationOptions};
+
+    let context = Context::default();
+    let symmetric = IntegrationOptions { exploit_symmetry: true, ..IntegrationOptions::default() };
+    let res = calculate_integral_with_options(&parse("x^3")?, "x".to_string(), Value::Scalar(-2.), Value::Scalar(2.), symmetric, &context)?;
+    assert_eq!(res, vec![Value::Scalar(0.)]);
+
+    let res = calculate_integral_with_options(&parse("[x, x^3]")?, "x".to_string(), Value::Scalar(2.), Value::Scalar(-2.), symmetric, &context)?;
+    assert_eq!(res, vec![Value::Vector(vec![0., 0.])]);
+
+    let res = quick_eval("I(x^2, x, -3, 3)", &context)?.round(6).to_vec();
+    assert_eq!(res, vec![Value::Scalar(18.)]);
+
+    let res = quick_eval("I(sqrt(1-x^2), x, -1, 1)", &context)?.round(2).to_vec();
+    assert_eq!(res, vec![Value::Scalar(1.57)]);
+
+    let res = calculate_integral_with_options(&parse("x^3")?, "x".to_string(), Value::Scalar(-2.), Value::Scalar(2.), IntegrationOptions::default(), &context)?;
+    assert!(res[0].get_scalar().unwrap().abs() < 1e-9);
+
+    // the default path doesn't guess the parity from a few samples
+    let res = quick_eval("I(x+0.0000001, x, -1, 1)", &context)?.to_vec();
+    assert!((res[0].get_scalar().unwrap()-0.0000002).abs() < 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn calculus_eval14() -> Result<(), MathLibError> {
+    let context = Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(10.)]), Variable::new("a", vec![Value::Scalar(2.)])]);
+    let before = context.clone();
+
+    // the index only shadows the outer x within the body of the sum
+    let res = quick_eval("sum(x*a, x, 1, 3)+x", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(22.)]);
+
+    // the bounds are evaluated with the outer x
+    let res = quick_eval("sum(x^2, x, 1, x)", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(385.)]);
+
+    let res = quick_eval("prod(x, x, 1, 4)*x", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(240.)]);
+
+    let res = quick_eval("D(x^2, x, 3)+I(x, x, 0, 2)+x", &context)?.round(3).to_vec();
+    assert_eq!(res, vec![Value::Scalar(18.)]);
+
+    let res = quick_eval("sum(sum(x*y, y, 1, x), x, 1, 2)", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(7.)]);
+
+    assert_eq!(context, before);
+    assert_eq!(quick_eval("x", &context)?.to_vec(), vec![Value::Scalar(10.)]);
+
+    // too many terms to sum up one by one, while closed forms still work for huge bounds
+    assert!(quick_eval("sum(1/k^2, k, 1, 1000000000000)", &Context::empty()).is_err());
+    assert!(quick_eval("prod(k, k, 1, 1000000000000)", &Context::empty()).is_err());
+    assert_eq!(quick_eval("sum(k, k, 1, 1000000000000)", &Context::empty())?.to_vec(), vec![Value::Scalar(500000000000500000000000.)]);
+
+    assert_eq!(parse("sum(k, 2, 1, 3)"), Err(ParserError::InvalidVariableName("2".to_string())));
+
+    Ok(())
+}
+
+#[cfg(feature = "uncertainty")]
+#[test]
+fn calculus_eval15() -> Result<(), MathLibError> {
+    let res = quick_eval("grad(x^2, [x], 1+-0.1)", &Context::empty());
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can't calculate the gradient at an uncertain point!".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn session1() -> Result<(), MathLibError> {
+    let mut session = crate::Session::new();
+    session.set("a", Value::Scalar(2.));
+
+    assert!(!session.is_cached("3a+pi-pi"));
+    assert_eq!(session.eval("3a+pi-pi")?.to_vec(), vec![Value::Scalar(6.)]);
+    assert!(session.is_cached("3a+pi-pi"));
+
+    session.set("a", Value::Scalar(5.));
+    assert_eq!(session.eval("3a+pi-pi")?.to_vec(), vec![Value::Scalar(15.)]);
+    assert_eq!(session.context().get_var("a").unwrap().values.get(0), Some(&Value::Scalar(5.)));
+
+    assert!(session.eval("3b").is_err());
+    assert!(session.eval("(3").is_err());
+    assert!(!session.is_cached("(3"));
+
+    session.clear_cache();
+    assert!(!session.is_cached("3a+pi-pi"));
+
+    Ok(())
+}
+
+#[test]
+fn alias1() -> Result<(), MathLibError> {
+    use crate::{parse_with_aliases, Session};
+
+    let mut context = Context::empty();
+    context.add_alias("asin", "arcsin");
+    context.add_alias("sen", "sin");
+
+    assert_eq!(quick_eval("asin(0)", &context)?.to_vec(), vec![Value::Scalar(0.)]);
+    assert_eq!(quick_eval("2sen(0)+abs(-3)", &context)?.to_vec(), vec![Value::Scalar(3.)]);
+    assert_eq!(parse_with_aliases("asin(x)", &context.aliases)?, parse("arcsin(x)")?);
+
+    // aliases also work for functions of the context and are not resolved recursively
+    context.add_fun(&Function::new("f", parse("2x")?, vec!["x"]));
+    context.add_alias("g", "f");
+    context.add_alias("f", "g");
+    assert_eq!(quick_eval("g(3)", &context)?.to_vec(), vec![Value::Scalar(6.)]);
+
+    let mut session = Session::from_context(context);
+    assert_eq!(session.eval("asin(0)")?.to_vec(), vec![Value::Scalar(0.)]);
+
+    // changing an alias invalidates the expressions that were parsed with the old one
+    assert_eq!(session.eval("sen(0)")?.to_vec(), vec![Value::Scalar(0.)]);
+    session.context_mut().add_alias("sen", "cos");
+    assert_eq!(session.eval("sen(0)")?.to_vec(), vec![Value::Scalar(1.)]);
+
+    assert!(quick_eval("asin(0)", &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn progress1() -> Result<(), MathLibError> {
+    use std::{cell::Cell, ops::ControlFlow};
+    use crate::{maths::calculus::{calculate_integral_with_progress, IntegrationOptions}, roots::RootFinder};
+
+    let calls = Cell::new(0);
+    let last = Cell::new(0.);
+    let counter = |p: f64| {
+        calls.set(calls.get()+1);
+        last.set(p);
+        ControlFlow::Continue(())
+    };
+    let root_finder = RootFinder::new(vec![parse("x^4-16")?], Context::empty(), vec!["x".to_string()])?;
+    let res = root_finder.find_roots_with_progress(Some(&counter))?;
+    assert_eq!(res.len(), 2);
+    assert!(calls.get() > 1);
+    assert_eq!(last.get(), 1.);
+
+    let calls = Cell::new(0);
+    let cancel = |_: f64| {
+        calls.set(calls.get()+1);
+        ControlFlow::Break(())
+    };
+    let res = root_finder.find_roots_with_progress(Some(&cancel));
+    assert_eq!(res, Err(EvalError::Cancelled));
+    assert_eq!(calls.get(), 1);
+
+    let res = calculate_integral_with_progress(&parse("x")?, "x".to_string(), Value::Scalar(0.), Value::Scalar(1.), IntegrationOptions::default(), Some(&cancel), &Context::empty());
+    assert_eq!(res, Err(EvalError::Cancelled));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval36() -> Result<(), MathLibError> {
+    let mut context = Context::from_vars(vec![Variable::new("a", vec![Value::Scalar(5.)])]);
+    context.add_fun(&Function::new("f_{0}", parse("x+1")?, vec!["x"]));
+    for i in 1..25 {
+        context.add_fun(&Function::new(format!("f_{{{}}}", i), parse(format!("f_{{{}}}(x)+1", i-1))?, vec!["x".to_string()]));
+    }
+    context.add_fun(&Function::new("g", parse("y*a")?, vec!["y"]));
+    context.add_fun(&Function::new("h", parse("g(2)+a")?, vec!["a"]));
+    context.add_fun(&Function::new("k", parse("D(t^2, t, x)")?, vec!["x"]));
+    let before = context.clone();
+
+    let res = quick_eval("f_{24}(0)", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(25.)]);
+
+    // inputs of the calling function shadow the variables of the context
+    let res = quick_eval("h(3)+g(1)", &context)?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(14.)]);
+
+    let res = quick_eval("k(f_{2}(0))", &context)?.round(3).to_vec();
+    assert_eq!(res, vec![Value::Scalar(6.)]);
+
+    assert_eq!(context, before);
+
+    Ok(())
+}
+
+#[cfg(feature = "uncertainty")]
+#[test]
+fn medium_eval37() -> Result<(), MathLibError> {
+    let res = quick_eval("(3+-0.1)+(4+-0.1)", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Uncertain(7., 0.02f64.sqrt()));
+
+    let res = quick_eval("2*(3+-0.1)", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Uncertain(6., 0.2));
+    assert_eq!(res[0].to_string(), "6 ± 0.2");
+
+    let res = quick_eval("3±0.1", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Uncertain(3., 0.1));
+
+    // with whitespace between + and - it is still a sum
+    let res = quick_eval("3 + -2", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(1.));
+
+    let res = quick_eval("3+ -2", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(1.));
+    Ok(())
+}
+
+#[test]
+fn medium_eval38() -> Result<(), MathLibError> {
+    let res = quick_eval("1e5", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(100000.));
+
+    let res = quick_eval("2.5e-3", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(0.0025));
+
+    let res = quick_eval("[1e2, 2.5E+1, 4e-1]", &Context::empty())?.to_vec();
+
+    assert_eq!(res[0], Value::Vector(vec![100., 25., 0.4]));
+
+    let res = quick_eval("3e", &Context::default())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(3.*std::f64::consts::E));
+
+    let x = Variable::new("x", vec![Value::Scalar(2.)]);
+    let res = quick_eval("1-2e3x", &Context::from_vars(vec![x]))?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(-3999.));
+    Ok(())
+}
+
+#[test]
+fn medium_eval39() -> Result<(), MathLibError> {
+    let context = Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(1.)]), Variable::new("x", vec![Value::Scalar(2.)])]);
+    let res = quick_eval("x", &context);
+
+    assert_eq!(res.unwrap_err(), QuickEvalError::DuplicateVars);
+    assert_eq!(context.duplicate_names(), vec!["x".to_string()]);
+
+    let mut context = Context::default();
+    context.add_fun(&Function::new("pi", parse("2x")?, vec!["x"]));
+    assert!(context.duplicate_names().is_empty());
+    assert_eq!(quick_eval("pi(1)", &context)?.to_vec(), vec![Value::Scalar(2.)]);
+    Ok(())
+}
+
+#[test]
+fn medium_eval40() -> Result<(), MathLibError> {
+    let x = Variable::new("x", vec![Value::Scalar(3.), Value::Scalar(-3.)]);
+    let context = Context::from_vars(vec![x]);
+
+    assert_eq!(quick_eval("x_0", &context)?.to_vec(), vec![Value::Scalar(3.)]);
+    assert_eq!(quick_eval("2x_{1}", &context)?.to_vec(), vec![Value::Scalar(-6.)]);
+    assert_eq!(quick_eval("x_2", &context).unwrap_err(), QuickEvalError::EvalError(EvalError::NoVariable("x_2".to_string())));
+
+    // an existing variable with the exact name takes precedence
+    let mut context = context;
+    context.add_var(&Variable::new("x_0", vec![Value::Scalar(5.)]));
+    assert_eq!(quick_eval("x_0", &context)?.to_vec(), vec![Value::Scalar(5.)]);
+    Ok(())
+}
+
+#[test]
+fn medium_eval41() -> Result<(), MathLibError> {
+    // transition matrix of a Markov chain, the largest eigenvalue of a stochastic matrix is 1
+    let res = quick_eval("spectral_radius([[0.9, 0.1, 0], [0.2, 0.7, 0.1], [0, 0.3, 0.7]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 1.).abs() < 1e-10);
+
+    // rotation by 90 degrees, the eigenvalues are i and -i
+    let res = quick_eval("spectral_radius([[0, -1], [1, 0]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 1.).abs() < 1e-10);
+
+    let res = quick_eval("spectral_radius([[2, 1], [1, 2]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 3.).abs() < 1e-10);
+
+    // periodic Markov chains and permutation matrices, all eigenvalues lie on the unit circle
+    for m in ["[[0, 0, 1], [1, 0, 0], [0, 1, 0]]", "[[0, 1, 0], [0, 0, 1], [1, 0, 0]]", "[[0, 1], [1, 0]]",
+              "[[0, 0, 0, 1], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]]", "[[0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1], [1, 0, 0, 0]]",
+              "[[0, 0.5, 0, 0.5], [0.5, 0, 0.5, 0], [0, 0.5, 0, 0.5], [0.5, 0, 0.5, 0]]"] {
+        let res = quick_eval(format!("spectral_radius({})", m), &Context::empty())?.to_vec();
+        assert!((res[0].get_scalar().unwrap() - 1.).abs() < 1e-10, "{}", m);
+    }
+
+    // companion matrix of (x-1)(x+2)(x-3)(x+4)
+    let res = quick_eval("spectral_radius([[-2, 13, 14, -24], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 4.).abs() < 1e-8);
+
+    let res = quick_eval("spectral_radius([[1, 2, 3], [4, 5, 6]])", &Context::empty());
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can only calculate the spectral radius of a square matrix!".to_string())));
+
+    // the induced 2-norm is the largest singular value
+    let res = quick_eval("norm2([[2, 1], [1, 2]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 3.).abs() < 1e-10);
+
+    let res = quick_eval("norm2([[3, 0, 0], [0, 4, 0]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 4.).abs() < 1e-10);
+
+    let res = quick_eval("norm2([[1, 1, 1]])", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 3f64.sqrt()).abs() < 1e-10);
+
+    assert!(quick_eval("norm2([1, 2])", &Context::empty()).is_err());
+    assert_eq!(parse("norm2(A)")?.as_latex(), "\\left\\|A\\right\\|_{2}");
+    Ok(())
+}
+
+#[test]
+fn medium_eval42() -> Result<(), MathLibError> {
+    let x = Variable::new("x", vec![Value::Scalar(-2.), Value::Scalar(2.)]);
+    let mut context = Context::new(vec![x], vec![Function::new("g", parse("h(t)")?, vec!["t"]), Function::new("h", parse("f(t)")?, vec!["t"])]);
+
+    let f = Function::new("f", parse("a*y^2+b")?, vec!["y", "b"]);
+    assert_eq!(f.validate(&context), Err(EvalError::NoVariable("a".to_string())));
+
+    let f = Function::new("f", parse("x_1*y^2+I(t, t, 0, b)")?, vec!["y", "b"]);
+    assert_eq!(f.validate(&context), Ok(()));
+    assert_eq!(Function::new("f", parse("x_2*y")?, vec!["y"]).validate(&context), Err(EvalError::NoVariable("x_2".to_string())));
+
+    let f = Function::new("f", parse("2g(y)")?, vec!["y"]);
+    assert_eq!(f.validate(&context), Err(EvalError::RecursiveFunction));
+
+    context.funs.remove(1);
+    assert_eq!(f.validate(&context), Ok(()));
+
+    Ok(())
+}
+
+#[cfg(feature = "bignum")]
+#[test]
+fn medium_eval43() -> Result<(), MathLibError> {
+    use crate::eval_scalar_bignum;
+
+    let res = eval_scalar_bignum(&parse("1/3")?, &Context::empty(), 50)?;
+    assert_eq!(res.to_string(), format!("0.{}", "3".repeat(50)));
+
+    let res = eval_scalar_bignum(&parse("sqrt(2)^2-2")?, &Context::empty(), 50)?;
+    assert!(res.to_f64().value().abs() < 1e-48);
+
+    let res = eval_scalar_bignum(&parse("[1, 2]")?, &Context::empty(), 50);
+    assert!(res.is_err());
+
+    for expr in ["0^(-1)", "0^(-0.5)", "root(0, -2)"] {
+        let res = eval_scalar_bignum(&parse(expr)?, &Context::empty(), 50);
+        assert_eq!(res.unwrap_err(), EvalError::MathError("Can't raise zero to a negative power!".to_string()));
+    }
+    assert_eq!(eval_scalar_bignum(&parse("0^2")?, &Context::empty(), 50)?.to_string(), "0");
+
+    let context = Context::default();
+    let res = eval_scalar_bignum(&parse("pi")?, &context, 50)?;
+    assert_eq!(res.to_string(), "3.1415926535897932384626433832795028841971693993751");
+    let res = eval_scalar_bignum(&parse("e")?, &context, 50)?;
+    assert_eq!(res.to_string(), "2.7182818284590452353602874713526624977572470937");
+    let res = eval_scalar_bignum(&parse("tau")?, &context, 50)?;
+    assert_eq!(res.to_string(), "6.2831853071795864769252867665590057683943387987502");
+    let res = eval_scalar_bignum(&parse("phi")?, &context, 50)?;
+    assert_eq!(res.to_string(), "1.6180339887498948482045868343656381177203091798058");
+
+    // a redefined constant is used as is
+    let context = Context::from_vars(vec![Variable::new("pi", vec![Value::Scalar(3.)])]);
+    assert_eq!(eval_scalar_bignum(&parse("pi")?, &context, 50)?.to_string(), "3");
+
+    let res = eval_scalar_bignum(&parse("sin(1)")?, &Context::empty(), 50);
+    assert_eq!(res.unwrap_err(), EvalError::MathError("Can't evaluate sin in arbitrary precision! Only +, -, *, /, ^, sqrt, root, ln and abs are supported.".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval44() -> Result<(), MathLibError> {
+    assert_eq!(quick_eval("heaviside(-1)", &Context::empty())?.to_vec(), vec![Value::Scalar(0.)]);
+    assert_eq!(quick_eval("heaviside(1)", &Context::empty())?.to_vec(), vec![Value::Scalar(1.)]);
+    assert_eq!(quick_eval("heaviside(0)", &Context::empty())?.to_vec(), vec![Value::Scalar(0.5)]);
+    assert_eq!(quick_eval("heaviside([-2, 0, 3])", &Context::empty())?.to_vec(), vec![Value::Vector(vec![0., 0.5, 1.])]);
+    assert!(crate::maths::heaviside(&Value::Scalar(f64::NAN)).unwrap().get_scalar().unwrap().is_nan());
+
+    // the integral of a gaussian with standard deviation σ times t^2 is 0.5^2+σ^2
+    let res = quick_eval("I(dirac(t-0.5, 0.05)*t^2, t, -1, 2)", &Context::empty())?.to_vec();
+    assert!((res[0].get_scalar().unwrap() - 0.2525).abs() < 1e-4);
+
+    assert!(quick_eval("dirac(1, 0)", &Context::empty()).is_err());
+    assert_eq!(parse("2heaviside(t-1)")?.as_latex(), "2H{(t-1)}");
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval45() -> Result<(), MathLibError> {
+    use crate::eval_with_stats;
+
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![1., 3.], vec![2., 4.]])]);
+    let b = Variable::new("B", vec![Value::Matrix(vec![vec![2., 0.], vec![0., 2.]])]);
+    let context = Context::from_vars(vec![a, b]);
+
+    let (res, stats) = eval_with_stats(&parse("det(A)+det(A)")?, &context)?;
+    assert_eq!(res.round(3).to_vec(), vec![Value::Scalar(-4.)]);
+    assert_eq!(stats.determinants, 1);
+
+    let (res, stats) = eval_with_stats(&parse("det(A)*det(B)-det(2A)")?, &context)?;
+    assert_eq!(res.round(3).to_vec(), vec![Value::Scalar(0.)]);
+    assert_eq!(stats.determinants, 3);
+
+    let (_, stats) = eval_with_stats(&parse("A/B+A/B")?, &context)?;
+    assert_eq!(stats.inversions, 1);
+
+    assert!(quick_eval("det([1, 2])", &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval46() -> Result<(), MathLibError> {
+    let res = quick_eval("[[3, 4, 5],\n [1, 2, 3],\n\t[5, 6, 7]]", &Context::empty())?.to_vec();
+    #[cfg(not(feature = "row-major"))]
+    assert_eq!(res[0], Value::Matrix(vec![vec![3., 1., 5.], vec![4., 2., 6.], vec![5., 3., 7.]]));
+    #[cfg(feature = "row-major")]
+    assert_eq!(res[0], Value::Matrix(vec![vec![3., 4., 5.], vec![1., 2., 3.], vec![5., 6., 7.]]));
+
+    let res = quick_eval("1 + 2 +\n3 * 4 -\r\n\t5", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(10.));
+
+    let res = parse("1 +\n2");
+    assert_eq!(res, parse("1+2"));
+
+    let res = parse("1+2\n3");
+    assert_eq!(res, Err(ParserError::AmbiguousNumber("2 3".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn decimal_separator1() -> Result<(), MathLibError> {
+    use crate::normalize_decimal_separator;
+
+    let res = quick_eval(normalize_decimal_separator("3,14 * 2"), &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(6.28));
+
+    assert_eq!(normalize_decimal_separator("(0,5+1,25)*2"), "(0.5+1.25)*2");
+    assert_eq!(normalize_decimal_separator("3(1,5)"), "3(1.5)");
+
+    // commas separating arguments or entries are kept
+    assert_eq!(normalize_decimal_separator("root(8,3)+[1,2]+f(2,3)*log1p(1,5)"), "root(8,3)+[1,2]+f(2,3)*log1p(1,5)");
+    assert_eq!(normalize_decimal_separator("2,5*{1,5}"), "2.5*{1,5}");
+    assert_eq!(normalize_decimal_separator("x, 3"), "x, 3");
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval47() -> Result<(), MathLibError> {
+    let res = quick_eval("mean([1, 2, 3, 4])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(2.5));
+
+    let v = Variable::new("v", vec![Value::Vector(vec![2., 4., 4., 4., 5., 5., 7., 9.])]);
+    let context = Context::from_vars(vec![v]);
+
+    assert_eq!(quick_eval("var(v)", &context)?.to_vec(), vec![Value::Scalar(4.)]);
+    assert_eq!(quick_eval("std(v)", &context)?.to_vec(), vec![Value::Scalar(2.)]);
+    assert_eq!(quick_eval("var(v, 1)", &context)?.to_vec(), vec![Value::Scalar(32./7.)]);
+    assert_eq!(quick_eval("std(v, 1)", &context)?.round(3).to_vec(), vec![Value::Scalar(2.138)]);
+
+    assert!(quick_eval("mean(3)", &Context::empty()).is_err());
+    assert!(quick_eval("var([[1, 2], [3, 4]])", &Context::empty()).is_err());
+    assert!(quick_eval("var([1], 1)", &Context::empty()).is_err());
+
+    assert_eq!(parse("var(v, 1)")?.as_latex(), "\\operatorname{var}{(v, 1)}");
+    assert_eq!(parse("mean(v)")?.as_latex(), "\\operatorname{mean}{(v)}");
+    assert_eq!(parse("std(v)")?.as_string(), "std(v)");
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval48() -> Result<(), MathLibError> {
+    let v = Variable::new("v", vec![Value::Vector(vec![3., 1., 4., 1., 5.])]);
+    let context = Context::from_vars(vec![v]);
+
+    assert_eq!(quick_eval("vsum(v)", &context)?.to_vec(), vec![Value::Scalar(14.)]);
+    assert_eq!(quick_eval("vprod(v)", &context)?.to_vec(), vec![Value::Scalar(60.)]);
+    assert_eq!(quick_eval("vmin(v)", &context)?.to_vec(), vec![Value::Scalar(1.)]);
+    assert_eq!(quick_eval("vmax(v)", &context)?.to_vec(), vec![Value::Scalar(5.)]);
+    assert_eq!(quick_eval("vmax(v)-vmin(v)", &context)?.to_vec(), vec![Value::Scalar(4.)]);
+
+    assert!(quick_eval("vsum(3)", &Context::empty()).is_err());
+    assert!(quick_eval("vmin([[1, 2], [3, 4]])", &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval49() -> Result<(), MathLibError> {
+    // the transition matrix of medium_eval31, built directly so it doesn't depend on the matrix layout
+    let m = Value::Matrix(vec![vec![0.9, 0.2, 0.], vec![0.1, 0.7, 0.3], vec![0., 0.1, 0.7]]);
+    let context = Context::from_vars(vec![Variable::new("M", vec![m.clone()])]);
+
+    let steady_state = quick_eval("steady_state(M)", &context)?.round(6).to_vec();
+    assert_eq!(steady_state, vec![Value::Vector(vec![0.6, 0.3, 0.1])]);
+
+    // every column of M^n converges to the steady state
+    let mut power = m.clone();
+    for _ in 1..200 {
+        power = &power * &m;
+    }
+    match power {
+        Value::Matrix(p) => {
+            for j in 0..3 {
+                assert_eq!(Value::Vector(p.iter().map(|r| (r[j]*1e6).round()/1e6).collect()), steady_state[0]);
+            }
+        },
+        _ => panic!("Expected a matrix!")
+    }
+
+    // two closed classes, so there is no unique steady state
+    let res = quick_eval("steady_state([[1, 0], [0, 1]])", &Context::empty());
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("The Markov chain doesn't have a unique steady state!".to_string())));
+
+    let res = quick_eval("steady_state([[1, 2], [3, 4]])", &Context::empty());
+    assert_eq!(res.unwrap_err(), QuickEvalError::EvalError(EvalError::MathError("Can only calculate the steady state of a column stochastic matrix!".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval50() -> Result<(), MathLibError> {
+    let res = quick_eval("identity(3)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 0., 0.], vec![0., 1., 0.], vec![0., 0., 1.]]));
+
+    let res = quick_eval("diag([1, 2, 3])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 0., 0.], vec![0., 2., 0.], vec![0., 0., 3.]]));
+
+    let res = quick_eval("zeros(2, 3)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![0., 0., 0.], vec![0., 0., 0.]]));
+
+    let res = quick_eval("ones(2, 1)*[3]", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![3., 3.]));
+
+    let res = quick_eval("det(2identity(3))", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(8.));
+
+    for expr in ["identity(1.5)", "identity(-2)", "zeros(2, 0)", "ones([1, 2], 2)", "diag(3)", "identity(100000000000)", "zeros(100000000000, 100000000000)", "ones(100000, 100000)", "diag(range(1, 100000))"] {
+        assert!(quick_eval(expr, &Context::empty()).is_err());
+    }
+    assert_eq!(parse("zeros(2)"), Err(ParserError::WrongNumberOfArgs("zeros".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval51() -> Result<(), MathLibError> {
+    let res = quick_eval("range(1, 5)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1., 2., 3., 4., 5.]));
+
+    let res = quick_eval("range(-1, -1)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![-1.]));
+
+    let res = quick_eval("linspace(0, 1, 5)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![0., 0.25, 0.5, 0.75, 1.]));
+
+    let res = quick_eval("linspace(0, 1, 4)", &Context::empty())?.round(6).to_vec();
+    assert_eq!(res[0], Value::Vector(vec![0., 0.333333, 0.666667, 1.]));
+
+    let res = quick_eval("vsum(range(1, 100))", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(5050.));
+
+    for expr in ["range(5, 1)", "range(1.5, 3)", "linspace(0, 1, 0)", "linspace(0, 1, 2.5)", "linspace([1], 2, 3)", "range(0, 100000000000000000000)", "linspace(0, 1, 100000000000)"] {
+        assert!(quick_eval(expr, &Context::empty()).is_err());
+    }
+    assert_eq!(parse("linspace(0, 1)"), Err(ParserError::WrongNumberOfArgs("linspace".to_string())));
+    assert_eq!(parse("linspace(0, x, 2n)")?.as_string(), "linspace(0, x, 2n)");
+
+    Ok(())
+}
+
+#[test]
+fn medium_eval52() -> Result<(), MathLibError> {
+    use crate::latex::continued_fraction_as_latex;
+
+    let res = quick_eval("continued_fraction(phi, 15)", &Context::default())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1.; 15]));
+
+    let res = quick_eval("continued_fraction(pi, 4)", &Context::default())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![3., 7., 15., 1.]));
+
+    // rational numbers have a finite continued fraction
+    let res = quick_eval("continued_fraction(-7/4, 10)", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![-2., 4.]));
+
+    assert!(quick_eval("continued_fraction([1, 2], 3)", &Context::empty()).is_err());
+    assert!(quick_eval("continued_fraction(2, 0)", &Context::empty()).is_err());
+
+    assert_eq!(continued_fraction_as_latex(&[3., 7., 15., 1.]), "3+\\cfrac{1}{7+\\cfrac{1}{15+\\cfrac{1}{1}}}");
+    assert_eq!(continued_fraction_as_latex(&[-2.]), "-2");
+    assert_eq!(continued_fraction_as_latex(&[]), "");
+
+    Ok(())
+}
+
+#[test]
+fn sample1() -> Result<(), MathLibError> {
+    let f = Function::new("f", parse("x^2")?, vec!["x"]);
+
+    let samples = f.sample(&Context::empty(), "x", 0., 4., 5)?;
+    assert_eq!(samples, vec![(0., Value::Scalar(0.)), (1., Value::Scalar(1.)), (2., Value::Scalar(4.)), (3., Value::Scalar(9.)), (4., Value::Scalar(16.))]);
+
+    // other variables come from the context, every result of ± gets its own pair
+    let g = Function::new("g", parse("a*(&sqrt(x))")?, vec!["x"]);
+    let context = Context::from_vars(vec![Variable::new("a", vec![Value::Scalar(2.)])]);
+    let samples = g.sample(&context, "x", 1., 4., 2)?;
+    assert_eq!(samples, vec![(1., Value::Scalar(2.)), (1., Value::Scalar(-2.)), (4., Value::Scalar(4.)), (4., Value::Scalar(-4.))]);
+
+    assert_eq!(f.sample(&Context::empty(), "x", 0., 1., 0)?, vec![]);
+    assert_eq!(f.sample(&Context::empty(), "y", 0., 1., 2), Err(EvalError::NoVariable("x".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn eval_many1() -> Result<(), MathLibError> {
+    use crate::eval_many;
+
+    let ast = parse("x^2")?;
+    let contexts: Vec<Context> = (1..=5).map(|i| Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(i as f64)])])).collect();
+
+    let res: Vec<Vec<Value>> = eval_many(&ast, &contexts)?.into_iter().map(|v| v.to_vec()).collect();
+    assert_eq!(res, vec![vec![Value::Scalar(1.)], vec![Value::Scalar(4.)], vec![Value::Scalar(9.)], vec![Value::Scalar(16.)], vec![Value::Scalar(25.)]]);
+
+    assert_eq!(eval_many(&ast, &[])?, vec![]);
+
+    let mut contexts = contexts;
+    contexts.push(Context::empty());
+    assert_eq!(eval_many(&ast, &contexts), Err(EvalError::NoVariable("x".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn order1() -> Result<(), MathLibError> {
+    use crate::helpers::cart_prod;
+
+    let product = cart_prod(&vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+    assert_eq!(product, vec![vec![1, 3, 5], vec![1, 3, 6], vec![1, 4, 5], vec![1, 4, 6], vec![2, 3, 5], vec![2, 3, 6], vec![2, 4, 5], vec![2, 4, 6]]);
+
+    let s = |v: &[f64]| v.iter().map(|x| Value::Scalar(*x)).collect::<Vec<Value>>();
+    let a = Variable::new("a", s(&[1., 2.]));
+    let b = Variable::new("b", s(&[10., 20.]));
+    let c = Variable::new("c", s(&[100., 200.]));
+    let f = Function::new("f", parse("x+y+z")?, vec!["x", "y", "z"]);
+    let context = Context::new(vec![a, b, c], vec![f]);
+
+    assert_eq!(quick_eval("a+b+c", &context)?.to_vec(), s(&[111., 211., 121., 221., 112., 212., 122., 222.]));
+
+    // c varies slowest and a fastest in all of these
+    let expected = s(&[111., 112., 121., 122., 211., 212., 221., 222.]);
+    assert_eq!(quick_eval("c+b+a", &context)?.to_vec(), expected);
+    assert_eq!(quick_eval("f(c, b, a)", &context)?.to_vec(), expected);
+    assert_eq!(quick_eval("vsum([c, b, a])", &context)?.to_vec(), expected);
+    assert_eq!(quick_eval("det([[c, 0], [0, 1]])+det([[1, 0], [0, b]])+a", &context)?.to_vec(), expected);
+
+    let res = quick_eval("[&1, &2, &3]", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Vector(vec![1., 2., 3.]));
+    assert_eq!(res[1], Value::Vector(vec![1., 2., -3.]));
+    assert_eq!(res[7], Value::Vector(vec![-1., -2., -3.]));
+
+    Ok(())
+}
+
+#[test]
+fn angle1() -> Result<(), MathLibError> {
+    use crate::AngleMode;
+
+    let scalar = |expr: &str, context: &Context| -> Result<f64, MathLibError> {
+        match quick_eval(expr, context)?.to_vec()[0] {
+            Value::Scalar(x) => return Ok(x),
+            _ => return Err(MathLibError::Other("Expected a scalar!".to_string()))
+        }
+    };
+
+    let mut context = Context::default();
+    context.angle_mode = AngleMode::Degrees;
+
+    assert_eq!(scalar("sin(90)", &context)?, 1.);
+    assert!((scalar("cos(60)", &context)?-0.5).abs() < 1e-12);
+    assert!((scalar("tan(45)", &context)?-1.).abs() < 1e-12);
+    assert_eq!(scalar("arcsin(1)", &context)?, 90.);
+    assert!((scalar("arccos(0.5)", &context)?-60.).abs() < 1e-12);
+    assert_eq!(scalar("arctan(1)", &context)?, 45.);
+
+    context.add_fun(&Function::new("f", parse("sin(x)")?, vec!["x"]));
+    assert_eq!(scalar("f(90)", &context)?, 1.);
+
+    assert!((scalar("sin(90)", &Context::default())?-0.894).abs() < 1e-3);
+
+    Ok(())
+}
+
+#[test]
+fn atan2_1() -> Result<(), MathLibError> {
+    use std::f64::consts::PI;
+
+    let scalar = |expr: &str| -> Result<f64, MathLibError> {
+        match quick_eval(expr, &Context::empty())?.to_vec()[0] {
+            Value::Scalar(x) => return Ok(x),
+            _ => return Err(MathLibError::Other("Expected a scalar!".to_string()))
+        }
+    };
+
+    assert_eq!(scalar("atan2(1, 1)")?, PI/4.);
+    assert_eq!(scalar("atan2(1, -1)")?, 3.*PI/4.);
+    assert_eq!(scalar("atan2(-1, -1)")?, -3.*PI/4.);
+    assert_eq!(scalar("atan2(-1, 1)")?, -PI/4.);
+    assert_eq!(scalar("atan2(1, 0)")?, PI/2.);
+
+    assert!(quick_eval("atan2([1, 2], 1)", &Context::empty()).is_err());
+    assert_eq!(parse("atan2(y, x)")?.as_latex(), "\\operatorname{atan2}{(y, x)}");
+
+    Ok(())
+}
+
+#[test]
+fn min_max1() -> Result<(), MathLibError> {
+    assert_eq!(quick_eval("min(3, 5)", &Context::empty())?.to_vec()[0], Value::Scalar(3.));
+    assert_eq!(quick_eval("max(-2, -7)", &Context::empty())?.to_vec()[0], Value::Scalar(-2.));
+    assert_eq!(quick_eval("max(0, min(x, 1))", &Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(1.5)])]))?.to_vec()[0], Value::Scalar(1.));
+    assert_eq!(quick_eval("vmin([4, 2, 3])+min(1, 2)", &Context::empty())?.to_vec()[0], Value::Scalar(3.));
+
+    assert!(quick_eval("min([1, 2], 3)", &Context::empty()).is_err());
+    assert_eq!(parse("min(a, b)")?.as_latex(), "\\min\\left(a, b\\right)");
+
+    Ok(())
+}
+
+#[test]
+fn gcd_lcm1() -> Result<(), MathLibError> {
+    let scalar = |expr: &str| -> Result<Value, MathLibError> {
+        return Ok(quick_eval(expr, &Context::empty())?.to_vec()[0].clone());
+    };
+
+    assert_eq!(scalar("gcd(12, 18)")?, Value::Scalar(6.));
+    assert_eq!(scalar("lcm(4, 6)")?, Value::Scalar(12.));
+    assert_eq!(scalar("gcd(-12, 18)")?, Value::Scalar(6.));
+    assert_eq!(scalar("gcd(0, 5)")?, Value::Scalar(5.));
+    assert_eq!(scalar("gcd(0, 0)")?, Value::Scalar(0.));
+    assert_eq!(scalar("lcm(0, 5)")?, Value::Scalar(0.));
+    assert_eq!(scalar("lcm(7, 13)")?, Value::Scalar(91.));
+
+    assert!(quick_eval("gcd(1.5, 3)", &Context::empty()).is_err());
+    assert!(quick_eval("lcm([1, 2], 3)", &Context::empty()).is_err());
+    assert_eq!(parse("gcd(a, b)")?.as_latex(), "\\gcd{(a, b)}");
+    assert_eq!(parse("lcm(a, b)")?.as_latex(), "\\operatorname{lcm}{(a, b)}");
+
+    Ok(())
+}
+
+#[test]
+fn choose_perm1() -> Result<(), MathLibError> {
+    let scalar = |expr: &str| -> Result<Value, MathLibError> {
+        return Ok(quick_eval(expr, &Context::empty())?.to_vec()[0].clone());
+    };
+
+    assert_eq!(scalar("choose(5, 2)")?, Value::Scalar(10.));
+    assert_eq!(scalar("perm(5, 2)")?, Value::Scalar(20.));
+    assert_eq!(scalar("choose(5, 0)")?, Value::Scalar(1.));
+    assert_eq!(scalar("perm(5, 0)")?, Value::Scalar(1.));
+    assert_eq!(scalar("perm(5, 5)")?, Value::Scalar(120.));
+    // the naive formula overflows for 200!, the multiplicative one doesn't
+    assert_eq!(scalar("choose(200, 2)")?, Value::Scalar(19900.));
+    // past 2^53, n+1 == n in f64, so the product has to be counted with an integer
+    assert_eq!(scalar("perm(1e20, 1)")?, Value::Scalar(1e20));
+    assert_eq!(scalar("perm(1e17, 2)")?, Value::Scalar(1e17*(1e17-1.)));
+    assert_eq!(scalar("choose(1e17, 2)")?, Value::Scalar(1e17*(1e17-1.)/2.));
+
+    assert!(quick_eval("choose(2, 5)", &Context::empty()).is_err());
+    assert!(quick_eval("perm(-1, 0)", &Context::empty()).is_err());
+    assert!(quick_eval("choose(2.5, 1)", &Context::empty()).is_err());
+    assert_eq!(quick_eval("perm(1000, 1000)", &Context::empty()), Err(QuickEvalError::EvalError(EvalError::Overflow("perm".to_string()))));
+    assert_eq!(parse("choose(n, k)")?.as_latex(), "\\binom{n}{k}");
+
+    Ok(())
+}
+
+#[test]
+fn clamp1() -> Result<(), MathLibError> {
+    assert_eq!(quick_eval("clamp(-3, 0, 1)", &Context::empty())?.to_vec()[0], Value::Scalar(0.));
+    assert_eq!(quick_eval("clamp(0.25, 0, 1)", &Context::empty())?.to_vec()[0], Value::Scalar(0.25));
+    assert_eq!(quick_eval("clamp(7, 0, 1)", &Context::empty())?.to_vec()[0], Value::Scalar(1.));
+    assert_eq!(quick_eval("clamp(2, 2, 2)", &Context::empty())?.to_vec()[0], Value::Scalar(2.));
+    assert_eq!(quick_eval("clamp(2, 0, 1&0.5)", &Context::empty())?.to_vec(), vec![Value::Scalar(1.5), Value::Scalar(0.5)]);
+
+    assert!(quick_eval("clamp(0.5, 1, 0)", &Context::empty()).is_err());
+    assert!(quick_eval("clamp([1, 2], 0, 1)", &Context::empty()).is_err());
+    assert_eq!(parse("clamp(1, 2)"), Err(ParserError::WrongNumberOfArgs("clamp".to_string())));
+    assert_eq!(parse("clamp(x, 0, 1)")?.as_latex(), "\\operatorname{clamp}{(x, 0, 1)}");
+    assert_eq!(parse("clamp(x, a, b)")?.as_string(), "clamp(x, a, b)");
+
+    Ok(())
+}
+
+#[test]
+fn rank1() -> Result<(), MathLibError> {
+    let rank = |expr: &str| -> Result<Value, MathLibError> {
+        return Ok(quick_eval(expr, &Context::empty())?.to_vec()[0].clone());
+    };
+
+    assert_eq!(rank("rank([[2, 0, 1], [1, 3, 0], [0, 1, 4]])")?, Value::Scalar(3.));
+    // the third column is the sum of the first two, so the determinant is 0
+    assert_eq!(rank("rank([[1, 2, 3], [4, 5, 6], [5, 7, 9]])")?, Value::Scalar(2.));
+    assert_eq!(rank("rank([[1, 2], [2, 4], [0, 1], [3, 3]])")?, Value::Scalar(2.));
+    assert_eq!(rank("rank([[1, 2], [2, 4], [3, 6]])")?, Value::Scalar(1.));
+    assert_eq!(rank("rank(zeros(2, 3))")?, Value::Scalar(0.));
+    assert_eq!(rank("rank(identity(4))")?, Value::Scalar(4.));
+
+    assert!(quick_eval("rank([1, 2])", &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn rref1() -> Result<(), MathLibError> {
+    // the augmented matrix of x+y+z = 6, 2y+5z = -4 and 2x+5y-z = 27
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![1., 1., 1., 6.], vec![0., 2., 5., -4.], vec![2., 5., -1., 27.]])]);
+    // the second row is twice the first one
+    let b = Variable::new("B", vec![Value::Matrix(vec![vec![1., 2., 3.], vec![2., 4., 6.], vec![0., 1., 1.]])]);
+    let context = Context::from_vars(vec![a, b]);
+
+    let res = quick_eval("rref(A)", &context)?.to_vec();
+    let expected = [[1., 0., 0., 5.], [0., 1., 0., 3.], [0., 0., 1., -2.]];
+    match &res[0] {
+        Value::Matrix(m) => {
+            assert_eq!(m.len(), 3);
+            for i in 0..3 {
+                for j in 0..4 {
+                    assert!((m[i][j]-expected[i][j]).abs() < 1e-12);
+                }
+            }
+        },
+        _ => panic!("Expected a matrix!")
+    }
+
+    let res = quick_eval("rref(B)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 0., 1.], vec![0., 1., 1.], vec![0., 0., 0.]]));
+    assert_eq!(quick_eval("rank(B)", &context)?.to_vec()[0], Value::Scalar(2.));
+
+    assert!(quick_eval("rref([1, 2])", &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn slice1() -> Result<(), MathLibError> {
+    let v = Variable::new("v", vec![Value::Vector(vec![1., 2., 3., 4., 5.])]);
+    let context = Context::from_vars(vec![v]);
+
+    assert_eq!(quick_eval("slice([1, 2, 3, 4, 5], 1, 3)", &context)?.to_vec()[0], Value::Vector(vec![2., 3.]));
+    assert_eq!(quick_eval("slice(v, -2, 5)", &context)?.to_vec()[0], Value::Vector(vec![4., 5.]));
+    assert_eq!(quick_eval("slice(v, 0, -1)", &context)?.to_vec()[0], Value::Vector(vec![1., 2., 3., 4.]));
+    assert_eq!(quick_eval("slice(v, -1, 5)?0", &context)?.to_vec()[0], Value::Scalar(5.));
+
+    assert!(quick_eval("slice(v, 0, 6)", &context).is_err());
+    assert!(quick_eval("slice(v, -6, 2)", &context).is_err());
+    assert!(quick_eval("slice(v, 3, 1)", &context).is_err());
+    assert!(quick_eval("slice(v, 0.5, 1)", &context).is_err());
+    // single indices still have to be non-negative
+    assert!(quick_eval("v?(-1)", &context).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn row_col1() -> Result<(), MathLibError> {
+    let m = Variable::new("M", vec![Value::Matrix(vec![vec![1., 2., 3.], vec![4., 5., 6.], vec![7., 8., 9.]])]);
+    let context = Context::from_vars(vec![m]);
+
+    assert_eq!(quick_eval("row(M, 1)", &context)?.to_vec()[0], Value::Vector(vec![4., 5., 6.]));
+    assert_eq!(quick_eval("col(M, 2)", &context)?.to_vec()[0], Value::Vector(vec![3., 6., 9.]));
+    assert_eq!(quick_eval("row(M, 0)?2", &context)?.to_vec()[0], quick_eval("col(M, 2)?0", &context)?.to_vec()[0]);
+
+    // rows and columns follow the orientation of the parsed matrix
+    #[cfg(not(feature = "row-major"))]
+    assert_eq!(quick_eval("col([[1, 2], [3, 4]], 1)", &Context::empty())?.to_vec()[0], Value::Vector(vec![3., 4.]));
+    #[cfg(feature = "row-major")]
+    assert_eq!(quick_eval("row([[1, 2], [3, 4]], 1)", &Context::empty())?.to_vec()[0], Value::Vector(vec![3., 4.]));
+
+    assert!(quick_eval("row(M, 3)", &context).is_err());
+    assert!(quick_eval("col(M, -1)", &context).is_err());
+    assert!(quick_eval("row([1, 2], 0)", &context).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn concat1() -> Result<(), MathLibError> {
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![1., 2.], vec![3., 4.]])]);
+    let b = Variable::new("B", vec![Value::Matrix(vec![vec![5., 6.], vec![7., 8.]])]);
+    let c = Variable::new("C", vec![Value::Matrix(vec![vec![9., 10., 11.]])]);
+    let context = Context::from_vars(vec![a, b, c]);
+
+    assert_eq!(quick_eval("concat([1, 2], [3, 4])", &context)?.to_vec()[0], Value::Vector(vec![1., 2., 3., 4.]));
+    assert_eq!(quick_eval("hstack(A, B)", &context)?.to_vec()[0], Value::Matrix(vec![vec![1., 2., 5., 6.], vec![3., 4., 7., 8.]]));
+    assert_eq!(quick_eval("vstack(A, B)", &context)?.to_vec()[0], Value::Matrix(vec![vec![1., 2.], vec![3., 4.], vec![5., 6.], vec![7., 8.]]));
+
+    assert!(quick_eval("vstack(A, C)", &context).is_err());
+    assert!(quick_eval("hstack(A, C)", &context).is_err());
+    assert!(quick_eval("concat(A, B)", &context).is_err());
+    assert!(quick_eval("concat([1, 2], 3)", &context).is_err());
 
     Ok(())
 }
 
 #[test]
-fn calculus_eval2() -> Result<(), MathLibError> {
-    let res = quick_eval("I(x^2, x, 0, 5)", &Context::empty())?.to_vec();
+fn reshape1() -> Result<(), MathLibError> {
+    let res = quick_eval("reshape([1, 2, 3, 4, 5, 6], 2, 3)", &Context::empty())?.to_vec();
+    // the entries are used in the same order as in a matrix literal
+    #[cfg(not(feature = "row-major"))]
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 3., 5.], vec![2., 4., 6.]]));
+    #[cfg(not(feature = "row-major"))]
+    assert_eq!(res[0], quick_eval("[[1, 2], [3, 4], [5, 6]]", &Context::empty())?.to_vec()[0]);
+    #[cfg(feature = "row-major")]
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 2., 3.], vec![4., 5., 6.]]));
+    #[cfg(feature = "row-major")]
+    assert_eq!(res[0], quick_eval("[[1, 2, 3], [4, 5, 6]]", &Context::empty())?.to_vec()[0]);
+
+    let res = quick_eval("reshape([1, 2, 3, 4], 2, 3)", &Context::empty());
+    assert_eq!(res, Err(QuickEvalError::EvalError(EvalError::MathError("Can't reshape a vector with 4 entries into a 2×3 matrix!".to_string()))));
+    assert!(quick_eval("reshape([1, 2, 3, 4], 2.5, 2)", &Context::empty()).is_err());
+    assert!(quick_eval("reshape([[1, 2], [3, 4]], 1, 4)", &Context::empty()).is_err());
+    // huge dimensions mustn't overflow when multiplied
+    assert!(quick_eval("reshape([1, 2], 1e15, 1e15)", &Context::empty()).is_err());
+    assert!(quick_eval("reshape([1, 2], 10000000000, 10000000000)", &Context::empty()).is_err());
 
-    assert_eq!(res[0].round(4), Value::Scalar(41.6667));
+    Ok(())
+}
+
+#[test]
+fn outer1() -> Result<(), MathLibError> {
+    let res = quick_eval("outer([1, 2], [3, 4])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![3., 4.], vec![6., 8.]]));
+
+    let res = quick_eval("outer([1, 2, 3], [1, -1])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., -1.], vec![2., -2.], vec![3., -3.]]));
+
+    assert!(quick_eval("outer([1, 2], 3)", &Context::empty()).is_err());
+    assert!(quick_eval("outer(range(1, 100000), range(1, 100000))", &Context::empty()).is_err());
+    assert_eq!(parse("outer(a, b)")?.as_latex(), "a\\otimes b");
+    assert_eq!(parse("outer(a+b, c)")?.as_latex(), "\\left(a+b\\right)\\otimes c");
+
+    Ok(())
+}
+
+#[test]
+fn kron1() -> Result<(), MathLibError> {
+    let a = Variable::new("A", vec![Value::Matrix(vec![vec![1., 2.], vec![3., 4.]])]);
+    let b = Variable::new("B", vec![Value::Matrix(vec![vec![0., 5.], vec![6., 7.]])]);
+    let c = Variable::new("C", vec![Value::Matrix(vec![vec![1., 1., 1.]])]);
+    let context = Context::from_vars(vec![a, b, c]);
+
+    let res = quick_eval("kron(A, B)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![0., 5., 0., 10.], vec![6., 7., 12., 14.], vec![0., 15., 0., 20.], vec![18., 21., 24., 28.]]));
+
+    let res = quick_eval("kron(C, A)", &context)?.to_vec();
+    assert_eq!(res[0], Value::Matrix(vec![vec![1., 2., 1., 2., 1., 2.], vec![3., 4., 3., 4., 3., 4.]]));
+
+    assert!(quick_eval("kron(A, [1, 2])", &context).is_err());
+    assert!(quick_eval("kron(ones(1000, 1000), ones(100, 100))", &context).is_err());
+    assert_eq!(parse("kron(A, B)")?.as_latex(), "A\\otimes B");
+
+    Ok(())
+}
+
+#[test]
+fn frobenius1() -> Result<(), MathLibError> {
+    let res = quick_eval("abs([[3, 4], [0, 0]])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(5.));
+
+    // the Frobenius norm of a matrix is the length of the vector of its entries
+    let res = quick_eval("abs([[1, 2], [3, 4]])-abs([1, 2, 3, 4])", &Context::empty())?.to_vec();
+    assert_eq!(res[0], Value::Scalar(0.));
+    assert_eq!(parse("abs(A)")?.as_latex(), "|A|");
+
+    Ok(())
+}
+
+#[test]
+fn dot1() -> Result<(), MathLibError> {
+    let dot = parse("3*(x+1)")?.to_dot();
+
+    assert!(dot.starts_with("digraph AST {\n"));
+    for label in ["Mult", "3", "Parenths", "Add", "x", "1"] {
+        assert!(dot.contains(&format!("[label=\"{}\"]", label)), "missing {} in {}", label, dot);
+    }
+    assert_eq!(dot.matches(" -> ").count(), 5);
+    assert!(dot.contains("n0 -> n1;"));
+
+    // the placeholder of unary operations isn't part of the tree
+    assert_eq!(parse("sin(x)")?.to_dot().matches(" -> ").count(), 1);
+    assert!(parse("\\sigma")?.to_dot().contains("[label=\"\\\\sigma\"]"));
+
+    Ok(())
+}
+
+#[test]
+fn sign1() -> Result<(), MathLibError> {
+    assert_eq!(quick_eval("sign(3.5)", &Context::empty())?.to_vec()[0], Value::Scalar(1.));
+    assert_eq!(quick_eval("sign(-2)", &Context::empty())?.to_vec()[0], Value::Scalar(-1.));
+    // by convention the sign of 0 is 0
+    assert_eq!(quick_eval("sign(0)", &Context::empty())?.to_vec()[0], Value::Scalar(0.));
+    assert_eq!(quick_eval("sign([-4, 0, 7])", &Context::empty())?.to_vec()[0], Value::Vector(vec![-1., 0., 1.]));
+    assert_eq!(parse("sign(x)")?.as_latex(), "\\operatorname{sgn}{(x)}");
+
+    Ok(())
+}
+
+#[test]
+fn reciprocal_trig1() -> Result<(), MathLibError> {
+    let scalar = |expr: &str| -> Result<f64, MathLibError> {
+        match quick_eval(expr, &Context::default())?.to_vec()[0] {
+            Value::Scalar(x) => return Ok(x),
+            _ => return Err(MathLibError::Other("Expected a scalar!".to_string()))
+        }
+    };
+
+    assert_eq!(scalar("sec(0)")?, 1.);
+    assert!((scalar("cot(pi/4)")?-1.).abs() < 1e-12);
+    assert!((scalar("csc(pi/6)")?-2.).abs() < 1e-12);
+    // at the poles the result is inf, even if the pole isn't exactly representable
+    assert_eq!(scalar("csc(0)")?, f64::INFINITY);
+    assert_eq!(scalar("cot(0)")?, f64::INFINITY);
+    assert_eq!(scalar("sec(pi/2)")?, f64::INFINITY);
+    assert_eq!(scalar("sec(-3pi/2)")?, f64::INFINITY);
+    assert_eq!(scalar("csc(pi)")?, f64::INFINITY);
+    assert_eq!(scalar("cot(2pi)")?, f64::INFINITY);
+    assert!((scalar("csc(0.000000001)")?-1e9).abs() < 1e-3);
+    assert!(scalar("sec(pi/2-0.000000001)")?.is_finite());
+
+    let mut context = Context::default();
+    context.angle_mode = crate::AngleMode::Degrees;
+    assert!((quick_eval("sec(60)", &context)?.to_vec()[0].get_scalar().unwrap()-2.).abs() < 1e-12);
+    assert_eq!(quick_eval("sec(90)", &context)?.to_vec()[0], Value::Scalar(f64::INFINITY));
+    assert_eq!(parse("cot(x)+sec(x)+csc(x)")?.as_latex(), "\\cot{(x)}+\\sec{(x)}+\\csc{(x)}");
+
+    Ok(())
+}
+
+#[test]
+fn inverse_hyperbolic1() -> Result<(), MathLibError> {
+    let scalar = |expr: &str| -> Result<f64, MathLibError> {
+        match quick_eval(expr, &Context::default())?.to_vec()[0] {
+            Value::Scalar(x) => return Ok(x),
+            _ => return Err(MathLibError::Other("Expected a scalar!".to_string()))
+        }
+    };
+
+    assert_eq!(scalar("arsinh(0)")?, 0.);
+    assert!((scalar("arsinh(1)")?-(1.+2f64.sqrt()).ln()).abs() < 1e-12);
+    assert_eq!(scalar("arcosh(1)")?, 0.);
+    assert!((scalar("arcosh(2)")?-(2.+3f64.sqrt()).ln()).abs() < 1e-12);
+    assert!((scalar("artanh(0.5)")?-3f64.ln()/2.).abs() < 1e-12);
+
+    assert_eq!(quick_eval("arcosh(0.5)", &Context::empty()), Err(QuickEvalError::EvalError(EvalError::MathError("Can only take arcosh of a scalar >= 1!".to_string()))));
+    assert!(quick_eval("artanh(1)", &Context::empty()).is_err());
+    assert!(quick_eval("artanh(-2)", &Context::empty()).is_err());
+    assert_eq!(parse("arsinh(x)")?.as_latex(), "\\operatorname{arsinh}{(x)}");
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn computation1() -> Result<(), MathLibError> {
+    use crate::Computation;
+
+    let mut context = Context::default();
+    context.add_var(&Variable::new("x", vec![Value::Scalar(-2.), Value::Scalar(2.)]));
+    context.add_fun(&Function::new("f", parse("x^2+a")?, vec!["x", "a"]));
+
+    let computation = Computation::from_eval("f(x, 1)*[1, pi]", &context)?;
+    assert_eq!(computation.results.len(), 2);
+
+    let json = serde_json::to_string(&computation).map_err(|e| MathLibError::Other(e.to_string()))?;
+    let parsed: Computation = serde_json::from_str(&json).map_err(|e| MathLibError::Other(e.to_string()))?;
+
+    assert_eq!(parsed, computation);
+    assert_eq!(parsed.context.get_var("x"), context.get_var("x"));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn computation2() -> Result<(), MathLibError> {
+    use crate::{eval, Step};
+
+    let term = parse("3*x+1")?;
+    let context = Context::from_vars(vec![Variable::new("x", vec![Value::Scalar(2.)])]);
+    let history = vec![
+        Step::Calc { term: term.clone(), result: eval(&term, &context)?, variable_save: Some("y".to_string()) },
+        Step::Fun { term: parse("x^2")?, inputs: vec!["x".to_string()], name: "f".to_string() },
+        Step::Equ { eqs: vec![(parse("x^2")?, parse("4")?)], results: Values::from_vec(vec![Value::Scalar(-2.), Value::Scalar(2.)]), variable_save: None }
+    ];
+
+    let json = serde_json::to_string(&history).map_err(|e| MathLibError::Other(e.to_string()))?;
+    let parsed: Vec<Step> = serde_json::from_str(&json).map_err(|e| MathLibError::Other(e.to_string()))?;
+
+    assert_eq!(parsed.len(), history.len());
+    for i in 0..history.len() {
+        assert_eq!(parsed[i].as_latex(), history[i].as_latex());
+    }
 
     Ok(())
 }
@@ -407,6 +2241,49 @@ fn medium_solve3() -> Result<(), MathLibError> {
     Ok(())
 }
 
+#[test]
+fn medium_solve6() -> Result<(), MathLibError> {
+    // linear systems are solved directly instead of using Newton's method, which makes the results exact
+    let res = quick_eval("eq(2x+5y+2z=-38, 3x-2y+4z=17, -6x+y-7z=-12, x, y, z)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Vector(vec![3., -8., -2.])]);
+
+    let res = quick_eval("eq(3x-9z=33, 7x-4y-z=-15, 4x+6y+5z=-6, x, y, z)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Vector(vec![-1., 3., -4.])]);
+
+    let res = quick_eval("eq(4x-2=6, x)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(2.)]);
+
+    // not linear, even though it looks linear at most sample points
+    let res = quick_eval("eq(abs(x-5)=2, x)", &Context::empty())?.round(3).to_vec();
+    assert_eq!(res, vec![Value::Scalar(3.), Value::Scalar(7.)]);
+
+    Ok(())
+}
+
+#[test]
+fn medium_solve7() -> Result<(), MathLibError> {
+    // polynomials of degree 2 and 3 are solved using the closed-form formulas
+    let res = quick_eval("eq(x^2=9, x)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(-3.), Value::Scalar(3.)]);
+
+    let res = quick_eval("eq(3x^2+2x-1=0, x)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(-1.), Value::Scalar(1./3.)]);
+
+    let res = quick_eval("eq(x^3-6x^2+11x-6=0, x)", &Context::empty())?.round(9).to_vec();
+    assert_eq!(res, vec![Value::Scalar(1.), Value::Scalar(2.), Value::Scalar(3.)]);
+
+    let res = quick_eval("eq(x^3=8, x)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![Value::Scalar(2.)]);
+
+    let res = quick_eval("eq((x-1)^2*(x+2)=0, x)", &Context::empty())?.round(6).to_vec();
+    assert_eq!(res, vec![Value::Scalar(-2.), Value::Scalar(1.)]);
+
+    let res = quick_eval("eq(x^2+1=0, x)", &Context::empty())?.to_vec();
+    assert_eq!(res, vec![]);
+
+    Ok(())
+}
+
 #[test]
 fn medium_solve4() -> Result<(), MathLibError> {
     let function = parse("4x^2-9")?;
@@ -428,6 +2305,19 @@ fn calculus_solve1() -> Result<(), MathLibError> {
     Ok(())
 }
 
+#[test]
+fn medium_solve5() -> Result<(), MathLibError> {
+    let trace = crate::roots::newton_trace(&parse("x^2-2")?, "x", 1., &Context::empty())?;
+    assert!(trace.len() > 2);
+    assert!((trace[0] - 1.5).abs() < 1e-6);
+    for i in 1..trace.len() {
+        assert!(trace[i] <= trace[i-1]);
+    }
+    assert!((trace.last().unwrap() - 2f64.sqrt()).abs() < 1e-8);
+
+    Ok(())
+}
+
 #[test]
 fn hard_solve1() -> Result<(), MathLibError> {
     let equation = "eq(((25x^3-96x^2+512x+384)/(x^4+2x^3+90x^2-128x+1664)^(1.5))/(-sqrt(1-((32-x+x^2)/(((x-1)^2+25)(x^2+64)))^2))=0, x)";
@@ -492,6 +2382,353 @@ fn hard_solve6() -> Result<(), MathLibError> {
     Ok(())
 }
 
+#[test]
+fn hard_solve7() -> Result<(), MathLibError> {
+    // a large context, so that lookups go through the index of the context
+    let mut vars: Vec<Variable> = (0..20).map(|i| Variable::new(format!("c_{{{}}}", i), vec![Value::Scalar(i as f64)])).collect();
+    vars.push(Variable::new("a", vec![Value::Scalar(2.)]));
+    let mut context = Context::from_vars(vars);
+    context.add_fun(&Function::new("f", parse("a*t")?, vec!["t"]));
+
+    let equation = "eq(f(x)+5y+2z=-38, 3x-2y+4z=c_{17}, -6x+y-7z=-c_{12}, x, y, z)";
+
+    let res = quick_eval(equation, &context)?.round(3).to_vec();
+
+    assert_eq!(res, vec![Value::Vector(vec![3., -8., -2.])]);
+
+    context.remove_var("c_{17}");
+    assert!(!context.contains_var("c_{17}"));
+    assert_eq!(context.get_var("c_{18}").unwrap().values.get(0), Some(&Value::Scalar(18.)));
+    assert_eq!(context.get_var("a").unwrap().values.get(0), Some(&Value::Scalar(2.)));
+
+    // the index mustn't stop a context from being shared between threads
+    let context = std::sync::Arc::new(context);
+    let shared = context.clone();
+    let res = std::thread::spawn(move || shared.get_var("c_{3}").is_some()).join().unwrap();
+    assert!(res && context.contains_var("c_{4}"));
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve8() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let expr = parse("ln(x-4999)")?;
+
+    let res = RootFinder::new(vec![expr.clone()], Context::empty(), vec!["x".to_string()])?.find_roots()?;
+    assert!(res.is_empty());
+
+    let res = RootFinder::new(vec![expr], Context::empty(), vec!["x".to_string()])?
+        .with_guess_range(4990., 5010., 1.)
+        .with_max_iterations(100)
+        .find_roots()?;
+    assert_eq!(res.len(), 1);
+    assert!((res[0].get_scalar().unwrap()-5000.).abs() < 1e-6);
+
+    let res = RootFinder::new(vec![parse("x^2-4")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(0., -1., 1.).find_roots();
+    assert!(res.is_err());
+
+    // a tiny step would otherwise result in (almost) infinitely many starting points
+    let res = RootFinder::new(vec![parse("x^2-4")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(0., 1., 1e-300).find_roots();
+    assert!(res.is_err());
+    let res = RootFinder::new(vec![parse("x+y-4")?, parse("x-y")?, parse("x-2")?], Context::empty(), vec!["x".to_string(), "y".to_string()])?.with_guess_range(0., 1., 1e-300).find_roots();
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve9() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let expressions = vec![parse("y-x^2")?, parse("y-x-2")?];
+    let root_finder = RootFinder::new(expressions, Context::empty(), vec!["x".to_string(), "y".to_string()])?;
+    let res = root_finder.find_roots_named()?;
+    assert_eq!(res.len(), 2);
+    for r in &res {
+        assert_eq!(r.len(), 2);
+        let (x, y) = (r["x"], r["y"]);
+        assert!(((x-2.).abs() < 1e-6 && (y-4.).abs() < 1e-6) || ((x+1.).abs() < 1e-6 && (y-1.).abs() < 1e-6));
+    }
+    assert!((res[0]["x"]-res[1]["x"]).abs() > 1.);
+
+    let root_finder = RootFinder::new(vec![parse("x^2-9")?], Context::empty(), vec!["x".to_string()])?;
+    let res = root_finder.find_roots_named()?;
+    assert_eq!(res.iter().map(|r| r["x"].round()).collect::<Vec<f64>>(), vec![-3., 3.]);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve10() -> Result<(), MathLibError> {
+    use crate::solver::{augmented_matrix_latex, linear_system};
+
+    let system = parse("eq(x+y=3, 2x-y=1, x, y)")?;
+    let latex = augmented_matrix_latex(&system, &Context::empty())?;
+    assert_eq!(latex, "\\left[\\begin{array}{cc|c}1 & 1 & 3 \\\\2 & -1 & 1 \\\\\\end{array}\\right]");
+
+    let equations = vec![(parse("3a-c")?, parse("k")?), (parse("a+b+c")?, parse("0")?)];
+    let context = Context::from_vars(vec![Variable::new("k", vec![Value::Scalar(4.)])]);
+    let (a, b) = linear_system(&equations, &context, &["a".to_string(), "b".to_string(), "c".to_string()])?;
+    assert_eq!(a, vec![vec![3., 0., -1.], vec![1., 1., 1.]]);
+    assert_eq!(b, vec![4., 0.]);
+
+    let system = parse("eq(x*y=3, x-y=1, x, y)")?;
+    assert!(augmented_matrix_latex(&system, &Context::empty()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve11() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    // the first two equations are linearly dependent, so only the combination of the first and the
+    // last equation can be solved, while the second equation acts as a check
+    let expressions = vec![parse("x+y-3")?, parse("2x+2y-6")?, parse("x-y-1")?];
+    let root_finder = RootFinder::new(expressions, Context::empty(), vec!["x".to_string(), "y".to_string()])?;
+    let (roots, report) = root_finder.find_roots_with_report()?;
+    assert_eq!(Values::from_vec(roots).round(3).to_vec(), vec![Value::Vector(vec![2., 1.])]);
+    assert_eq!(report.combination, Some(vec![0, 2]));
+    assert_eq!(report.iterations.len(), 1);
+    assert!(report.iterations[0] <= 2);
+
+    let root_finder = RootFinder::new(vec![parse("x^2+1")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(-2., 2., 1.);
+    let (roots, report) = root_finder.find_roots_with_report()?;
+    assert!(roots.is_empty());
+    assert_eq!(report.combination, None);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve12() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let root_finder = |max_solutions: Option<usize>| -> Result<RootFinder, MathLibError> {
+        let root_finder = RootFinder::new(vec![parse("sin(x)")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(-20., 20., 0.5);
+        match max_solutions {
+            Some(m) => return Ok(root_finder.with_max_solutions(m)),
+            None => return Ok(root_finder)
+        }
+    };
+
+    let (roots, report) = root_finder(None)?.find_roots_with_report()?;
+    assert_eq!(roots.len(), 10);
+    assert!(report.truncated);
+
+    let (roots, report) = root_finder(Some(3))?.find_roots_with_report()?;
+    let pi = std::f64::consts::PI;
+    assert_eq!(Values::from_vec(roots).round(3).to_vec(), Values::from_vec(vec![Value::Scalar(-pi), Value::Scalar(0.), Value::Scalar(pi)]).round(3).to_vec());
+    assert!(report.truncated);
+
+    let (roots, report) = root_finder(Some(100))?.find_roots_with_report()?;
+    assert!(roots.len() > 10);
+    assert!(!report.truncated);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve13() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let root_finder = RootFinder::new(vec![parse("sqrt(x)-(x-2)")?], Context::empty(), vec!["x".to_string()])?.with_domain(parse("x>=0")?);
+    let roots = root_finder.find_roots()?;
+    assert_eq!(Values::from_vec(roots).round(6).to_vec(), vec![Value::Scalar(4.)]);
+
+    // squaring both sides introduces the extraneous root 1, which the domain x>=2 (from x-2 >= 0) discards
+    let root_finder = RootFinder::new(vec![parse("x-(x-2)^2")?], Context::empty(), vec!["x".to_string()])?;
+    assert_eq!(Values::from_vec(root_finder.find_roots()?).round(6).to_vec(), vec![Value::Scalar(1.), Value::Scalar(4.)]);
+    let roots = root_finder.with_domain(parse("x-2>=0")?).find_roots()?;
+    assert_eq!(Values::from_vec(roots).round(6).to_vec(), vec![Value::Scalar(4.)]);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve14() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let exact = 0.7390851332151607;
+    let root_finder = RootFinder::new(vec![parse("cos(x)-x")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(-2., 2., 1.);
+
+    let roots = root_finder.with_precision(14).find_roots()?;
+    assert_eq!(roots.len(), 1);
+    assert!((roots[0].get_scalar().unwrap() - exact).abs() < 1e-13);
+    assert_eq!(roots[0].display_with_precision(NumberFormat::Default, 3), "0.739");
+    assert_eq!(Value::Scalar(1234.5678).display_with_precision(NumberFormat::SiPrefix, 1), "1.2k");
+
+    // the directly solved polynomial has the roots 1 and 1.0001, which are the same root with 4 digits
+    let root_finder = RootFinder::new(vec![parse("x^2-2.0001x+1.0001")?], Context::empty(), vec!["x".to_string()])?;
+    assert_eq!(root_finder.find_roots()?.len(), 2);
+    let root_finder = RootFinder::new(vec![parse("x^2-2.0001x+1.0001")?], Context::empty(), vec!["x".to_string()])?;
+    assert_eq!(root_finder.with_precision(4).find_roots()?.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve15() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let root_finder = RootFinder::new(vec![parse("x^2-x-6")?], Context::empty(), vec!["x".to_string()])?;
+    let (roots, report) = root_finder.find_roots_with_report()?;
+    assert_eq!(Values::from_vec(roots).round(3).to_vec(), vec![Value::Scalar(-2.), Value::Scalar(3.)]);
+    assert_eq!(report.distinct_roots, 2);
+    assert!(!report.truncated);
+
+    let root_finder = RootFinder::new(vec![parse("sin(x)")?], Context::empty(), vec!["x".to_string()])?.with_guess_range(-20., 20., 0.5);
+    let (roots, report) = root_finder.find_roots_with_report()?;
+    assert_eq!(report.starting_points, 80);
+    assert!(report.converged >= report.distinct_roots);
+    assert!(report.distinct_roots > roots.len());
+    assert!(report.truncated);
+
+    Ok(())
+}
+
+#[test]
+fn hard_solve16() -> Result<(), MathLibError> {
+    use crate::roots::RootFinder;
+
+    let expression = parse("x-(1&3)")?;
+    let root_finder = || RootFinder::new(vec![expression.clone()], Context::empty(), vec!["x".to_string()]);
+
+    let roots = root_finder()?.find_roots()?;
+    assert_eq!(Values::from_vec(roots).round(3).to_vec(), vec![Value::Scalar(4.)]);
+
+    let roots = root_finder()?.with_all_branches(true).find_roots()?;
+    assert_eq!(Values::from_vec(roots).round(3).to_vec(), vec![Value::Scalar(-2.), Value::Scalar(4.)]);
+
+    let expressions = vec![parse("x+y-(3&1)")?, parse("x-y-(1&1)")?];
+    let root_finder = RootFinder::new(expressions, Context::empty(), vec!["x".to_string(), "y".to_string()])?.with_all_branches(true);
+    let (roots, report) = root_finder.find_roots_with_report()?;
+    assert_eq!(report.distinct_roots, 4);
+    assert!(roots.contains(&Value::Vector(vec![3., 1.])));
+    assert!(roots.contains(&Value::Vector(vec![1., 1.])));
+
+    Ok(())
+}
+
+#[test]
+fn context1() -> Result<(), MathLibError> {
+    let mut context = Context::default();
+    context.add_fun(&Function::new("f", parse("x^2")?, vec!["x"]));
+
+    assert_eq!(context.get_var("pi").unwrap().values.get(0), Some(&Value::Scalar(std::f64::consts::PI)));
+    assert!(context.contains_var("e"));
+    assert!(context.get_var("x").is_none());
+    assert!(!context.contains_var("x"));
+
+    assert_eq!(context.get_fun("f").unwrap().name, "f");
+    assert!(context.contains_fun("f"));
+    assert!(context.get_fun("g").is_none());
+    assert!(!context.contains_fun("pi"));
+
+    Ok(())
+}
+
+#[test]
+fn latex1() -> Result<(), MathLibError> {
+    let x = Variable::new("x", vec![Value::Scalar(3.)]);
+    let function = Function::new("f", parse("x^2")?, vec!["x"]);
+
+    let latex = Context::new(vec![x], vec![function]).as_latex();
+
+    assert_eq!(latex, "\\begin{aligned}x &= 3\\\\ \nf(x) &= x^{2}\\end{aligned}");
+
+    Ok(())
+}
+
+#[test]
+fn latex2() -> Result<(), MathLibError> {
+    let res = quick_eval("tau", &Context::default())?.to_vec();
+
+    assert_eq!(res[0], Value::Scalar(2.*std::f64::consts::PI));
+
+    let res = quick_eval("phi^2-phi", &Context::default())?.to_vec();
+
+    assert!((res[0].get_scalar().unwrap() - 1.).abs() < 1e-12);
+
+    assert_eq!(parse("tau*r+phi")?.as_latex(), "\\tau\\cdot r+\\phi");
+    assert_eq!(Value::Scalar(1.).as_latex_at_var("tau", false), "\\tau = 1");
+
+    Ok(())
+}
+
+#[test]
+fn latex3() -> Result<(), MathLibError> {
+    use crate::Step;
+
+    let eqs = vec![(parse("2x+5y+2z")?, parse("-38")?), (parse("3x-2y+4z")?, parse("17")?), (parse("-6x+y-7z")?, parse("-12")?)];
+    let results = quick_eval("eq(2x+5y+2z=-38, 3x-2y+4z=17, -6x+y-7z=-12, x, y, z)", &Context::empty())?.round(3);
+
+    let step = Step::Equ { eqs, results, variable_save: Some("s".to_string()) };
+
+    assert_eq!(step.as_latex(), "2x+5y+2z &= -38 \\\\ \n3x-2y+4z &= 17 \\\\ \n-6x+y-7z &= -12 \\\\ \n\\Rightarrow s &= \\begin{pmatrix}3\\\\ -8\\\\ -2\\end{pmatrix}");
+    assert_eq!(step.as_latex_inline(), "\\begin{cases}2x+5y+2z = -38 \\\\ 3x-2y+4z = 17 \\\\ -6x+y-7z = -12\\end{cases} \\Rightarrow s = \\begin{pmatrix}3\\\\ -8\\\\ -2\\end{pmatrix}");
+    assert!(step.as_latex_with_tag(1).ends_with("\\end{pmatrix} \\tag{1}\\label{eq:1} \\\\ \\\\ \n"));
+
+    Ok(())
+}
+
+#[test]
+fn latex4() -> Result<(), MathLibError> {
+    use crate::Step;
+
+    let value = Value::Scalar(1.23456789);
+
+    assert_eq!(value.as_latex_with_precision(2), "1.23");
+    assert_eq!(value.as_latex_with_precision(6), "1.234568");
+    assert_eq!(Value::Vector(vec![1.23456789, 2.]).as_latex_with_precision(2), "\\begin{pmatrix}1.23\\\\ 2\\end{pmatrix}");
+
+    let step = Step::Calc { term: parse("a")?, result: Values::from_vec(vec![value]), variable_save: Some("x".to_string()) };
+
+    assert_eq!(step.as_latex_with_precision(2), "x &= a = 1.23");
+    assert_eq!(step.as_latex_with_precision(6), "x &= a = 1.234568");
+
+    Ok(())
+}
+
+#[test]
+fn latex5() -> Result<(), MathLibError> {
+    use crate::basetypes::MatrixStyle;
+
+    let vector = Value::Vector(vec![1., 2.]);
+    let matrix = Value::Matrix(vec![vec![1., 2.], vec![3., 4.]]);
+
+    assert!(vector.as_latex().starts_with("\\begin{pmatrix}"));
+    assert!(matrix.as_latex().starts_with("\\begin{bmatrix}"));
+
+    let styles = [(MatrixStyle::Paren, "pmatrix"), (MatrixStyle::Bracket, "bmatrix"), (MatrixStyle::Brace, "Bmatrix"), (MatrixStyle::Bars, "vmatrix")];
+    for (style, environment) in styles {
+        assert_eq!(vector.as_latex_with_style(style), format!("\\begin{{{}}}1\\\\ 2\\end{{{}}}", environment, environment));
+        assert_eq!(matrix.as_latex_with_style(style), format!("\\begin{{{}}}1 & 2 \\\\3 & 4 \\\\\\end{{{}}}", environment, environment));
+    }
+    assert_eq!(Value::Scalar(2.).as_latex_with_style(MatrixStyle::Bars), "2");
+
+    Ok(())
+}
+
+#[test]
+fn latex6() -> Result<(), MathLibError> {
+    use crate::Step;
+
+    let fraction = parse("1/2")?;
+    let matrix = Value::Matrix(vec![vec![1., 2.], vec![3., -4.]]);
+
+    assert_eq!(fraction.as_mathml(), "<math><mfrac><mn>1</mn><mn>2</mn></mfrac></math>");
+    assert_eq!(matrix.as_mathml(), "<math><mrow><mo>[</mo><mtable><mtr><mtd><mn>1</mn></mtd><mtd><mn>2</mn></mtd></mtr><mtr><mtd><mn>3</mn></mtd><mtd><mrow><mo>-</mo><mn>4</mn></mrow></mtd></mtr></mtable><mo>]</mo></mrow></math>");
+
+    let step = Step::Calc { term: fraction, result: Values::from_vec(vec![Value::Scalar(0.5)]), variable_save: Some("x".to_string()) };
+
+    assert_eq!(step.as_mathml(), "<math><mrow><mi>x</mi><mo>=</mo><mfrac><mn>1</mn><mn>2</mn></mfrac><mo>=</mo><mn>0.5</mn></mrow></math>");
+
+    Ok(())
+}
+
 #[cfg(feature = "output")]
 #[test]
 fn output1() -> Result<(), MathLibError> {
@@ -521,9 +2758,73 @@ fn output2() -> Result<(), MathLibError> {
 
     let step = Step::Calc { term: parsed_expr, result: res, variable_save: Some("x".to_string()) };
 
-    let png = png_from_latex(step.as_latex_inline(), 200, "#FFFFFF")?;
+    let png = png_from_latex(step.as_latex_inline(), 200, "#FFFFFF", None)?;
 
     let _ = fs::write("./images/test.png", png);
 
     Ok(())
 }
+
+#[cfg(feature = "output")]
+#[test]
+fn output3() -> Result<(), MathLibError> {
+    use crate::{eval, export_history, ExportType, Step};
+
+    let parsed_expr = parse("1+1")?;
+    let res = eval(&parsed_expr, &Context::empty())?;
+
+    let step = Step::Calc { term: parsed_expr, result: res, variable_save: None };
+
+    let pdf = export_history(vec![step.clone()], ExportType::Pdf)?;
+    assert!(pdf.starts_with(b"%PDF"));
+
+    let res = export_history(vec![step], ExportType::PdfWith("./does_not_exist/pdflatex".to_string()));
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "output")]
+#[test]
+fn output4() -> Result<(), MathLibError> {
+    use crate::png_from_latex;
+    use resvg::tiny_skia::Pixmap;
+
+    // the colors don't have to be of the same type
+    let png = png_from_latex("x^2".to_string(), 100, "#000000".to_string(), Some("#FFFFFF"))?;
+
+    let pixmap = Pixmap::decode_png(&png).unwrap();
+    let corner = pixmap.pixel(0, 0).unwrap();
+    assert_eq!((corner.red(), corner.green(), corner.blue(), corner.alpha()), (255, 255, 255, 255));
+
+    assert!(png_from_latex("x^2".to_string(), 100, "#000000", Some("white")).is_err());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "output", unix))]
+#[test]
+fn output5() -> Result<(), MathLibError> {
+    use crate::{eval, export_history, ExportType, Step};
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let parsed_expr = parse("1+1")?;
+    let res = eval(&parsed_expr, &Context::empty())?;
+    let step = Step::Calc { term: parsed_expr, result: res, variable_save: None };
+
+    // a fake "compiler" that returns the document it has been given as the pdf, so no TeX
+    // installation is needed
+    let binary = std::env::temp_dir().join(format!("math_utils_lib_fake_latex_{}", std::process::id()));
+    fs::write(&binary, "#!/bin/sh\ncp export.tex export.pdf\n").map_err(|e| MathLibError::Other(e.to_string()))?;
+    fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).map_err(|e| MathLibError::Other(e.to_string()))?;
+
+    let document = export_history(vec![step.clone()], ExportType::PdfWith(binary.to_string_lossy().to_string()));
+    let _ = fs::remove_file(&binary);
+    let document = String::from_utf8(document?).map_err(|e| MathLibError::Other(e.to_string()))?;
+
+    assert_eq!(document.as_bytes(), export_history(vec![step], ExportType::Tex)?);
+    assert!(document.contains("\\usepackage{amsmath}"));
+    assert!(document.contains("\\usepackage[margin=1in]{geometry}"));
+
+    Ok(())
+}